@@ -34,10 +34,10 @@ fn fill_tiny_skia(blend_mode: tiny_skia::BlendMode, bencher: &mut Bencher) {
     };
 
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
-    pixmap.fill_path(&path1, &paint1, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path1, &paint1, FillRule::Winding, Transform::identity(), Clip::None);
 
     bencher.iter(|| {
-        pixmap.fill_path(&path2, &paint2, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path2, &paint2, FillRule::Winding, Transform::identity(), Clip::None);
     });
 }
 
@@ -24,7 +24,7 @@ fn do_clip_tiny_skia(aa: bool, bencher: &mut Bencher) {
         mask.fill_path(&clip_path, FillRule::EvenOdd, aa, Transform::identity());
 
         // Do not use fill_rect, because it is very slow by itself.
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Some(&mask));
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::Mask(&mask));
     });
 }
 
@@ -13,7 +13,7 @@ fn rect_tiny_skia(bencher: &mut Bencher) {
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
     });
 }
 
@@ -82,7 +82,7 @@ fn rect_aa_tiny_skia(bencher: &mut Bencher) {
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
     });
 }
 
@@ -154,7 +154,7 @@ fn rect_aa_ts_tiny_skia(bencher: &mut Bencher) {
 
     bencher.iter(|| {
 
-        pixmap.fill_rect(rect, &paint, Transform::from_row(1.8, 0.3, -0.7, 0.8, 12.0, 15.3), None);
+        pixmap.fill_rect(rect, &paint, Transform::from_row(1.8, 0.3, -0.7, 0.8, 12.0, 15.3), Clip::None);
     });
 }
 
@@ -289,7 +289,7 @@ fn path_aa_tiny_skia(bencher: &mut Bencher) {
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), Clip::None);
     });
 }
 
@@ -394,7 +394,7 @@ fn source_tiny_skia(bencher: &mut Bencher) {
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
     });
 }
 
@@ -482,7 +482,7 @@ fn opaque_tiny_skia(bencher: &mut Bencher) {
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
     });
 }
 
@@ -30,7 +30,7 @@ fn two_stops_linear_tiny_skia(
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
     });
 }
 
@@ -560,7 +560,7 @@ fn simple_radial_tiny_skia(bencher: &mut Bencher) {
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
     });
 }
 
@@ -715,7 +715,7 @@ fn two_point_radial_tiny_skia(bencher: &mut Bencher) {
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
     });
 }
 
@@ -223,7 +223,7 @@ fn draw_tiny_skia(aa: bool, bencher: &mut Bencher) {
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
 
     bencher.iter(|| {
-        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
     });
 }
 
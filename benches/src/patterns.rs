@@ -20,7 +20,7 @@ fn pattern_tiny_skia(
         let path = pb.finish().unwrap();
 
         let mut pixmap = Pixmap::new(20, 20).unwrap();
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
         pixmap
     }
 
@@ -46,7 +46,7 @@ fn pattern_tiny_skia(
     let path = pb.finish().unwrap();
 
     bencher.iter(|| {
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
     });
 }
 
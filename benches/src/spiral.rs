@@ -220,7 +220,7 @@ fn tiny_skia(bencher: &mut Bencher) {
 
     let mut pixmap = Pixmap::new(1000, 1000).unwrap();
     bencher.iter(|| {
-        pixmap.stroke_path(&path, &paint, &Stroke::default(), Transform::identity(), None);
+        pixmap.stroke_path(&path, &paint, &Stroke::default(), Transform::identity(), Clip::None);
     });
 }
 
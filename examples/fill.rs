@@ -35,14 +35,14 @@ fn main() {
         &paint1,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
     pixmap.fill_path(
         &path2,
         &paint2,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
     pixmap.save_png("image.png").unwrap();
 }
@@ -31,12 +31,12 @@ fn main() {
         let mut xf = Transform::identity();
         xf = xf.pre_translate(0.0, 240.0 * i as f32);
 
-        pixmap.stroke_path(&path, &paint, &stroke, xf, None);
+        pixmap.stroke_path(&path, &paint, &stroke, xf, Clip::None);
 
         // Move down 0.5 pixel so lines start in the middle of the pixel, not the edge
         xf = xf.pre_translate(500.0, 0.5);
 
-        pixmap.stroke_path(&path, &paint, &stroke, xf, None);
+        pixmap.stroke_path(&path, &paint, &stroke, xf, Clip::None);
     }
 
     pixmap.save_png("image.png").unwrap();
@@ -17,7 +17,7 @@ fn main() {
     for i in 0..20 {
         let mut stroke = Stroke::default();
         stroke.width = 2.0 - (i as f32 / 10.0);
-        pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+        pixmap.stroke_path(&path, &paint, &stroke, transform, Clip::None);
         transform = transform.pre_translate(0.0, 20.0);
     }
 
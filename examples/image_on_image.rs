@@ -16,7 +16,7 @@ fn main() {
         triangle.as_ref(),
         &paint,
         Transform::from_row(1.2, 0.5, 0.5, 1.2, 0.0, 0.0),
-        None,
+        Clip::None,
     );
 
     println!(
@@ -46,14 +46,14 @@ fn create_triangle() -> Pixmap {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     let path = PathBuilder::from_rect(Rect::from_ltrb(0.0, 0.0, 200.0, 200.0).unwrap());
     let stroke = Stroke::default();
     paint.set_color_rgba8(200, 0, 0, 220);
 
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None); // TODO: stroke_rect
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None); // TODO: stroke_rect
 
     pixmap
 }
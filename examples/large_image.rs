@@ -39,7 +39,7 @@ fn main() {
     paint.set_color_rgba8(90, 175, 100, 150);
     paint.anti_alias = true;
     let large_rect = Rect::from_xywh(500.0, 500.0, 19000.0, 19000.0).unwrap();
-    pixmap.fill_rect(large_rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(large_rect, &paint, Transform::identity(), Clip::None);
 
     paint.set_color_rgba8(50, 127, 150, 200);
     paint.anti_alias = true;
@@ -48,7 +48,7 @@ fn main() {
         &paint,
         FillRule::Winding,
         Transform::default(),
-        Some(&mask),
+        Clip::Mask(&mask),
     );
 
     paint.set_color_rgba8(220, 140, 75, 180);
@@ -58,14 +58,14 @@ fn main() {
         &paint,
         FillRule::Winding,
         Transform::default(),
-        None,
+        Clip::None,
     );
 
     paint.set_color_rgba8(255, 10, 15, 180);
     paint.anti_alias = true;
     let mut stroke = Stroke::default();
     stroke.width = 0.8; // hairline
-    pixmap.stroke_path(&path2, &paint, &stroke, Transform::default(), None);
+    pixmap.stroke_path(&path2, &paint, &stroke, Transform::default(), Clip::None);
 
     pixmap.save_png("image.png").unwrap();
 }
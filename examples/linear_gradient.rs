@@ -29,7 +29,7 @@ fn main() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
     pixmap.save_png("image.png").unwrap();
 }
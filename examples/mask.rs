@@ -24,7 +24,7 @@ fn main() {
         Rect::from_xywh(0.0, 0.0, 500.0, 500.0).unwrap(),
         &paint,
         Transform::identity(),
-        Some(&mask),
+        Clip::Mask(&mask),
     );
     pixmap.save_png("image.png").unwrap();
 }
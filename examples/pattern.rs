@@ -21,7 +21,7 @@ fn main() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
     pixmap.save_png("image.png").unwrap();
 }
@@ -44,7 +44,7 @@ fn crate_triangle() -> Pixmap {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
     pixmap
 }
@@ -25,6 +25,6 @@ fn main() {
     stroke.dash = StrokeDash::new(vec![20.0, 40.0], 0.0);
 
     let mut pixmap = Pixmap::new(500, 500).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
     pixmap.save_png("image.png").unwrap();
 }
@@ -0,0 +1,402 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Boolean operations on polygons.
+//!
+//! A straightforward implementation: edges are split at intersections,
+//! then edges that separate the inside from the outside are linked into contours.
+//! Quadratic complexity, therefore should be used only for moderately sized polygons.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::Point;
+
+/// Parametric epsilon used to snap intersections to edge ends.
+const T_EPSILON: f64 = 1e-6;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum BooleanOp {
+    Union,
+    Difference,
+}
+
+impl BooleanOp {
+    fn is_inside(self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Difference => a && !b,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    p0: Point,
+    p1: Point,
+    /// 0 for the first operand and 1 for the second one.
+    operand: usize,
+}
+
+/// Applies a boolean operation to two sets of closed polygons.
+///
+/// Both operands use the non-zero fill rule.
+///
+/// Returned contours are non-self-intersecting and closed implicitly.
+/// Outer contours are clockwise and holes are counter-clockwise.
+/// Contours can touch each other at vertices.
+///
+/// Returns `None` when edges cannot be linked into closed contours,
+/// which can happen only due to numerical errors.
+pub(crate) fn apply(a: &[Vec<Point>], b: &[Vec<Point>], op: BooleanOp) -> Option<Vec<Vec<Point>>> {
+    let mut edges = Vec::new();
+    for (operand, polygons) in [a, b].iter().enumerate() {
+        for polygon in polygons.iter() {
+            for i in 0..polygon.len() {
+                let p0 = polygon[i];
+                let p1 = polygon[(i + 1) % polygon.len()];
+                if p0 != p1 && p0.is_finite() && p1.is_finite() {
+                    edges.push(Edge { p0, p1, operand });
+                }
+            }
+        }
+    }
+
+    let mut edges = split_edges(&edges);
+    snap_edges(&mut edges)?;
+    let edges = classify_edges(&edges, op);
+    link_edges(&edges)
+}
+
+/// Splits edges at intersections with each other.
+///
+/// Intersection points are shared between edges exactly,
+/// so split edges can be linked by comparing points.
+fn split_edges(edges: &[Edge]) -> Vec<Edge> {
+    let mut splits: Vec<Vec<(f64, Point)>> = edges.iter().map(|_| Vec::new()).collect();
+    for i in 0..edges.len() {
+        for j in i + 1..edges.len() {
+            let (head, tail) = splits.split_at_mut(j);
+            intersect(edges[i], edges[j], &mut head[i], &mut tail[0]);
+        }
+    }
+
+    let mut result = Vec::with_capacity(edges.len());
+    for (edge, splits) in edges.iter().zip(splits.iter_mut()) {
+        splits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(core::cmp::Ordering::Equal));
+
+        let mut prev = edge.p0;
+        for (_, p) in splits.iter().chain(core::iter::once(&(1.0, edge.p1))) {
+            if *p != prev {
+                result.push(Edge {
+                    p0: prev,
+                    p1: *p,
+                    operand: edge.operand,
+                });
+                prev = *p;
+            }
+        }
+    }
+
+    result
+}
+
+/// Merges nearly coincident edge end points.
+///
+/// Several edges intersecting at the same location can produce slightly different
+/// intersection points, which results in tiny edges that cannot be classified reliably.
+fn snap_edges(edges: &mut Vec<Edge>) -> Option<()> {
+    let mut points: Vec<Point> = edges.iter().flat_map(|e| [e.p0, e.p1]).collect();
+    // Compare by value, so that signed zeros end up next to each other.
+    points.sort_by(|a, b| {
+        (a.x, a.y)
+            .partial_cmp(&(b.x, b.y))
+            .unwrap_or(core::cmp::Ordering::Equal)
+    });
+    points.dedup_by_key(|p| key(*p));
+
+    // Points are sorted by x, therefore so are the points they are snapped to.
+    let mut targets: Vec<Point> = Vec::new();
+    let mut snapped = BTreeMap::new();
+    for p in points {
+        let eps = (p.x.abs() + p.y.abs()) * 1e-6;
+        let target = targets
+            .iter()
+            .rev()
+            .take_while(|t| t.x >= p.x - eps)
+            .find(|t| is_nearly_equal(**t, p))
+            .copied();
+
+        let target = target.unwrap_or_else(|| {
+            targets.push(p);
+            p
+        });
+
+        snapped.insert(key(p), target);
+    }
+
+    for edge in edges.iter_mut() {
+        edge.p0 = *snapped.get(&key(edge.p0))?;
+        edge.p1 = *snapped.get(&key(edge.p1))?;
+    }
+
+    edges.retain(|edge| edge.p0 != edge.p1);
+    Some(())
+}
+
+/// Adds edges intersection points to their split lists.
+fn intersect(a: Edge, b: Edge, splits_a: &mut Vec<(f64, Point)>, splits_b: &mut Vec<(f64, Point)>) {
+    // Bounding boxes check.
+    let eps = |e: Edge| (e.p0.x.abs() + e.p0.y.abs() + e.p1.x.abs() + e.p1.y.abs()) * 1e-6;
+    let eps = eps(a).max(eps(b));
+    if a.p0.x.max(a.p1.x) + eps < b.p0.x.min(b.p1.x)
+        || b.p0.x.max(b.p1.x) + eps < a.p0.x.min(a.p1.x)
+        || a.p0.y.max(a.p1.y) + eps < b.p0.y.min(b.p1.y)
+        || b.p0.y.max(b.p1.y) + eps < a.p0.y.min(a.p1.y)
+    {
+        return;
+    }
+
+    // End points lying on the other edge split it exactly at them.
+    // This handles touching and collinear overlapping edges, where an intersection point
+    // computed with a limited precision would not be shared between the edges.
+    let mut touches = false;
+    for p in [b.p0, b.p1] {
+        if let Some(t) = point_on_edge(p, a, eps) {
+            splits_a.push((t, p));
+            touches = true;
+        }
+    }
+
+    for p in [a.p0, a.p1] {
+        if let Some(u) = point_on_edge(p, b, eps) {
+            splits_b.push((u, p));
+            touches = true;
+        }
+    }
+
+    // Two segments can intersect only once, unless they are collinear.
+    if touches {
+        return;
+    }
+
+    let (px, py) = (f64::from(a.p0.x), f64::from(a.p0.y));
+    let (rx, ry) = (f64::from(a.p1.x) - px, f64::from(a.p1.y) - py);
+    let (qx, qy) = (f64::from(b.p0.x), f64::from(b.p0.y));
+    let (sx, sy) = (f64::from(b.p1.x) - qx, f64::from(b.p1.y) - qy);
+    let (dx, dy) = (qx - px, qy - py);
+
+    let denom = rx * sy - ry * sx;
+    if denom == 0.0 {
+        return;
+    }
+
+    // Intersections at end points were handled above, so only proper crossings are left.
+    let t = (dx * sy - dy * sx) / denom;
+    let u = (dx * ry - dy * rx) / denom;
+    if !is_interior(t) || !is_interior(u) {
+        return;
+    }
+
+    let p = Point::from_xy((px + rx * t) as f32, (py + ry * t) as f32);
+    if p != a.p0 && p != a.p1 && p != b.p0 && p != b.p1 {
+        splits_a.push((t, p));
+        splits_b.push((u, p));
+    }
+}
+
+/// Checks that `t` is inside an edge, excluding its end points.
+fn is_interior(t: f64) -> bool {
+    t > T_EPSILON && t < 1.0 - T_EPSILON
+}
+
+/// Returns the parametric position of a point lying inside an edge within `eps` distance.
+fn point_on_edge(p: Point, edge: Edge, eps: f32) -> Option<f64> {
+    if p == edge.p0 || p == edge.p1 {
+        return None;
+    }
+
+    let (px, py) = (f64::from(edge.p0.x), f64::from(edge.p0.y));
+    let (rx, ry) = (f64::from(edge.p1.x) - px, f64::from(edge.p1.y) - py);
+    let (dx, dy) = (f64::from(p.x) - px, f64::from(p.y) - py);
+
+    let len2 = rx * rx + ry * ry;
+    let t = (dx * rx + dy * ry) / len2;
+    let cross = dx * ry - dy * rx;
+    let eps = f64::from(eps);
+    if is_interior(t) && cross * cross <= eps * eps * len2 {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+/// Returns edges that separate the inside from the outside.
+///
+/// Edges are oriented so the inside is on the right.
+fn classify_edges(edges: &[Edge], op: BooleanOp) -> Vec<(Point, Point)> {
+    let mut result = Vec::new();
+    for (i, edge) in edges.iter().enumerate() {
+        let d = edge.p1 - edge.p0;
+        let m = Point::from_xy((edge.p0.x + edge.p1.x) * 0.5, (edge.p0.y + edge.p1.y) * 0.5);
+
+        // Cast a ray across the edge's major axis, so it never runs along the edge.
+        let horizontal = d.y.abs() >= d.x.abs();
+        let along = |p: Point| if horizontal { p.y } else { p.x };
+        let across = |p: Point| if horizontal { p.x } else { p.y };
+
+        // Windings per operand behind and in front of the edge along the ray.
+        let mut behind = [0i32; 2];
+        let mut front = [0i32; 2];
+        let mut is_duplicate = false;
+        for (j, other) in edges.iter().enumerate() {
+            let (a0, a1) = (along(other.p0), along(other.p1));
+            if (a0 <= along(m)) == (a1 <= along(m)) {
+                continue;
+            }
+
+            let t = (along(m) - a0) / (a1 - a0);
+            let c = across(other.p0) + (across(other.p1) - across(other.p0)) * t;
+            let dir = if a1 > a0 { 1 } else { -1 };
+            // Split edges share end points exactly, therefore coincident edges
+            // have the same end points.
+            let is_coincident = (other.p0 == edge.p0 && other.p1 == edge.p1)
+                || (other.p0 == edge.p1 && other.p1 == edge.p0);
+            if j == i || is_coincident {
+                // The edge itself or a coincident one.
+                behind[other.operand] += dir;
+                is_duplicate |= j < i;
+            } else if c > across(m) {
+                behind[other.operand] += dir;
+                front[other.operand] += dir;
+            }
+        }
+
+        let inside_behind = op.is_inside(behind[0] != 0, behind[1] != 0);
+        let inside_front = op.is_inside(front[0] != 0, front[1] != 0);
+        if inside_behind == inside_front || is_duplicate {
+            continue;
+        }
+
+        // A direction towards the inside.
+        let v = match (horizontal, inside_front) {
+            (true, true) => Point::from_xy(1.0, 0.0),
+            (true, false) => Point::from_xy(-1.0, 0.0),
+            (false, true) => Point::from_xy(0.0, 1.0),
+            (false, false) => Point::from_xy(0.0, -1.0),
+        };
+
+        if d.cross(v) > 0.0 {
+            result.push((edge.p0, edge.p1));
+        } else {
+            result.push((edge.p1, edge.p0));
+        }
+    }
+
+    result
+}
+
+/// Links oriented edges into closed contours.
+///
+/// Returns `None` when an edge doesn't lead back to the contour start.
+fn link_edges(edges: &[(Point, Point)]) -> Option<Vec<Vec<Point>>> {
+    let mut outgoing: BTreeMap<(u32, u32), Vec<usize>> = BTreeMap::new();
+    for (i, edge) in edges.iter().enumerate() {
+        outgoing.entry(key(edge.0)).or_default().push(i);
+    }
+
+    let mut used = alloc::vec![false; edges.len()];
+    let mut contours = Vec::new();
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+
+        let mut contour = Vec::new();
+        let mut idx = start;
+        loop {
+            used[idx] = true;
+            push_point(&mut contour, edges[idx].0);
+
+            let end = edges[idx].1;
+            if end == edges[start].0 {
+                break;
+            }
+
+            idx = *outgoing
+                .get(&key(end))
+                .and_then(|list| list.iter().find(|i| !used[**i]))?;
+        }
+
+        // Points around the contour start can be collinear as well.
+        while contour.len() > 2
+            && is_collinear(
+                contour[contour.len() - 2],
+                contour[contour.len() - 1],
+                contour[0],
+            )
+        {
+            contour.pop();
+        }
+
+        while contour.len() > 2 && is_collinear(contour[contour.len() - 1], contour[0], contour[1])
+        {
+            contour.remove(0);
+        }
+
+        if contour.len() > 2 {
+            contours.push(contour);
+        }
+    }
+
+    Some(contours)
+}
+
+/// Appends a point, while removing collinear points.
+fn push_point(contour: &mut Vec<Point>, p: Point) {
+    if contour.len() >= 2 && is_collinear(contour[contour.len() - 2], contour[contour.len() - 1], p)
+    {
+        contour.pop();
+    }
+
+    contour.push(p);
+}
+
+/// Returns a map key for a point.
+fn key(p: Point) -> (u32, u32) {
+    // Adding a positive zero turns a negative zero into a positive one,
+    // so points equal by value always have the same key.
+    ((p.x + 0.0).to_bits(), (p.y + 0.0).to_bits())
+}
+
+/// Checks that points are equal within the precision used to snap edges.
+fn is_nearly_equal(p0: Point, p1: Point) -> bool {
+    let eps = (p0.x.abs() + p0.y.abs()) * 1e-6;
+    (p0.x - p1.x).abs() <= eps && (p0.y - p1.y).abs() <= eps
+}
+
+/// Checks that `p1` lies on a straight line between `p0` and `p2`.
+fn is_collinear(p0: Point, p1: Point, p2: Point) -> bool {
+    let d0 = p1 - p0;
+    let d1 = p2 - p1;
+    d0.cross(d1).abs() <= 1e-6 * d0.length() * d1.length() && d0.dot(d1) > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_open_chain() {
+        let p0 = Point::from_xy(0.0, 0.0);
+        let p1 = Point::from_xy(10.0, 0.0);
+        let p2 = Point::from_xy(10.0, 10.0);
+        assert!(link_edges(&[(p0, p1), (p1, p2)]).is_none());
+        assert_eq!(
+            link_edges(&[(p0, p1), (p1, p2), (p2, p0)]).unwrap().len(),
+            1
+        );
+    }
+}
@@ -41,6 +41,8 @@ pub struct StrokeDash {
     interval_len: NonZeroPositiveF32,
     first_len: f32, // TODO: PositiveF32
     first_index: usize,
+    phase_reset: bool,
+    fit_to_length: bool,
 }
 
 impl StrokeDash {
@@ -73,8 +75,31 @@ impl StrokeDash {
             interval_len,
             first_len,
             first_index,
+            phase_reset: true,
+            fit_to_length: false,
         })
     }
+
+    /// Sets whether the dash pattern starts anew on each contour. On by default.
+    ///
+    /// When disabled, the pattern continues from where the previous contour ended,
+    /// as if all contours were a single one.
+    pub fn set_phase_reset(&mut self, flag: bool) {
+        self.phase_reset = flag;
+    }
+
+    /// Sets whether the dash pattern should be fitted to the path. Off by default.
+    ///
+    /// When enabled, the path is split at corners and the pattern is slightly
+    /// stretched or compressed on each piece to fit a whole number of intervals.
+    /// The first dash of the pattern is centered at each corner and at the start of
+    /// a closed contour, while open contours start and end with a whole first dash.
+    /// Which makes dashes meet nicely at the corners of rectangles and wrap around circles.
+    ///
+    /// The dash offset and the phase reset flag are ignored in this mode.
+    pub fn set_fit_to_length(&mut self, flag: bool) {
+        self.fit_to_length = flag;
+    }
 }
 
 #[cfg(test)]
@@ -176,18 +201,10 @@ fn dash_impl(src: &Path, dash: &StrokeDash, res_scale: f32) -> Option<Path> {
     // We also doesn't support the `SpecialLineRec` case.
     // I have no idea what the point in it.
 
-    fn is_even(x: usize) -> bool {
-        x % 2 == 0
-    }
-
     let mut pb = PathBuilder::new();
     let mut dash_count = 0.0;
+    let mut phase = (dash.first_len, dash.first_index);
     for contour in ContourMeasureIter::new(src, res_scale) {
-        let mut skip_first_segment = contour.is_closed;
-        let mut added_segment = false;
-        let length = contour.length;
-        let mut index = dash.first_index;
-
         // Since the path length / dash length ratio may be arbitrarily large, we can exert
         // significant memory pressure while attempting to build the filtered path. To avoid this,
         // we simply give up dashing beyond a certain threshold.
@@ -197,47 +214,181 @@ fn dash_impl(src: &Path, dash: &StrokeDash, res_scale: f32) -> Option<Path> {
         // segments seems reasonable: at 2 verbs per segment * 9 bytes per verb, this caps the
         // maximum dash memory overhead at roughly 17MB per path.
         const MAX_DASH_COUNT: usize = 1000000;
-        dash_count += length * (dash.array.len() >> 1) as f32 / dash.interval_len.get();
+        dash_count += contour.length * (dash.array.len() >> 1) as f32 / dash.interval_len.get();
         if dash_count > MAX_DASH_COUNT as f32 {
             return None;
         }
 
-        // Using double precision to avoid looping indefinitely due to single precision rounding
-        // (for extreme path_length/dash_length ratios). See test_infinite_dash() unittest.
-        let mut distance = 0.0;
-        let mut d_len = dash.first_len;
-
-        while distance < length {
-            debug_assert!(d_len >= 0.0);
-            added_segment = false;
-            if is_even(index) && !skip_first_segment {
-                added_segment = true;
-                contour.push_segment(distance, distance + d_len, true, &mut pb);
+        if dash.fit_to_length {
+            fit_dash_contour(&contour, dash, &mut pb);
+        } else {
+            if dash.phase_reset {
+                phase = (dash.first_len, dash.first_index);
             }
 
-            distance += d_len;
+            phase = dash_contour(&contour, dash, phase, &mut pb);
+        }
+    }
 
-            // clear this so we only respect it the first time around
-            skip_first_segment = false;
+    pb.finish()
+}
 
-            // wrap around our intervals array if necessary
-            index += 1;
-            debug_assert!(index <= dash.array.len());
-            if index == dash.array.len() {
-                index = 0;
-            }
+fn is_even(x: usize) -> bool {
+    x % 2 == 0
+}
 
-            // fetch our next d_len
-            d_len = dash.array[index];
+/// Dashes a contour starting from the specified interval length and index.
+///
+/// Returns the pattern position at the end of the contour.
+fn dash_contour(
+    contour: &ContourMeasure,
+    dash: &StrokeDash,
+    (first_len, first_index): (f32, usize),
+    pb: &mut PathBuilder,
+) -> (f32, usize) {
+    let mut skip_first_segment = contour.is_closed;
+    let mut added_segment = false;
+    let length = contour.length;
+    let mut index = first_index;
+
+    // Using double precision to avoid looping indefinitely due to single precision rounding
+    // (for extreme path_length/dash_length ratios). See test_infinite_dash() unittest.
+    let mut distance = 0.0;
+    let mut d_len = first_len;
+
+    while distance < length {
+        debug_assert!(d_len >= 0.0);
+        added_segment = false;
+        if is_even(index) && !skip_first_segment {
+            added_segment = true;
+            contour.push_segment(distance, distance + d_len, true, pb);
         }
 
-        // extend if we ended on a segment and we need to join up with the (skipped) initial segment
-        if contour.is_closed && is_even(dash.first_index) && dash.first_len >= 0.0 {
-            contour.push_segment(0.0, dash.first_len, !added_segment, &mut pb);
+        distance += d_len;
+
+        // clear this so we only respect it the first time around
+        skip_first_segment = false;
+
+        // wrap around our intervals array if necessary
+        index += 1;
+        debug_assert!(index <= dash.array.len());
+        if index == dash.array.len() {
+            index = 0;
         }
+
+        // fetch our next d_len
+        d_len = dash.array[index];
     }
 
-    pb.finish()
+    // extend if we ended on a segment and we need to join up with the (skipped) initial segment
+    if contour.is_closed && is_even(first_index) && first_len >= 0.0 {
+        contour.push_segment(0.0, first_len, !added_segment, pb);
+    }
+
+    // A zero-length dash at the very end of an open contour should still produce a dot.
+    if !contour.is_closed && distance == length && is_even(index) && d_len == 0.0 {
+        contour.push_segment(length, length, true, pb);
+    }
+
+    let overshoot = distance - length;
+    if overshoot > 0.0 {
+        // The previous interval continues on the next contour.
+        let prev_index = if index == 0 {
+            dash.array.len() - 1
+        } else {
+            index - 1
+        };
+        (overshoot, prev_index)
+    } else {
+        (d_len, index)
+    }
+}
+
+/// Dashes a contour piece by piece, scaling the pattern to fit each piece between corners.
+fn fit_dash_contour(contour: &ContourMeasure, dash: &StrokeDash, pb: &mut PathBuilder) {
+    let mut bounds = contour.corners();
+    bounds.insert(0, 0.0);
+    bounds.push(contour.length);
+    let pieces_count = bounds.len() - 1;
+
+    let interval_len = dash.interval_len.get();
+    let half_dash = dash.array[0] * 0.5;
+
+    // A dash crossing the start of a closed contour. Added at the end to be joined.
+    let mut first_dash = None;
+    // Whether the last added dash continues on the next piece.
+    let mut connected = false;
+    for (i, range) in bounds.windows(2).enumerate() {
+        let (start_d, stop_d) = (range[0], range[1]);
+        let len = stop_d - start_d;
+        if !(len > 0.0) {
+            continue;
+        }
+
+        // Pattern positions at the piece ends.
+        let start = if !contour.is_closed && i == 0 {
+            0.0
+        } else {
+            half_dash
+        };
+        let stop_offset = if !contour.is_closed && i + 1 == pieces_count {
+            dash.array[0]
+        } else {
+            half_dash
+        };
+
+        let base = stop_offset - start;
+        let min_count = if base > 0.0 { 0.0 } else { 1.0 };
+        let count = ((len - base) / interval_len).round().max(min_count);
+        let stop = count * interval_len + stop_offset;
+        let scale = len / (stop - start);
+        let to_distance = |pos: f32| {
+            if pos >= stop {
+                stop_d
+            } else {
+                start_d + (pos - start) * scale
+            }
+        };
+
+        let is_last_open = !contour.is_closed && i + 1 == pieces_count;
+        let join = connected;
+        connected = false;
+        for period in 0..=count as usize {
+            let mut on_start = period as f32 * interval_len;
+            for (index, d_len) in dash.array.iter().copied().enumerate() {
+                let on_end = on_start + d_len;
+                if is_even(index) {
+                    if d_len == 0.0 {
+                        // Zero-length dashes are dots with round and square caps.
+                        // The one at the piece end belongs to the next piece.
+                        if start <= on_start
+                            && (on_start < stop || (on_start == stop && is_last_open))
+                        {
+                            let d = to_distance(on_start);
+                            contour.push_segment(d, d, true, pb);
+                            connected = false;
+                        }
+                    } else if on_end > start && on_start < stop {
+                        let d0 = to_distance(on_start.max(start));
+                        let d1 = to_distance(on_end.min(stop));
+                        if contour.is_closed && i == 0 && on_start < start {
+                            first_dash = Some((d0, d1));
+                        } else {
+                            contour.push_segment(d0, d1, !(join && on_start < start), pb);
+                        }
+
+                        connected = on_end > stop;
+                    }
+                }
+
+                on_start = on_end;
+            }
+        }
+    }
+
+    if let Some((d0, d1)) = first_dash {
+        contour.push_segment(d0, d1, !connected, pb);
+    }
 }
 
 const MAX_T_VALUE: u32 = 0x3FFFFFFF;
@@ -414,6 +565,41 @@ struct ContourMeasure {
 }
 
 impl ContourMeasure {
+    /// Returns distances to vertices where the contour direction changes abruptly.
+    fn corners(&self) -> Vec<f32> {
+        let mut corners = Vec::new();
+        for pair in self.segments.windows(2) {
+            let (prev, next) = (pair[0], pair[1]);
+            if prev.point_index == next.point_index {
+                continue;
+            }
+
+            let mut prev_tangent = Point::zero();
+            let mut next_tangent = Point::zero();
+            compute_pos_tan(
+                &self.points[prev.point_index..],
+                prev.kind,
+                NormalizedF32::ONE,
+                None,
+                Some(&mut prev_tangent),
+            );
+            compute_pos_tan(
+                &self.points[next.point_index..],
+                next.kind,
+                NormalizedF32::ZERO,
+                None,
+                Some(&mut next_tangent),
+            );
+
+            // Tolerate small numerical errors at smooth vertices.
+            if prev_tangent.dot(next_tangent) < 0.9999 {
+                corners.push(prev.distance);
+            }
+        }
+
+        corners
+    }
+
     fn push_segment(
         &self,
         mut start_d: f32,
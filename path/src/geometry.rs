@@ -0,0 +1,626 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Geometry queries on individual curves.
+
+use alloc::vec::Vec;
+
+use arrayref::array_mut_ref;
+
+use crate::f32x2_t::f32x2;
+use crate::path::{compute_cubic_extremas, compute_quad_extremas, PathSegment, PathSegmentsIter};
+use crate::path64::{cubic64, quad64};
+use crate::path_geometry::{CubicCoeff, QuadCoeff};
+use crate::{Path, Point, Rect};
+
+/// The number of samples used to find the starting point for the nearest point search on cubics.
+const NEAREST_POINT_SAMPLES: usize = 16;
+
+/// The maximum number of Newton-Raphson iterations for the nearest point search.
+const NEAREST_POINT_ITERATIONS: usize = 8;
+
+/// The maximum subdivision depth for curve-curve intersections.
+const MAX_SUBDIVISION_DEPTH: usize = 32;
+
+/// The maximum number of curve pairs checked for intersections.
+///
+/// Prevents exponential blowup on overlapping curves.
+const MAX_SUBDIVISION_PAIRS: usize = 1 << 14;
+
+/// Parameters closer than this are treated as a single intersection.
+const T_EPSILON: f32 = 1e-4;
+
+/// A single path segment with absolute points.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Curve {
+    /// A straight line.
+    Line(Point, Point),
+    /// A quadratic Bezier curve.
+    Quad(Point, Point, Point),
+    /// A cubic Bezier curve.
+    Cubic(Point, Point, Point, Point),
+}
+
+/// The nearest point on a curve.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct NearestPoint {
+    /// The curve parameter in the 0..=1 range.
+    pub t: f32,
+    /// The point on the curve.
+    pub point: Point,
+    /// The distance to the point.
+    pub distance: f32,
+}
+
+/// An intersection of two curves.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CurveIntersection {
+    /// The parameter on the first curve.
+    pub t1: f32,
+    /// The parameter on the second curve.
+    pub t2: f32,
+    /// The intersection point.
+    pub point: Point,
+}
+
+impl Curve {
+    /// Returns the start point.
+    pub fn start(&self) -> Point {
+        match *self {
+            Curve::Line(p0, _) | Curve::Quad(p0, _, _) | Curve::Cubic(p0, _, _, _) => p0,
+        }
+    }
+
+    /// Returns the end point.
+    pub fn end(&self) -> Point {
+        match *self {
+            Curve::Line(_, p1) | Curve::Quad(_, _, p1) | Curve::Cubic(_, _, _, p1) => p1,
+        }
+    }
+
+    /// Returns a point at `t`.
+    ///
+    /// `t` is clamped to the 0..=1 range.
+    pub fn eval(&self, t: f32) -> Point {
+        let t = clamp_t(t);
+        match *self {
+            Curve::Line(p0, p1) => lerp(p0, p1, t),
+            Curve::Quad(p0, p1, p2) => {
+                let coeff = QuadCoeff::from_points(&[p0, p1, p2]);
+                Point::from_f32x2(coeff.eval(f32x2::splat(t)))
+            }
+            Curve::Cubic(p0, p1, p2, p3) => {
+                let coeff = CubicCoeff::from_points(&[p0, p1, p2, p3]);
+                Point::from_f32x2(coeff.eval(f32x2::splat(t)))
+            }
+        }
+    }
+
+    /// Splits the curve at `t`.
+    ///
+    /// `t` is clamped to the 0..=1 range.
+    pub fn split(&self, t: f32) -> (Curve, Curve) {
+        let t = clamp_t(t);
+        match *self {
+            Curve::Line(p0, p1) => {
+                let m = lerp(p0, p1, t);
+                (Curve::Line(p0, m), Curve::Line(m, p1))
+            }
+            Curve::Quad(p0, p1, p2) => {
+                let p01 = lerp(p0, p1, t);
+                let p12 = lerp(p1, p2, t);
+                let m = lerp(p01, p12, t);
+                (Curve::Quad(p0, p01, m), Curve::Quad(m, p12, p2))
+            }
+            Curve::Cubic(p0, p1, p2, p3) => {
+                let p01 = lerp(p0, p1, t);
+                let p12 = lerp(p1, p2, t);
+                let p23 = lerp(p2, p3, t);
+                let p012 = lerp(p01, p12, t);
+                let p123 = lerp(p12, p23, t);
+                let m = lerp(p012, p123, t);
+                (
+                    Curve::Cubic(p0, p01, p012, m),
+                    Curve::Cubic(m, p123, p23, p3),
+                )
+            }
+        }
+    }
+
+    /// Returns the curve's tight bounds.
+    ///
+    /// Unlike the control points bounds, includes only points that lie on the curve.
+    ///
+    /// Returns `None` when any point is not finite.
+    pub fn bounds(&self) -> Option<Rect> {
+        let mut extremas = [Point::zero(); 6];
+        extremas[0] = self.start();
+        let count = match *self {
+            Curve::Line(_, p1) => {
+                extremas[1] = p1;
+                1
+            }
+            Curve::Quad(p0, p1, p2) => {
+                compute_quad_extremas(p0, p1, p2, array_mut_ref![extremas, 1, 5])
+            }
+            Curve::Cubic(p0, p1, p2, p3) => {
+                compute_cubic_extremas(p0, p1, p2, p3, array_mut_ref![extremas, 1, 5])
+            }
+        };
+
+        Rect::from_points(&extremas[..count + 1])
+    }
+
+    /// Returns the point on the curve nearest to `p`.
+    pub fn nearest_point(&self, p: Point) -> NearestPoint {
+        let mut nearest = NearestPoint {
+            t: 0.0,
+            point: self.start(),
+            distance: self.start().distance(p),
+        };
+
+        let mut check = |t: f32| {
+            let point = self.eval(t);
+            let distance = point.distance(p);
+            if distance < nearest.distance {
+                nearest = NearestPoint { t, point, distance };
+            }
+        };
+
+        check(1.0);
+
+        match *self {
+            Curve::Line(p0, p1) => {
+                let d = p1 - p0;
+                let len_sqd = d.dot(d);
+                if len_sqd > 0.0 {
+                    check((p - p0).dot(d) / len_sqd);
+                }
+            }
+            Curve::Quad(p0, p1, p2) => {
+                // Solve (B(t) - p) * B'(t) = 0, which is a cubic equation.
+                let a = to_f64(p0 - p1.scaled(2.0) + p2);
+                let b = to_f64((p1 - p0).scaled(2.0));
+                let c = to_f64(p0 - p);
+                let dot = |u: (f64, f64), v: (f64, f64)| u.0 * v.0 + u.1 * v.1;
+
+                let mut roots = [0.0; 3];
+                let count = cubic64::roots_valid_t(
+                    2.0 * dot(a, a),
+                    3.0 * dot(a, b),
+                    dot(b, b) + 2.0 * dot(a, c),
+                    dot(b, c),
+                    &mut roots,
+                );
+
+                for t in &roots[..count] {
+                    check(*t as f32);
+                }
+            }
+            Curve::Cubic(..) => {
+                // The exact solution requires solving a quintic equation.
+                // Instead, refine the best samples using Newton's method.
+                let mut prev_dist = f32::MAX;
+                let mut dist = self.start().distance_to_sqd(p);
+                for i in 0..=NEAREST_POINT_SAMPLES {
+                    let next_dist = if i < NEAREST_POINT_SAMPLES {
+                        self.eval((i + 1) as f32 / NEAREST_POINT_SAMPLES as f32)
+                            .distance_to_sqd(p)
+                    } else {
+                        f32::MAX
+                    };
+
+                    // Refine only local minimums.
+                    if dist <= prev_dist && dist <= next_dist {
+                        check(self.refine_nearest_t(p, i as f32 / NEAREST_POINT_SAMPLES as f32));
+                    }
+
+                    prev_dist = dist;
+                    dist = next_dist;
+                }
+            }
+        }
+
+        nearest
+    }
+
+    /// Returns intersections with another curve.
+    ///
+    /// Intersections are sorted by `t1`.
+    ///
+    /// Intersections involving lines are computed analytically.
+    /// Intersections of curves are found by subdivision, therefore tangential contacts
+    /// and overlapping curves are not guaranteed to be found precisely.
+    pub fn intersections(&self, other: &Curve) -> Vec<CurveIntersection> {
+        let mut result = Vec::new();
+        match (*self, *other) {
+            (Curve::Line(a0, a1), Curve::Line(b0, b1)) => {
+                line_line_intersections(a0, a1, b0, b1, &mut result);
+            }
+            (Curve::Line(p0, p1), _) => {
+                line_curve_intersections(p0, p1, other, false, &mut result);
+            }
+            (_, Curve::Line(p0, p1)) => {
+                line_curve_intersections(p0, p1, self, true, &mut result);
+            }
+            _ => curve_curve_intersections(self, other, &mut result),
+        }
+
+        result.sort_by(|a, b| {
+            a.t1.partial_cmp(&b.t1)
+                .unwrap_or(core::cmp::Ordering::Equal)
+        });
+        result
+    }
+
+    fn refine_nearest_t(&self, p: Point, mut t: f32) -> f32 {
+        let (p0, p1, p2, p3) = match *self {
+            Curve::Cubic(p0, p1, p2, p3) => (p0, p1, p2, p3),
+            _ => return t,
+        };
+
+        // First and second derivatives' control points.
+        let d1 = [
+            (p1 - p0).scaled(3.0),
+            (p2 - p1).scaled(3.0),
+            (p3 - p2).scaled(3.0),
+        ];
+        let d2 = [(d1[1] - d1[0]).scaled(2.0), (d1[2] - d1[1]).scaled(2.0)];
+
+        for _ in 0..NEAREST_POINT_ITERATIONS {
+            let mt = 1.0 - t;
+            let diff = self.eval(t) - p;
+            let q1 = d1[0].scaled(mt * mt) + d1[1].scaled(2.0 * t * mt) + d1[2].scaled(t * t);
+            let q2 = lerp(d2[0], d2[1], t);
+
+            let denominator = q1.dot(q1) + diff.dot(q2);
+            if denominator == 0.0 {
+                break;
+            }
+
+            let new_t = clamp_t(t - diff.dot(q1) / denominator);
+            if (new_t - t).abs() < f32::EPSILON {
+                t = new_t;
+                break;
+            }
+
+            t = new_t;
+        }
+
+        t
+    }
+
+    fn control_points_bounds(&self) -> (Point, Point) {
+        let mut min = self.start();
+        let mut max = min;
+        let mut add = |p: Point| {
+            min = Point::from_xy(min.x.min(p.x), min.y.min(p.y));
+            max = Point::from_xy(max.x.max(p.x), max.y.max(p.y));
+        };
+
+        match *self {
+            Curve::Line(_, p1) => add(p1),
+            Curve::Quad(_, p1, p2) => {
+                add(p1);
+                add(p2);
+            }
+            Curve::Cubic(_, p1, p2, p3) => {
+                add(p1);
+                add(p2);
+                add(p3);
+            }
+        }
+
+        (min, max)
+    }
+
+    /// Returns the maximum distance from the control points to the chord.
+    fn flatness(&self) -> f32 {
+        let (p0, p3) = (self.start(), self.end());
+        let distance = |p: Point| {
+            let chord = p3 - p0;
+            let len = chord.length();
+            if len > 0.0 {
+                chord.cross(p - p0).abs() / len
+            } else {
+                p.distance(p0)
+            }
+        };
+
+        match *self {
+            Curve::Line(..) => 0.0,
+            Curve::Quad(_, p1, _) => distance(p1),
+            Curve::Cubic(_, p1, p2, _) => distance(p1).max(distance(p2)),
+        }
+    }
+}
+
+impl Path {
+    /// Returns an iterator over path's curves.
+    ///
+    /// Unlike [`Path::segments`], curves have absolute start points
+    /// and closed contours produce a closing line, unless it has a zero length.
+    pub fn curves(&self) -> PathCurvesIter<'_> {
+        PathCurvesIter {
+            segments: self.segments(),
+            last_move_to: Point::zero(),
+            last_point: Point::zero(),
+        }
+    }
+
+    /// Returns the point on the path nearest to `p`.
+    ///
+    /// Also returns the index of the curve the point is on, as returned by [`Path::curves`].
+    ///
+    /// Returns `None` when the path has no curves.
+    pub fn nearest_point(&self, p: Point) -> Option<(usize, NearestPoint)> {
+        let mut result: Option<(usize, NearestPoint)> = None;
+        for (i, curve) in self.curves().enumerate() {
+            let nearest = curve.nearest_point(p);
+            match result {
+                Some((_, prev)) if prev.distance <= nearest.distance => {}
+                _ => result = Some((i, nearest)),
+            }
+        }
+
+        result
+    }
+}
+
+/// An iterator over path curves.
+///
+/// Created by [`Path::curves`].
+#[allow(missing_debug_implementations)]
+#[derive(Clone)]
+pub struct PathCurvesIter<'a> {
+    segments: PathSegmentsIter<'a>,
+    last_move_to: Point,
+    last_point: Point,
+}
+
+impl Iterator for PathCurvesIter<'_> {
+    type Item = Curve;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let p0 = self.last_point;
+            let curve = match self.segments.next()? {
+                PathSegment::MoveTo(p) => {
+                    self.last_move_to = p;
+                    self.last_point = p;
+                    continue;
+                }
+                PathSegment::LineTo(p) => Curve::Line(p0, p),
+                PathSegment::QuadTo(p1, p) => Curve::Quad(p0, p1, p),
+                PathSegment::CubicTo(p1, p2, p) => Curve::Cubic(p0, p1, p2, p),
+                PathSegment::Close => {
+                    self.last_point = self.last_move_to;
+                    if p0 == self.last_move_to {
+                        continue;
+                    }
+
+                    return Some(Curve::Line(p0, self.last_move_to));
+                }
+            };
+
+            self.last_point = curve.end();
+            return Some(curve);
+        }
+    }
+}
+
+fn line_line_intersections(
+    a0: Point,
+    a1: Point,
+    b0: Point,
+    b1: Point,
+    result: &mut Vec<CurveIntersection>,
+) {
+    let a = a1 - a0;
+    let b = b1 - b0;
+    let denom = a.cross(b);
+    if denom != 0.0 {
+        let d = b0 - a0;
+        let t1 = d.cross(b) / denom;
+        let t2 = d.cross(a) / denom;
+        if is_valid_t(t1) && is_valid_t(t2) {
+            let t1 = clamp_t(t1);
+            result.push(CurveIntersection {
+                t1,
+                t2: clamp_t(t2),
+                point: lerp(a0, a1, t1),
+            });
+        }
+
+        return;
+    }
+
+    // Parallel lines. Only collinear ones can overlap,
+    // in which case the overlap ends are returned.
+    if (b0 - a0).cross(a) != 0.0 {
+        return;
+    }
+
+    let project = |p: Point, l0: Point, l1: Point| {
+        let d = l1 - l0;
+        let len_sqd = d.dot(d);
+        if len_sqd > 0.0 {
+            Some((p - l0).dot(d) / len_sqd)
+        } else {
+            None
+        }
+    };
+
+    let mut push = |t1: f32, t2: f32, point: Point| {
+        let is_duplicate = result
+            .iter()
+            .any(|r| (r.t1 - t1).abs() < T_EPSILON && (r.t2 - t2).abs() < T_EPSILON);
+        if !is_duplicate {
+            result.push(CurveIntersection { t1, t2, point });
+        }
+    };
+
+    for (t2, p) in [(0.0, b0), (1.0, b1)] {
+        if let Some(t1) = project(p, a0, a1).filter(|t| is_valid_t(*t)) {
+            push(clamp_t(t1), t2, p);
+        }
+    }
+
+    for (t1, p) in [(0.0, a0), (1.0, a1)] {
+        if let Some(t2) = project(p, b0, b1).filter(|t| is_valid_t(*t)) {
+            push(t1, clamp_t(t2), p);
+        }
+    }
+}
+
+/// Finds intersections of a line with a quad or a cubic.
+///
+/// The curve is moved into the line's coordinate system,
+/// where intersections are the roots of its signed distance to the line.
+fn line_curve_intersections(
+    p0: Point,
+    p1: Point,
+    curve: &Curve,
+    swap: bool,
+    result: &mut Vec<CurveIntersection>,
+) {
+    let line = p1 - p0;
+    let len_sqd = f64::from(line.x) * f64::from(line.x) + f64::from(line.y) * f64::from(line.y);
+    if len_sqd == 0.0 {
+        return;
+    }
+
+    let distance = |p: Point| {
+        let (dx, dy) = to_f64(p - p0);
+        f64::from(line.x) * dy - f64::from(line.y) * dx
+    };
+
+    let mut roots = [0.0; 3];
+    let count = match *curve {
+        Curve::Line(..) => return,
+        Curve::Quad(c0, c1, c2) => {
+            let (d0, d1, d2) = (distance(c0), distance(c1), distance(c2));
+            quad64::roots_valid_t(d0 - 2.0 * d1 + d2, 2.0 * (d1 - d0), d0, &mut roots)
+        }
+        Curve::Cubic(c0, c1, c2, c3) => {
+            let src = [
+                distance(c0),
+                0.0,
+                distance(c1),
+                0.0,
+                distance(c2),
+                0.0,
+                distance(c3),
+                0.0,
+            ];
+            let (a, b, c, d) = cubic64::coefficients(&src);
+            cubic64::roots_valid_t(a, b, c, d, &mut roots)
+        }
+    };
+
+    for t in &roots[..count] {
+        let curve_t = *t as f32;
+        let point = curve.eval(curve_t);
+        let (dx, dy) = to_f64(point - p0);
+        let line_t = ((dx * f64::from(line.x) + dy * f64::from(line.y)) / len_sqd) as f32;
+        if !is_valid_t(line_t) {
+            continue;
+        }
+
+        let line_t = clamp_t(line_t);
+        let (t1, t2) = if swap {
+            (curve_t, line_t)
+        } else {
+            (line_t, curve_t)
+        };
+
+        result.push(CurveIntersection { t1, t2, point });
+    }
+}
+
+/// Finds intersections of two curves by recursive subdivision.
+///
+/// Curves are subdivided while their control point bounds overlap,
+/// until they are flat enough to be intersected as lines.
+fn curve_curve_intersections(c1: &Curve, c2: &Curve, result: &mut Vec<CurveIntersection>) {
+    let (min1, max1) = c1.control_points_bounds();
+    let (min2, max2) = c2.control_points_bounds();
+    let size = (max1.x - min1.x)
+        .max(max1.y - min1.y)
+        .max(max2.x - min2.x)
+        .max(max2.y - min2.y);
+    let tolerance = size * 1e-5;
+
+    let mut pairs_count = 0;
+    let mut stack = alloc::vec![(*c1, 0.0f32, 1.0f32, *c2, 0.0f32, 1.0f32, 0)];
+    while let Some((a, a_start, a_end, b, b_start, b_end, depth)) = stack.pop() {
+        pairs_count += 1;
+        if pairs_count > MAX_SUBDIVISION_PAIRS {
+            break;
+        }
+
+        let (a_min, a_max) = a.control_points_bounds();
+        let (b_min, b_max) = b.control_points_bounds();
+        if a_max.x < b_min.x || b_max.x < a_min.x || a_max.y < b_min.y || b_max.y < a_min.y {
+            continue;
+        }
+
+        let a_flat = a.flatness() <= tolerance;
+        let b_flat = b.flatness() <= tolerance;
+        if (a_flat && b_flat) || depth == MAX_SUBDIVISION_DEPTH {
+            let mut lines = Vec::new();
+            line_line_intersections(a.start(), a.end(), b.start(), b.end(), &mut lines);
+            for line in lines {
+                let t1 = a_start + (a_end - a_start) * line.t1;
+                let t2 = b_start + (b_end - b_start) * line.t2;
+                let is_duplicate = result
+                    .iter()
+                    .any(|r| (r.t1 - t1).abs() < T_EPSILON && (r.t2 - t2).abs() < T_EPSILON);
+                if !is_duplicate {
+                    result.push(CurveIntersection {
+                        t1,
+                        t2,
+                        point: c1.eval(t1),
+                    });
+                }
+            }
+
+            continue;
+        }
+
+        // Split the less flat curve.
+        if a.flatness() >= b.flatness() {
+            let (a1, a2) = a.split(0.5);
+            let a_mid = (a_start + a_end) * 0.5;
+            stack.push((a2, a_mid, a_end, b, b_start, b_end, depth + 1));
+            stack.push((a1, a_start, a_mid, b, b_start, b_end, depth + 1));
+        } else {
+            let (b1, b2) = b.split(0.5);
+            let b_mid = (b_start + b_end) * 0.5;
+            stack.push((a, a_start, a_end, b2, b_mid, b_end, depth + 1));
+            stack.push((a, a_start, a_end, b1, b_start, b_mid, depth + 1));
+        }
+    }
+}
+
+fn is_valid_t(t: f32) -> bool {
+    t >= -T_EPSILON && t <= 1.0 + T_EPSILON
+}
+
+fn clamp_t(t: f32) -> f32 {
+    if t > 0.0 {
+        t.min(1.0)
+    } else {
+        0.0
+    }
+}
+
+fn lerp(a: Point, b: Point, t: f32) -> Point {
+    a + (b - a).scaled(t)
+}
+
+fn to_f64(p: Point) -> (f64, f64) {
+    (f64::from(p.x), f64::from(p.y))
+}
@@ -35,13 +35,19 @@ extern crate std;
 
 extern crate alloc;
 
+mod boolean;
 mod dash;
 mod f32x2_t;
 mod f32x4_t;
 mod floating_point;
+mod geometry;
+mod markers;
+mod offset;
 mod path;
+mod path64;
 mod path_builder;
 pub mod path_geometry;
+mod polyline;
 mod rect;
 mod scalar;
 mod size;
@@ -51,6 +57,8 @@ mod transform;
 pub use dash::StrokeDash;
 pub use f32x2_t::f32x2;
 pub use floating_point::*;
+pub use geometry::{Curve, CurveIntersection, NearestPoint, PathCurvesIter};
+pub use markers::{MarkerShape, Markers};
 pub use path::*;
 pub use path_builder::*;
 pub use rect::*;
@@ -0,0 +1,293 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use crate::path::PathSegment;
+use crate::{Path, PathBuilder, Point, Transform};
+
+/// A shape placed at path vertices.
+///
+/// Shapes are defined in stroke width units, with the origin at a vertex
+/// and the X axis pointing along the path tangent.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MarkerShape {
+    /// A notched arrowhead.
+    ///
+    /// The tip is 2 units ahead of the vertex, the back corners are 1 unit behind
+    /// and the notch is at the vertex. 3 units wide.
+    Arrow,
+    /// A triangle with the base at the vertex.
+    ///
+    /// 2.5 units long and 3 units wide.
+    Triangle,
+    /// A square rotated by 45 degrees and centered at the vertex.
+    ///
+    /// 3 units wide.
+    Diamond,
+    /// A circle centered at the vertex.
+    ///
+    /// 3 units wide.
+    Circle,
+    /// A user-defined shape.
+    Path(Path),
+}
+
+impl MarkerShape {
+    fn to_path(&self) -> Option<Path> {
+        let polygon = |points: &[(f32, f32)]| {
+            let mut pb = PathBuilder::new();
+            pb.move_to(points[0].0, points[0].1);
+            for p in &points[1..] {
+                pb.line_to(p.0, p.1);
+            }
+            pb.close();
+            pb.finish()
+        };
+
+        match self {
+            MarkerShape::Arrow => polygon(&[(2.0, 0.0), (-1.0, 1.5), (0.0, 0.0), (-1.0, -1.5)]),
+            MarkerShape::Triangle => polygon(&[(2.5, 0.0), (0.0, 1.5), (0.0, -1.5)]),
+            MarkerShape::Diamond => polygon(&[(1.5, 0.0), (0.0, 1.5), (-1.5, 0.0), (0.0, -1.5)]),
+            MarkerShape::Circle => PathBuilder::from_circle(0.0, 0.0, 1.5),
+            MarkerShape::Path(path) => Some(path.clone()),
+        }
+    }
+}
+
+/// SVG-style markers.
+///
+/// See [`Path::markers`] for details.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Markers {
+    /// A marker at the first path vertex.
+    ///
+    /// Default: None
+    pub start: Option<MarkerShape>,
+
+    /// A marker at every vertex except the first and the last one.
+    ///
+    /// Default: None
+    pub mid: Option<MarkerShape>,
+
+    /// A marker at the last path vertex.
+    ///
+    /// Default: None
+    pub end: Option<MarkerShape>,
+}
+
+impl Path {
+    /// Returns shapes placed at the start and the end of each open contour.
+    ///
+    /// Unlike [`LineCap`](crate::LineCap), can use any shape.
+    /// Shapes are pointing away from the contour, therefore the start shape is flipped.
+    /// The contour itself should be stroked using [`LineCap::Butt`](crate::LineCap::Butt),
+    /// which is covered by the built-in shapes.
+    ///
+    /// Shapes are scaled by `stroke_width`.
+    ///
+    /// Returns `None` when `stroke_width` is not positive or there are no open contours.
+    pub fn cap_shapes(
+        &self,
+        start: Option<&MarkerShape>,
+        end: Option<&MarkerShape>,
+        stroke_width: f32,
+    ) -> Option<Path> {
+        if !(stroke_width > 0.0 && stroke_width.is_finite()) {
+            return None;
+        }
+
+        let start = start.and_then(|s| s.to_path());
+        let end = end.and_then(|s| s.to_path());
+
+        let mut pb = PathBuilder::new();
+        for contour in contours(self) {
+            if contour.closed {
+                continue;
+            }
+
+            let first = contour.vertices.iter().find_map(|v| v.outgoing);
+            if let (Some(shape), Some(dir)) = (&start, first) {
+                let point = contour.vertices[0].point;
+                push_marker(&mut pb, shape, point, -dir, stroke_width);
+            }
+
+            let last = contour.vertices.iter().rev().find_map(|v| v.incoming);
+            if let (Some(shape), Some(dir)) = (&end, last) {
+                let point = contour.vertices[contour.vertices.len() - 1].point;
+                push_marker(&mut pb, shape, point, dir, stroke_width);
+            }
+        }
+
+        pb.finish()
+    }
+
+    /// Returns markers placed at path vertices.
+    ///
+    /// Follows the SVG markers logic: the start marker is placed at the first path vertex,
+    /// the end one at the last path vertex and the mid one at all other vertices,
+    /// including the ones of other contours.
+    /// Markers are oriented along the bisector of incoming and outgoing tangents.
+    ///
+    /// Shapes are scaled by `stroke_width`, just like `markerUnits="strokeWidth"` in SVG.
+    ///
+    /// Returns `None` when `stroke_width` is not positive or there are no markers.
+    pub fn markers(&self, markers: &Markers, stroke_width: f32) -> Option<Path> {
+        if !(stroke_width > 0.0 && stroke_width.is_finite()) {
+            return None;
+        }
+
+        let start = markers.start.as_ref().and_then(|s| s.to_path());
+        let mid = markers.mid.as_ref().and_then(|s| s.to_path());
+        let end = markers.end.as_ref().and_then(|s| s.to_path());
+
+        let contours = contours(self);
+        let mut vertices = Vec::new();
+        for contour in &contours {
+            vertices.extend(contour.vertices.iter().copied());
+            if contour.closed {
+                // A closed contour ends at its first vertex.
+                vertices.push(contour.vertices[0]);
+            }
+        }
+
+        let mut pb = PathBuilder::new();
+        let last_index = vertices.len().saturating_sub(1);
+        for (i, vertex) in vertices.iter().enumerate() {
+            let shape = if i == 0 {
+                &start
+            } else if i == last_index {
+                &end
+            } else {
+                &mid
+            };
+
+            if let Some(shape) = shape {
+                let dir = vertex.direction().unwrap_or(Point::from_xy(1.0, 0.0));
+                push_marker(&mut pb, shape, vertex.point, dir, stroke_width);
+            }
+        }
+
+        pb.finish()
+    }
+}
+
+fn push_marker(pb: &mut PathBuilder, shape: &Path, point: Point, dir: Point, scale: f32) {
+    let ts = Transform::from_row(
+        dir.x * scale,
+        dir.y * scale,
+        -dir.y * scale,
+        dir.x * scale,
+        point.x,
+        point.y,
+    );
+    pb.push_path_with_transform(shape, ts);
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    point: Point,
+    /// A unit tangent at the end of the incoming segment.
+    incoming: Option<Point>,
+    /// A unit tangent at the start of the outgoing segment.
+    outgoing: Option<Point>,
+}
+
+impl Vertex {
+    /// Returns a bisector of incoming and outgoing tangents.
+    fn direction(&self) -> Option<Point> {
+        match (self.incoming, self.outgoing) {
+            (Some(a), Some(b)) => {
+                let mut dir = a + b;
+                if dir.normalize() {
+                    Some(dir)
+                } else {
+                    // A 180 degrees turn.
+                    Some(Point::from_xy(-a.y, a.x))
+                }
+            }
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+struct Contour {
+    vertices: Vec<Vertex>,
+    closed: bool,
+}
+
+fn contours(path: &Path) -> Vec<Contour> {
+    let mut contours: Vec<Contour> = Vec::new();
+    for segment in path.segments() {
+        if let PathSegment::MoveTo(p) = segment {
+            contours.push(Contour {
+                vertices: alloc::vec![Vertex {
+                    point: p,
+                    incoming: None,
+                    outgoing: None,
+                }],
+                closed: false,
+            });
+            continue;
+        }
+
+        let contour = match contours.last_mut() {
+            Some(v) => v,
+            None => continue,
+        };
+
+        let last_index = contour.vertices.len() - 1;
+        let prev = contour.vertices[last_index].point;
+        let (points, len) = match segment {
+            PathSegment::MoveTo(_) => continue,
+            PathSegment::LineTo(p) => ([prev, p, p, p], 2),
+            PathSegment::QuadTo(p1, p) => ([prev, p1, p, p], 3),
+            PathSegment::CubicTo(p1, p2, p) => ([prev, p1, p2, p], 4),
+            PathSegment::Close => {
+                contour.closed = true;
+                let first = contour.vertices[0].point;
+                if prev == first && last_index > 0 {
+                    // Already closed explicitly.
+                    let last = contour.vertices.pop().unwrap();
+                    contour.vertices[0].incoming = last.incoming;
+                } else {
+                    let tangent = unit_tangent(&[prev, first]);
+                    contour.vertices[last_index].outgoing = tangent;
+                    contour.vertices[0].incoming = tangent;
+                }
+
+                continue;
+            }
+        };
+
+        let points = &points[..len];
+        let mut reversed = [Point::zero(); 4];
+        reversed[..len].copy_from_slice(points);
+        reversed[..len].reverse();
+
+        contour.vertices[last_index].outgoing = unit_tangent(points);
+        contour.vertices.push(Vertex {
+            point: points[len - 1],
+            incoming: unit_tangent(&reversed[..len]).map(|t| -t),
+            outgoing: None,
+        });
+    }
+
+    contours
+}
+
+/// Returns a unit tangent at the start of a curve defined by `points`.
+///
+/// Control points that coincide with the start point are skipped.
+fn unit_tangent(points: &[Point]) -> Option<Point> {
+    for p in &points[1..] {
+        let mut t = *p - points[0];
+        if t.normalize() {
+            return Some(t);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,90 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use crate::boolean::{self, BooleanOp};
+use crate::{LineCap, LineJoin, Path, PathBuilder, Point, Stroke};
+
+impl Path {
+    /// Returns a path grown or shrunk by `distance`.
+    ///
+    /// A positive distance grows the path and a negative one shrinks it.
+    /// The path is treated as filled using the non-zero fill rule,
+    /// therefore open contours are closed implicitly.
+    ///
+    /// The result consists of non-self-intersecting closed polygons
+    /// approximating the original curves within `tolerance`.
+    /// Outer contours are clockwise and holes are counter-clockwise,
+    /// so both fill rules produce the same result.
+    ///
+    /// Has a quadratic complexity, therefore should be used for moderately sized paths.
+    ///
+    /// Returns `None` when `distance` is not finite, `tolerance` is not positive,
+    /// the resulting path is empty or cannot be constructed due to numerical errors.
+    pub fn offset(
+        &self,
+        distance: f32,
+        line_join: LineJoin,
+        miter_limit: f32,
+        tolerance: f32,
+    ) -> Option<Path> {
+        if !distance.is_finite() || !(tolerance > 0.0) {
+            return None;
+        }
+
+        // Remove self-intersections first, otherwise the stroke would follow them.
+        let polygons = boolean::apply(&to_polygons(self, tolerance), &[], BooleanOp::Union)?;
+        if distance == 0.0 {
+            return to_path(&polygons);
+        }
+
+        // The offset outline is a centered stroke with a doubled width.
+        // Its union with the original shape grows it and a difference shrinks it.
+        let stroke = Stroke {
+            width: distance.abs() * 2.0,
+            miter_limit,
+            line_cap: LineCap::Butt,
+            line_join,
+            ..Stroke::default()
+        };
+
+        // The stroker's precision is `1 / (resolution_scale * 4)`.
+        let res_scale = (tolerance * 4.0).recip();
+        let outline = to_path(&polygons)?.stroke(&stroke, res_scale)?;
+        let outline = to_polygons(&outline, tolerance);
+
+        let op = if distance < 0.0 {
+            BooleanOp::Difference
+        } else {
+            BooleanOp::Union
+        };
+
+        to_path(&boolean::apply(&polygons, &outline, op)?)
+    }
+}
+
+/// Converts closed polygons into a path.
+fn to_path(polygons: &[Vec<Point>]) -> Option<Path> {
+    let mut pb = PathBuilder::new();
+    for polygon in polygons {
+        pb.move_to(polygon[0].x, polygon[0].y);
+        for p in &polygon[1..] {
+            pb.line_to(p.x, p.y);
+        }
+        pb.close();
+    }
+
+    pb.finish()
+}
+
+/// Converts a path into polygons.
+///
+/// Open contours are closed implicitly.
+fn to_polygons(path: &Path, tolerance: f32) -> Vec<Vec<Point>> {
+    let mut polygons = path.flatten(tolerance);
+    polygons.retain(|polygon| polygon.len() > 2);
+    polygons
+}
@@ -6,7 +6,10 @@
 
 use alloc::vec::Vec;
 
-use crate::path_builder::PathBuilder;
+use crate::f32x2_t::f32x2;
+use crate::floating_point::FLOAT_PI;
+use crate::path_builder::{PathBuilder, PathDirection};
+use crate::path_geometry;
 use crate::transform::Transform;
 use crate::{Point, Rect};
 
@@ -151,6 +154,235 @@ impl Path {
         }
     }
 
+    /// Returns an iterator over path's contours.
+    ///
+    /// Each contour is returned as a separate path.
+    /// Contours consisting of just a MoveTo are skipped.
+    pub fn contours(&self) -> PathContoursIter<'_> {
+        PathContoursIter {
+            path: self,
+            verb_index: 0,
+            points_index: 0,
+        }
+    }
+
+    /// Returns a path with all contours reversed.
+    ///
+    /// The contours order is preserved.
+    pub fn reverse(&self) -> Path {
+        let mut verbs = Vec::with_capacity(self.verbs.len());
+        let mut points = Vec::with_capacity(self.points.len());
+
+        let mut verb_index = 0;
+        let mut points_index = 0;
+        while verb_index < self.verbs.len() {
+            let (verbs_count, points_count) = contour_len(&self.verbs[verb_index..]);
+            let contour_verbs = &self.verbs[verb_index..verb_index + verbs_count];
+            let is_closed = contour_verbs.last() == Some(&PathVerb::Close);
+
+            // Reversed points produce a valid contour as long as the verbs are reversed as well.
+            verbs.push(PathVerb::Move);
+            verbs.extend(
+                contour_verbs[1..]
+                    .iter()
+                    .rev()
+                    .filter(|verb| **verb != PathVerb::Close),
+            );
+            if is_closed {
+                verbs.push(PathVerb::Close);
+            }
+
+            points.extend(
+                self.points[points_index..points_index + points_count]
+                    .iter()
+                    .rev(),
+            );
+
+            verb_index += verbs_count;
+            points_index += points_count;
+        }
+
+        Path {
+            verbs,
+            points,
+            bounds: self.bounds,
+        }
+    }
+
+    /// Returns the winding direction of the path.
+    ///
+    /// Computed using the signed area of all contours, closed implicitly.
+    /// Control points are treated as regular ones, which is enough to tell the direction
+    /// of non-self-intersecting contours.
+    ///
+    /// Returns `None` when the area is zero.
+    pub fn winding_direction(&self) -> Option<PathDirection> {
+        let mut area = 0.0;
+        let mut points_index = 0;
+        let mut verb_index = 0;
+        while verb_index < self.verbs.len() {
+            let (verbs_count, points_count) = contour_len(&self.verbs[verb_index..]);
+            let contour = &self.points[points_index..points_index + points_count];
+            for (i, p0) in contour.iter().enumerate() {
+                let p1 = contour[(i + 1) % contour.len()];
+                area += p0.cross(p1);
+            }
+
+            verb_index += verbs_count;
+            points_index += points_count;
+        }
+
+        if area > 0.0 {
+            Some(PathDirection::CW)
+        } else if area < 0.0 {
+            Some(PathDirection::CCW)
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the path is an axis-aligned rectangle.
+    ///
+    /// The path must have a single contour of lines. Collinear and duplicated points are allowed.
+    /// An open contour is treated as closed, like when filling.
+    ///
+    /// Returns the rectangle on success.
+    pub fn is_rect(&self) -> Option<Rect> {
+        let (verbs_count, _) = contour_len(&self.verbs);
+        if verbs_count != self.verbs.len() {
+            return None;
+        }
+
+        let is_line = |verb: &PathVerb| *verb == PathVerb::Line || *verb == PathVerb::Close;
+        if !self.verbs[1..].iter().all(is_line) {
+            return None;
+        }
+
+        // Collect edge directions, merging consecutive edges that go in the same direction.
+        let mut directions: Vec<(bool, bool)> = Vec::with_capacity(4);
+        for (i, p0) in self.points.iter().enumerate() {
+            let p1 = self.points[(i + 1) % self.points.len()];
+            if *p0 == p1 {
+                continue;
+            }
+
+            let direction = if p0.y == p1.y {
+                (true, p1.x > p0.x)
+            } else if p0.x == p1.x {
+                (false, p1.y > p0.y)
+            } else {
+                return None;
+            };
+
+            if directions.last() != Some(&direction) {
+                directions.push(direction);
+            }
+        }
+
+        if directions.len() > 1 && directions.first() == directions.last() {
+            directions.pop();
+        }
+
+        // Four edges with alternating axes.
+        // Since the contour is closed, opposite edges must have opposite directions.
+        if directions.len() != 4 || (0..4).any(|i| directions[i].0 == directions[(i + 1) % 4].0) {
+            return None;
+        }
+
+        Some(self.bounds)
+    }
+
+    /// Checks that the path is an oval.
+    ///
+    /// The path must have a single closed contour of curves approximating an axis-aligned ellipse,
+    /// like the one produced by [`PathBuilder::push_oval`]
+    /// or by four cubic curves with the usual 0.552 control points offset.
+    ///
+    /// Returns the oval bounds on success.
+    pub fn is_oval(&self) -> Option<Rect> {
+        // The conic to quads conversion precision.
+        const TOLERANCE: f32 = 0.25;
+
+        let (verbs_count, _) = contour_len(&self.verbs);
+        if verbs_count != self.verbs.len() || self.verbs.last() != Some(&PathVerb::Close) {
+            return None;
+        }
+
+        let is_curve = |verb: &PathVerb| *verb == PathVerb::Quad || *verb == PathVerb::Cubic;
+        if !self.verbs[1..self.verbs.len() - 1].iter().all(is_curve) {
+            return None;
+        }
+
+        let bounds = self.compute_tight_bounds()?;
+        let center = Point::from_xy(
+            bounds.left() + bounds.width() * 0.5,
+            bounds.top() + bounds.height() * 0.5,
+        );
+        let rx = bounds.width() * 0.5;
+        let ry = bounds.height() * 0.5;
+        if !(rx > 0.0 && ry > 0.0) {
+            return None;
+        }
+
+        // Sample curves at their ends and midpoints.
+        let mut samples = Vec::new();
+        for segment in self.segments() {
+            match segment {
+                PathSegment::MoveTo(p) => samples.push(p),
+                PathSegment::QuadTo(p1, p2) => {
+                    let p0 = samples[samples.len() - 1];
+                    let coeff = path_geometry::QuadCoeff::from_points(&[p0, p1, p2]);
+                    samples.push(Point::from_f32x2(coeff.eval(f32x2::splat(0.5))));
+                    samples.push(p2);
+                }
+                PathSegment::CubicTo(p1, p2, p3) => {
+                    let p0 = samples[samples.len() - 1];
+                    let coeff = path_geometry::CubicCoeff::from_points(&[p0, p1, p2, p3]);
+                    samples.push(Point::from_f32x2(coeff.eval(f32x2::splat(0.5))));
+                    samples.push(p3);
+                }
+                PathSegment::LineTo(_) | PathSegment::Close => {}
+            }
+        }
+
+        if samples.first() != samples.last() {
+            return None;
+        }
+
+        // All samples must be on the ellipse.
+        let tolerance = TOLERANCE / rx.min(ry);
+        for p in &samples {
+            let dx = (p.x - center.x) / rx;
+            let dy = (p.y - center.y) / ry;
+            if ((dx * dx + dy * dy).sqrt() - 1.0).abs() > tolerance {
+                return None;
+            }
+        }
+
+        // And go around it exactly once, in a single direction.
+        let mut area = 0.0;
+        let mut direction = 0.0;
+        for pair in samples.windows(2) {
+            let cross = (pair[0] - center).cross(pair[1] - center);
+            if cross * direction < 0.0 {
+                return None;
+            }
+
+            if cross != 0.0 {
+                direction = cross;
+            }
+
+            area += cross * 0.5;
+        }
+
+        let expected_area = FLOAT_PI * rx * ry;
+        if (area.abs() - expected_area).abs() > expected_area * 0.25 {
+            return None;
+        }
+
+        Some(bounds)
+    }
+
     /// Clears the path and returns a `PathBuilder` that will reuse an allocated memory.
     pub fn clear(mut self) -> PathBuilder {
         self.verbs.clear();
@@ -194,7 +426,28 @@ impl core::fmt::Debug for Path {
     }
 }
 
-fn compute_quad_extremas(p0: Point, p1: Point, p2: Point, extremas: &mut [Point; 5]) -> usize {
+/// Returns the number of verbs and points in the contour at the start of `verbs`.
+fn contour_len(verbs: &[PathVerb]) -> (usize, usize) {
+    let mut points_count = 0;
+    for (i, verb) in verbs.iter().enumerate() {
+        match verb {
+            PathVerb::Move if i > 0 => return (i, points_count),
+            PathVerb::Move | PathVerb::Line => points_count += 1,
+            PathVerb::Quad => points_count += 2,
+            PathVerb::Cubic => points_count += 3,
+            PathVerb::Close => {}
+        }
+    }
+
+    (verbs.len(), points_count)
+}
+
+pub(crate) fn compute_quad_extremas(
+    p0: Point,
+    p1: Point,
+    p2: Point,
+    extremas: &mut [Point; 5],
+) -> usize {
     use crate::path_geometry;
 
     let src = [p0, p1, p2];
@@ -211,7 +464,7 @@ fn compute_quad_extremas(p0: Point, p1: Point, p2: Point, extremas: &mut [Point;
     extrema_idx + 1
 }
 
-fn compute_cubic_extremas(
+pub(crate) fn compute_cubic_extremas(
     p0: Point,
     p1: Point,
     p2: Point,
@@ -379,3 +632,38 @@ impl Iterator for PathSegmentsIter<'_> {
         }
     }
 }
+
+/// An iterator over path contours.
+///
+/// Created by [`Path::contours`].
+#[allow(missing_debug_implementations)]
+#[derive(Clone)]
+pub struct PathContoursIter<'a> {
+    path: &'a Path,
+    verb_index: usize,
+    points_index: usize,
+}
+
+impl Iterator for PathContoursIter<'_> {
+    type Item = Path;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.verb_index < self.path.verbs.len() {
+            let (verbs_count, points_count) = contour_len(&self.path.verbs[self.verb_index..]);
+            let verbs = &self.path.verbs[self.verb_index..self.verb_index + verbs_count];
+            let points = &self.path.points[self.points_index..self.points_index + points_count];
+            self.verb_index += verbs_count;
+            self.points_index += points_count;
+
+            if verbs_count > 1 {
+                return Some(Path {
+                    verbs: verbs.to_vec(),
+                    points: points.to_vec(),
+                    bounds: Rect::from_points(points)?,
+                });
+            }
+        }
+
+        None
+    }
+}
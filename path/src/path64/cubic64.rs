@@ -9,7 +9,7 @@ use super::quad64;
 use super::Scalar64;
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
-use tiny_skia_path::NoStdFloat;
+use crate::NoStdFloat;
 
 pub const POINT_COUNT: usize = 4;
 const PI: f64 = 3.141592653589793;
@@ -233,23 +233,18 @@ pub fn roots_valid_t(a: f64, b: f64, c: f64, d: f64, t: &mut [f64; 3]) -> usize
     let mut s = [0.0; 3];
     let real_roots = roots_real(a, b, c, d, &mut s);
     let mut found_roots = quad64::push_valid_ts(&s, real_roots, t);
-    'outer: for index in 0..real_roots {
-        let t_value = s[index];
+    for &t_value in &s[..real_roots] {
         if !t_value.approximately_one_or_less() && t_value.between(1.0, 1.00005) {
-            for idx2 in 0..found_roots {
-                if t[idx2].approximately_equal(1.0) {
-                    continue 'outer;
-                }
+            if t[..found_roots].iter().any(|t| t.approximately_equal(1.0)) {
+                continue;
             }
 
             debug_assert!(found_roots < 3);
             t[found_roots] = 1.0;
             found_roots += 1;
         } else if !t_value.approximately_zero_or_more() && t_value.between(-0.00005, 0.0) {
-            for idx2 in 0..found_roots {
-                if t[idx2].approximately_equal(0.0) {
-                    continue 'outer;
-                }
+            if t[..found_roots].iter().any(|t| t.approximately_equal(0.0)) {
+                continue;
             }
 
             debug_assert!(found_roots < 3);
@@ -277,10 +272,8 @@ fn roots_real(a: f64, b: f64, c: f64, d: f64, s: &mut [f64; 3]) -> usize {
     {
         // 0 is one root
         let mut num = quad64::roots_real(a, b, c, s);
-        for i in 0..num {
-            if s[i].approximately_zero() {
-                return num;
-            }
+        if s[..num].iter().any(|s| s.approximately_zero()) {
+            return num;
         }
 
         s[num] = 0.0;
@@ -292,10 +285,8 @@ fn roots_real(a: f64, b: f64, c: f64, d: f64, s: &mut [f64; 3]) -> usize {
     if (a + b + c + d).approximately_zero() {
         // 1 is one root
         let mut num = quad64::roots_real(a, a + b, -d, s);
-        for i in 0..num {
-            if s[i].almost_dequal_ulps(1.0) {
-                return num;
-            }
+        if s[..num].iter().any(|s| s.almost_dequal_ulps(1.0)) {
+            return num;
         }
         s[num] = 1.0;
         num += 1;
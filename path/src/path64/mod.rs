@@ -4,10 +4,10 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
-use tiny_skia_path::{Scalar, SCALAR_MAX};
+use crate::{Scalar, SCALAR_MAX};
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
-use tiny_skia_path::NoStdFloat;
+use crate::NoStdFloat;
 
 // Must be first, because of macro scope rules.
 #[macro_use]
@@ -15,7 +15,7 @@ pub mod point64;
 
 pub mod cubic64;
 pub mod line_cubic_intersections;
-mod quad64;
+pub mod quad64;
 
 // The code below is from SkPathOpsTypes.
 
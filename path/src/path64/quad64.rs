@@ -7,19 +7,18 @@
 use super::Scalar64;
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
-use tiny_skia_path::NoStdFloat;
+use crate::NoStdFloat;
 
 pub fn push_valid_ts(s: &[f64], real_roots: usize, t: &mut [f64]) -> usize {
     let mut found_roots = 0;
-    'outer: for index in 0..real_roots {
-        let mut t_value = s[index];
+    for &t_value in &s[..real_roots] {
         if t_value.approximately_zero_or_more() && t_value.approximately_one_or_less() {
-            t_value = t_value.bound(0.0, 1.0);
-
-            for idx2 in 0..found_roots {
-                if t[idx2].approximately_equal(t_value) {
-                    continue 'outer;
-                }
+            let t_value = t_value.bound(0.0, 1.0);
+            if t[..found_roots]
+                .iter()
+                .any(|t| t.approximately_equal(t_value))
+            {
+                continue;
             }
 
             t[found_roots] = t_value;
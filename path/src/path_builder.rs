@@ -9,17 +9,20 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use crate::{Path, Point, Rect};
+use crate::{Path, Point, Rect, Transform};
 
 use crate::path::PathVerb;
 use crate::path_geometry;
 use crate::scalar::{Scalar, SCALAR_ROOT_2_OVER_2};
 
+/// A contour direction.
+///
+/// Assumes a Y-down coordinate system.
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub(crate) enum PathDirection {
-    /// Clockwise direction for adding closed contours.
+pub enum PathDirection {
+    /// Clockwise direction.
     CW,
-    /// Counter-clockwise direction for adding closed contours.
+    /// Counter-clockwise direction.
     CCW,
 }
 
@@ -337,10 +340,42 @@ impl PathBuilder {
 
     /// Adds a path.
     pub fn push_path(&mut self, other: &Path) {
-        self.last_move_to_index = self.points.len();
+        self.push_path_data(&other.verbs, &other.points);
+    }
 
-        self.verbs.extend_from_slice(&other.verbs);
-        self.points.extend_from_slice(&other.points);
+    /// Adds a transformed path.
+    ///
+    /// Does nothing when any of the transformed points is not finite.
+    pub fn push_path_with_transform(&mut self, other: &Path, ts: Transform) {
+        let mut points = other.points.clone();
+        ts.map_points(&mut points);
+        if points.iter().any(|p| !p.is_finite()) {
+            return;
+        }
+
+        self.push_path_data(&other.verbs, &points);
+    }
+
+    fn push_path_data(&mut self, verbs: &[PathVerb], points: &[Point]) {
+        // Subsequent segments continue the last contour of the pushed path.
+        let mut points_count = self.points.len();
+        for verb in verbs {
+            match verb {
+                PathVerb::Move => {
+                    self.last_move_to_index = points_count;
+                    points_count += 1;
+                }
+                PathVerb::Line => points_count += 1,
+                PathVerb::Quad => points_count += 2,
+                PathVerb::Cubic => points_count += 3,
+                PathVerb::Close => {}
+            }
+        }
+
+        self.move_to_required = verbs.last() == Some(&PathVerb::Close);
+
+        self.verbs.extend_from_slice(verbs);
+        self.points.extend_from_slice(points);
     }
 
     pub(crate) fn push_path_builder(&mut self, other: &PathBuilder) {
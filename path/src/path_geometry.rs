@@ -880,6 +880,57 @@ impl AutoConicToQuads {
     }
 }
 
+/// Chops a monotonic cubic at the specified `x` coordinate.
+///
+/// Returns `false` when the cubic doesn't cross `x`, leaving `dst` untouched.
+#[doc(hidden)]
+pub fn chop_mono_cubic_at_x(src: &[Point; 4], x: f32, dst: &mut [Point; 7]) -> bool {
+    cubic_dchop_at_intercept(src, x, true, dst)
+}
+
+/// Chops a monotonic cubic at the specified `y` coordinate.
+///
+/// Returns `false` when the cubic doesn't cross `y`, leaving `dst` untouched.
+#[doc(hidden)]
+pub fn chop_mono_cubic_at_y(src: &[Point; 4], y: f32, dst: &mut [Point; 7]) -> bool {
+    cubic_dchop_at_intercept(src, y, false, dst)
+}
+
+fn cubic_dchop_at_intercept(
+    src: &[Point; 4],
+    intercept: f32,
+    is_vertical: bool,
+    dst: &mut [Point; 7],
+) -> bool {
+    use crate::path64::{cubic64::Cubic64, line_cubic_intersections, point64::Point64};
+
+    let src = [
+        Point64::from_point(src[0]),
+        Point64::from_point(src[1]),
+        Point64::from_point(src[2]),
+        Point64::from_point(src[3]),
+    ];
+
+    let cubic = Cubic64::new(src);
+    let mut roots = [0.0; 3];
+    let count = if is_vertical {
+        line_cubic_intersections::vertical_intersect(&cubic, f64::from(intercept), &mut roots)
+    } else {
+        line_cubic_intersections::horizontal_intersect(&cubic, f64::from(intercept), &mut roots)
+    };
+
+    if count > 0 {
+        let pair = cubic.chop_at(roots[0]);
+        for (d, p) in dst.iter_mut().zip(pair.points.iter()) {
+            *d = p.to_point();
+        }
+
+        true
+    } else {
+        false
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,508 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Conversions between curves and polylines.
+
+use alloc::vec::Vec;
+
+use crate::f32x2_t::f32x2;
+use crate::path::PathSegment;
+use crate::path_geometry::{CubicCoeff, QuadCoeff};
+use crate::{Path, PathBuilder, Point};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use crate::NoStdFloat;
+
+/// The maximum number of lines a single curve can be flattened into.
+const MAX_FLATTEN_LINES: usize = 256;
+
+/// The maximum number of Newton-Raphson reparameterization passes per curve.
+const MAX_REPARAMETERIZATIONS: usize = 4;
+
+impl Path {
+    /// Converts the path into polylines.
+    ///
+    /// Each contour becomes a separate polyline, with curves approximated by lines
+    /// deviating from them by no more than `tolerance`.
+    /// Closed contours end with their first point.
+    ///
+    /// A single curve is split into no more than 256 lines.
+    pub fn flatten(&self, tolerance: f32) -> Vec<Vec<Point>> {
+        flatten_contours(self, tolerance)
+            .into_iter()
+            .map(|mut contour| {
+                if contour.closed && contour.points.len() > 1 {
+                    contour.points.push(contour.points[0]);
+                }
+
+                contour.points
+            })
+            .collect()
+    }
+
+    /// Returns a path with redundant polyline points removed.
+    ///
+    /// Uses the Ramer-Douglas-Peucker algorithm.
+    /// Curves are flattened first, therefore the result contains only lines.
+    /// The result deviates from the original path by no more than `tolerance`.
+    ///
+    /// Returns `None` when `tolerance` is not positive or finite.
+    pub fn simplify_polyline(&self, tolerance: f32) -> Option<Path> {
+        if !(tolerance > 0.0 && tolerance.is_finite()) {
+            return None;
+        }
+
+        // Split the tolerance between flattening and simplification.
+        let tolerance = tolerance * 0.5;
+
+        let mut pb = PathBuilder::new();
+        for mut contour in flatten_contours(self, tolerance) {
+            if contour.closed && contour.points.len() > 2 {
+                // Split a closed contour at the point farthest from the first one
+                // and simplify both halves as open polylines.
+                let first = contour.points[0];
+                let mut farthest = 0;
+                let mut max_dist = 0.0;
+                for (i, p) in contour.points.iter().enumerate() {
+                    let dist = p.distance_to_sqd(first);
+                    if dist > max_dist {
+                        max_dist = dist;
+                        farthest = i;
+                    }
+                }
+
+                contour.points.push(first);
+                let mut keep = alloc::vec![false; contour.points.len()];
+                simplify(&contour.points, 0, farthest, tolerance, &mut keep);
+                let last = contour.points.len() - 1;
+                simplify(&contour.points, farthest, last, tolerance, &mut keep);
+                keep[last] = false;
+                push_polyline(&mut pb, &contour.points, &keep, true);
+            } else {
+                let mut keep = alloc::vec![false; contour.points.len()];
+                let last = contour.points.len() - 1;
+                simplify(&contour.points, 0, last, tolerance, &mut keep);
+                push_polyline(&mut pb, &contour.points, &keep, contour.closed);
+            }
+        }
+
+        pb.finish()
+    }
+}
+
+impl PathBuilder {
+    /// Adds a contour of cubic curves fitted to a sequence of points.
+    ///
+    /// The curves deviate from the points by no more than `error`,
+    /// which is useful for smoothing sampled or traced data.
+    /// When the first and the last points are the same, the contour is closed
+    /// and has no corner at its start.
+    ///
+    /// Based on "An Algorithm for Automatically Fitting Digitized Curves" by Philip J. Schneider.
+    ///
+    /// Does nothing when:
+    /// - there are less than 2 distinct points
+    /// - any point is not finite
+    /// - `error` is not positive or finite
+    pub fn fit_curves(&mut self, points: &[Point], error: f32) {
+        if !(error > 0.0 && error.is_finite()) || points.iter().any(|p| !p.is_finite()) {
+            return;
+        }
+
+        let mut points = points.to_vec();
+        points.dedup();
+        if points.len() < 2 {
+            return;
+        }
+
+        let last = points.len() - 1;
+        let closed = points.len() > 3 && points[0] == points[last];
+
+        let mut start_tangent = unit(points[1] - points[0]);
+        let mut end_tangent = unit(points[last - 1] - points[last]);
+        if closed {
+            if let Some(tangent) = unit(points[1] - points[last - 1]) {
+                start_tangent = Some(tangent);
+                end_tangent = Some(-tangent);
+            }
+        }
+
+        let (start_tangent, end_tangent) = match (start_tangent, end_tangent) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return,
+        };
+
+        self.move_to(points[0].x, points[0].y);
+
+        // Pieces are fitted from the start to the end, splitting them when the error is too big.
+        let error_sqd = error * error;
+        let mut stack = alloc::vec![(0, last, start_tangent, end_tangent)];
+        while let Some((first, last, start_tangent, end_tangent)) = stack.pop() {
+            match fit_cubic(&points[first..=last], start_tangent, end_tangent, error_sqd) {
+                Ok(curve) => self.cubic_to(
+                    curve[1].x, curve[1].y, curve[2].x, curve[2].y, curve[3].x, curve[3].y,
+                ),
+                Err(split) => {
+                    let split = first + split;
+                    let center = unit(points[split - 1] - points[split + 1])
+                        .or_else(|| unit(points[split - 1] - points[split]))
+                        .unwrap_or(end_tangent);
+                    stack.push((split, last, -center, end_tangent));
+                    stack.push((first, split, start_tangent, center));
+                }
+            }
+        }
+
+        if closed {
+            self.close();
+        }
+    }
+}
+
+struct Contour {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+fn flatten_contours(path: &Path, tolerance: f32) -> Vec<Contour> {
+    let mut contours: Vec<Contour> = Vec::new();
+    for segment in path.segments() {
+        if let PathSegment::MoveTo(p) = segment {
+            contours.push(Contour {
+                points: alloc::vec![p],
+                closed: false,
+            });
+            continue;
+        }
+
+        let contour = match contours.last_mut() {
+            Some(v) => v,
+            None => continue,
+        };
+
+        let p0 = contour.points[contour.points.len() - 1];
+        match segment {
+            PathSegment::MoveTo(_) => {}
+            PathSegment::LineTo(p) => contour.points.push(p),
+            PathSegment::QuadTo(p1, p2) => {
+                flatten_quad(&[p0, p1, p2], tolerance, &mut contour.points);
+            }
+            PathSegment::CubicTo(p1, p2, p3) => {
+                flatten_cubic(&[p0, p1, p2, p3], tolerance, &mut contour.points);
+            }
+            PathSegment::Close => {
+                // An explicitly closed contour ends with its first point already.
+                if contour.points.len() > 1 && p0 == contour.points[0] {
+                    contour.points.pop();
+                }
+
+                contour.closed = true;
+            }
+        }
+    }
+
+    contours
+}
+
+/// Returns the number of lines required to approximate a curve
+/// with the specified second derivative magnitude.
+///
+/// Based on Wang's formula.
+fn flatten_lines_count(deviation: f32, tolerance: f32) -> usize {
+    let n = (deviation / tolerance).sqrt().ceil();
+    if n.is_finite() {
+        (n as usize).clamp(1, MAX_FLATTEN_LINES)
+    } else {
+        MAX_FLATTEN_LINES
+    }
+}
+
+/// Appends a flattened quad to `points`, excluding its start point.
+pub(crate) fn flatten_quad(quad: &[Point; 3], tolerance: f32, points: &mut Vec<Point>) {
+    let dd = (quad[0] - quad[1].scaled(2.0) + quad[2]).length();
+    let n = flatten_lines_count(dd * 0.25, tolerance);
+    let coeff = QuadCoeff::from_points(quad);
+    for i in 1..n {
+        let t = i as f32 / n as f32;
+        points.push(Point::from_f32x2(coeff.eval(f32x2::splat(t))));
+    }
+
+    points.push(quad[2]);
+}
+
+/// Appends a flattened cubic to `points`, excluding its start point.
+pub(crate) fn flatten_cubic(cubic: &[Point; 4], tolerance: f32, points: &mut Vec<Point>) {
+    let dd1 = (cubic[0] - cubic[1].scaled(2.0) + cubic[2]).length();
+    let dd2 = (cubic[1] - cubic[2].scaled(2.0) + cubic[3]).length();
+    let n = flatten_lines_count(dd1.max(dd2) * 0.75, tolerance);
+    let coeff = CubicCoeff::from_points(cubic);
+    for i in 1..n {
+        let t = i as f32 / n as f32;
+        points.push(Point::from_f32x2(coeff.eval(f32x2::splat(t))));
+    }
+
+    points.push(cubic[3]);
+}
+
+/// Marks points between `first` and `last` inclusive that should be kept.
+fn simplify(points: &[Point], first: usize, last: usize, tolerance: f32, keep: &mut [bool]) {
+    keep[first] = true;
+    keep[last] = true;
+
+    let tolerance_sqd = tolerance * tolerance;
+    let mut stack = alloc::vec![(first, last)];
+    while let Some((first, last)) = stack.pop() {
+        let mut farthest = 0;
+        let mut max_dist = 0.0;
+        for i in first + 1..last {
+            let dist = distance_to_line_sqd(points[i], points[first], points[last]);
+            if dist > max_dist {
+                max_dist = dist;
+                farthest = i;
+            }
+        }
+
+        if max_dist > tolerance_sqd {
+            keep[farthest] = true;
+            stack.push((first, farthest));
+            stack.push((farthest, last));
+        }
+    }
+}
+
+fn push_polyline(pb: &mut PathBuilder, points: &[Point], keep: &[bool], closed: bool) {
+    let mut iter = points.iter().zip(keep).filter(|(_, k)| **k).map(|(p, _)| p);
+    if let Some(p) = iter.next() {
+        pb.move_to(p.x, p.y);
+    }
+
+    for p in iter {
+        pb.line_to(p.x, p.y);
+    }
+
+    if closed {
+        pb.close();
+    }
+}
+
+/// Returns a squared distance from `p` to a line segment.
+fn distance_to_line_sqd(p: Point, a: Point, b: Point) -> f32 {
+    let ab = b - a;
+    let len_sqd = ab.length_sqd();
+    if len_sqd == 0.0 {
+        return p.distance_to_sqd(a);
+    }
+
+    let t = ((p - a).dot(ab) / len_sqd).clamp(0.0, 1.0);
+    p.distance_to_sqd(a + ab.scaled(t))
+}
+
+fn unit(mut v: Point) -> Option<Point> {
+    if v.normalize() {
+        Some(v)
+    } else {
+        None
+    }
+}
+
+/// Fits a single cubic to points.
+///
+/// Returns an index of the point with the biggest error when the fit is not good enough.
+fn fit_cubic(
+    points: &[Point],
+    start_tangent: Point,
+    end_tangent: Point,
+    error_sqd: f32,
+) -> Result<[Point; 4], usize> {
+    let first = points[0];
+    let last = points[points.len() - 1];
+    if points.len() == 2 {
+        let dist = first.distance(last) / 3.0;
+        return Ok([
+            first,
+            first + start_tangent.scaled(dist),
+            last + end_tangent.scaled(dist),
+            last,
+        ]);
+    }
+
+    // Chord-length parameterization.
+    let mut params = Vec::with_capacity(points.len());
+    params.push(0.0);
+    for pair in points.windows(2) {
+        let prev = params[params.len() - 1];
+        params.push(prev + pair[0].distance(pair[1]));
+    }
+
+    let total = params[params.len() - 1];
+    for u in &mut params {
+        *u /= total;
+    }
+
+    let mut curve = generate_cubic(points, &params, start_tangent, end_tangent);
+    let (mut max_error, mut split) = compute_max_error(points, &params, &curve);
+    if max_error < error_sqd {
+        return Ok(curve);
+    }
+
+    // When the error is not too big, try to improve the parameterization.
+    if max_error < error_sqd * 4.0 {
+        for _ in 0..MAX_REPARAMETERIZATIONS {
+            reparameterize(points, &mut params, &curve);
+            curve = generate_cubic(points, &params, start_tangent, end_tangent);
+            (max_error, split) = compute_max_error(points, &params, &curve);
+            if max_error < error_sqd {
+                return Ok(curve);
+            }
+        }
+    }
+
+    Err(split)
+}
+
+/// Finds control points using the least-squares method.
+fn generate_cubic(
+    points: &[Point],
+    params: &[f32],
+    start_tangent: Point,
+    end_tangent: Point,
+) -> [Point; 4] {
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    let mut c = [[0.0f32; 2]; 2];
+    let mut x = [0.0f32; 2];
+    for (p, u) in points.iter().zip(params) {
+        let b = bernstein(*u);
+        let a1 = start_tangent.scaled(b[1]);
+        let a2 = end_tangent.scaled(b[2]);
+
+        c[0][0] += a1.dot(a1);
+        c[0][1] += a1.dot(a2);
+        c[1][1] += a2.dot(a2);
+
+        let tmp = *p - (first.scaled(b[0] + b[1]) + last.scaled(b[2] + b[3]));
+        x[0] += a1.dot(tmp);
+        x[1] += a2.dot(tmp);
+    }
+
+    let det_c0_c1 = c[0][0] * c[1][1] - c[0][1] * c[0][1];
+    let det_c0_x = c[0][0] * x[1] - c[0][1] * x[0];
+    let det_x_c1 = x[0] * c[1][1] - x[1] * c[0][1];
+
+    let (mut alpha_l, mut alpha_r) = if det_c0_c1 != 0.0 {
+        (det_x_c1 / det_c0_c1, det_c0_x / det_c0_c1)
+    } else {
+        (0.0, 0.0)
+    };
+
+    // Fall back to a heuristic when control points are too close to the ends
+    // or point in the wrong direction.
+    let seg_len = first.distance(last);
+    let epsilon = seg_len * 1e-6;
+    if !(alpha_l >= epsilon && alpha_r >= epsilon) {
+        alpha_l = seg_len / 3.0;
+        alpha_r = alpha_l;
+    }
+
+    [
+        first,
+        first + start_tangent.scaled(alpha_l),
+        last + end_tangent.scaled(alpha_r),
+        last,
+    ]
+}
+
+/// Returns the maximum squared distance from points to the curve and its index.
+fn compute_max_error(points: &[Point], params: &[f32], curve: &[Point; 4]) -> (f32, usize) {
+    let coeff = CubicCoeff::from_points(curve);
+    let mut max_dist = 0.0;
+    let mut split = points.len() / 2;
+    for i in 1..points.len() - 1 {
+        let p = Point::from_f32x2(coeff.eval(f32x2::splat(params[i])));
+        let dist = p.distance_to_sqd(points[i]);
+        if dist >= max_dist {
+            max_dist = dist;
+            split = i;
+        }
+    }
+
+    (max_dist, split)
+}
+
+/// Improves the parameterization using a single Newton-Raphson iteration.
+fn reparameterize(points: &[Point], params: &mut [f32], curve: &[Point; 4]) {
+    let coeff = CubicCoeff::from_points(curve);
+    let d1 = [
+        (curve[1] - curve[0]).scaled(3.0),
+        (curve[2] - curve[1]).scaled(3.0),
+        (curve[3] - curve[2]).scaled(3.0),
+    ];
+    let d2 = [(d1[1] - d1[0]).scaled(2.0), (d1[2] - d1[1]).scaled(2.0)];
+
+    for (p, u) in points.iter().zip(params.iter_mut()) {
+        let t = *u;
+        let mt = 1.0 - t;
+        let q = Point::from_f32x2(coeff.eval(f32x2::splat(t)));
+        let q1 = d1[0].scaled(mt * mt) + d1[1].scaled(2.0 * t * mt) + d1[2].scaled(t * t);
+        let q2 = d2[0].scaled(mt) + d2[1].scaled(t);
+
+        let diff = q - *p;
+        let numerator = diff.dot(q1);
+        let denominator = q1.dot(q1) + diff.dot(q2);
+        if denominator != 0.0 {
+            // Parameters outside of the curve would match its extrapolation.
+            let new_t = t - numerator / denominator;
+            if new_t.is_finite() {
+                *u = new_t.clamp(0.0, 1.0);
+            }
+        }
+    }
+}
+
+fn bernstein(t: f32) -> [f32; 4] {
+    let mt = 1.0 - t;
+    [mt * mt * mt, 3.0 * t * mt * mt, 3.0 * t * t * mt, t * t * t]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simplify_straight_line() {
+        let points = [
+            Point::from_xy(0.0, 0.0),
+            Point::from_xy(1.0, 0.1),
+            Point::from_xy(2.0, -0.1),
+            Point::from_xy(3.0, 5.0),
+            Point::from_xy(4.0, 0.0),
+        ];
+
+        let mut keep = [false; 5];
+        simplify(&points, 0, 4, 0.5, &mut keep);
+        assert_eq!(keep, [true, false, true, true, true]);
+    }
+
+    #[test]
+    fn fit_two_points() {
+        let points = [Point::from_xy(0.0, 0.0), Point::from_xy(3.0, 0.0)];
+        let curve = fit_cubic(
+            &points,
+            Point::from_xy(1.0, 0.0),
+            Point::from_xy(-1.0, 0.0),
+            1.0,
+        );
+        assert_eq!(
+            curve,
+            Ok([
+                Point::from_xy(0.0, 0.0),
+                Point::from_xy(1.0, 0.0),
+                Point::from_xy(2.0, 0.0),
+                Point::from_xy(3.0, 0.0),
+            ])
+        );
+    }
+}
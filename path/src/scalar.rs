@@ -93,6 +93,7 @@ pub trait NoStdFloat {
     fn round(self) -> Self;
     fn powf(self, y: Self) -> Self;
     fn acos(self) -> Self;
+    fn log10(self) -> Self;
 }
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
@@ -127,6 +128,9 @@ impl NoStdFloat for f32 {
     fn acos(self) -> Self {
         libm::acosf(self)
     }
+    fn log10(self) -> Self {
+        libm::log10f(self)
+    }
 }
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
@@ -161,6 +165,9 @@ impl NoStdFloat for f64 {
     fn acos(self) -> Self {
         libm::acos(self)
     }
+    fn log10(self) -> Self {
+        libm::log10(self)
+    }
 }
 
 #[cfg(test)]
@@ -6,13 +6,16 @@
 
 // Based on SkStroke.cpp
 
+use alloc::vec::Vec;
+
 use crate::{Path, Point, Transform};
 
 use crate::dash::StrokeDash;
 use crate::floating_point::{NonZeroPositiveF32, NormalizedF32, NormalizedF32Exclusive};
-use crate::path::{PathSegment, PathSegmentsIter};
+use crate::path::{PathSegment, PathSegmentsIter, PathVerb};
 use crate::path_builder::{PathBuilder, PathDirection};
 use crate::path_geometry;
+use crate::polyline::{flatten_cubic, flatten_quad};
 use crate::scalar::{Scalar, SCALAR_NEARLY_ZERO, SCALAR_ROOT_2_OVER_2};
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
@@ -65,6 +68,14 @@ pub struct Stroke {
     ///
     /// Default: None
     pub dash: Option<StrokeDash>,
+
+    /// A stroke position relative to the path.
+    ///
+    /// Affects only closed contours. Open ones are always centered.
+    /// Hairline strokes, i.e. ones with a zero width, are always centered as well.
+    ///
+    /// Default: Center
+    pub alignment: StrokeAlignment,
 }
 
 impl Default for Stroke {
@@ -75,6 +86,7 @@ impl Default for Stroke {
             line_cap: LineCap::default(),
             line_join: LineJoin::default(),
             dash: None,
+            alignment: StrokeAlignment::default(),
         }
     }
 }
@@ -116,6 +128,51 @@ pub enum LineJoin {
     Bevel,
 }
 
+/// A stroke position relative to the path.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub enum StrokeAlignment {
+    /// The stroke is centered on the path.
+    #[default]
+    Center,
+    /// The stroke is inside the contour.
+    ///
+    /// The whole stroke width is used.
+    Inside,
+    /// The stroke is outside the contour.
+    ///
+    /// The whole stroke width is used.
+    Outside,
+}
+
+/// A stroke width along a path.
+///
+/// Used by [`Path::stroke_variable`].
+#[derive(Copy, Clone)]
+pub enum WidthProfile<'a> {
+    /// A width per path vertex.
+    ///
+    /// Each `MoveTo`, `LineTo`, `QuadTo` and `CubicTo` segment end point is a vertex,
+    /// therefore the number of widths must be equal to the number of such segments.
+    ///
+    /// Widths are interpolated linearly along segments.
+    /// A closing segment is interpolated between the last and the first contour vertices.
+    Vertices(&'a [f32]),
+
+    /// A width as a function of a normalized distance along a contour.
+    ///
+    /// The argument is in a 0..=1 range, where 0 is the contour start and 1 is the contour end.
+    Length(&'a dyn Fn(f32) -> f32),
+}
+
+impl core::fmt::Debug for WidthProfile<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WidthProfile::Vertices(widths) => f.debug_tuple("Vertices").field(widths).finish(),
+            WidthProfile::Length(_) => f.debug_tuple("Length").finish(),
+        }
+    }
+}
+
 const QUAD_RECURSIVE_LIMIT: usize = 3;
 
 // quads with extreme widths (e.g. (0,1) (1,6) (0,3) width=5e7) recurse to point of failure
@@ -158,6 +215,14 @@ enum StrokeType {
     Inner = -1,
 }
 
+/// Contour sides produced by the stroker.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum ContourSide {
+    Both,
+    Outer, // the side the normals are pointing to
+    Inner,
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 enum ResultType {
     Split,      // the caller should split the quad stroke in two
@@ -172,7 +237,7 @@ enum IntersectRayType {
 }
 
 impl Path {
-    /// Returns a stoked path.
+    /// Returns a stroked path.
     ///
     /// `resolution_scale` can be obtained via
     /// [`compute_resolution_scale`](PathStroker::compute_resolution_scale).
@@ -183,6 +248,23 @@ impl Path {
     pub fn stroke(&self, stroke: &Stroke, resolution_scale: f32) -> Option<Path> {
         PathStroker::new().stroke(self, stroke, resolution_scale)
     }
+
+    /// Returns a stroked path with a variable width.
+    ///
+    /// `Stroke::width` and `Stroke::dash` are ignored.
+    /// The stroke is always centered, i.e. `Stroke::alignment` is ignored as well.
+    /// Curves are stroked with curves, like in [`Path::stroke`].
+    ///
+    /// Returns `None` when the width profile doesn't match the path
+    /// or contains negative or non-finite widths.
+    pub fn stroke_variable(
+        &self,
+        stroke: &Stroke,
+        profile: WidthProfile,
+        resolution_scale: f32,
+    ) -> Option<Path> {
+        PathStroker::new().stroke_variable(self, stroke, profile, resolution_scale)
+    }
 }
 
 /// A path stroker.
@@ -206,6 +288,7 @@ pub struct PathStroker {
 
     first_outer_pt: Point,
     first_outer_pt_index_in_contour: usize,
+    first_outer_verb_index_in_contour: usize,
     segment_count: i32,
     prev_is_line: bool,
 
@@ -216,6 +299,11 @@ pub struct PathStroker {
     inner: PathBuilder,
     outer: PathBuilder,
     cusper: PathBuilder,
+    // the current contour itself, for one-sided strokes
+    center: PathBuilder,
+    contour_side: ContourSide,
+    // radii sampled at uniform `t` steps along the current segment, for variable-width strokes
+    radii: Vec<f32>,
 
     stroke_type: StrokeType,
 
@@ -250,6 +338,7 @@ impl PathStroker {
 
             first_outer_pt: Point::zero(),
             first_outer_pt_index_in_contour: 0,
+            first_outer_verb_index_in_contour: 0,
             segment_count: -1,
             prev_is_line: false,
 
@@ -259,6 +348,9 @@ impl PathStroker {
             inner: PathBuilder::new(),
             outer: PathBuilder::new(),
             cusper: PathBuilder::new(),
+            center: PathBuilder::new(),
+            contour_side: ContourSide::Both,
+            radii: Vec::new(),
 
             stroke_type: StrokeType::Outer,
 
@@ -290,7 +382,7 @@ impl PathStroker {
         1.0
     }
 
-    /// Stokes the path.
+    /// Strokes the path.
     ///
     /// Can be called multiple times to reuse allocated buffers.
     ///
@@ -304,21 +396,201 @@ impl PathStroker {
             stroke.miter_limit,
             stroke.line_cap,
             stroke.line_join,
+            stroke.alignment,
             resolution_scale,
         )
     }
 
+    /// Strokes the path with a variable width.
+    ///
+    /// Can be called multiple times to reuse allocated buffers.
+    ///
+    /// See [`Path::stroke_variable`] for details.
+    pub fn stroke_variable(
+        &mut self,
+        path: &Path,
+        stroke: &Stroke,
+        profile: WidthProfile,
+        resolution_scale: f32,
+    ) -> Option<Path> {
+        if let WidthProfile::Vertices(widths) = profile {
+            let vertices = path.verbs.iter().filter(|v| **v != PathVerb::Close).count();
+            if widths.len() != vertices {
+                return None;
+            }
+        }
+
+        self.init(
+            path,
+            0.0,
+            stroke.miter_limit,
+            stroke.line_cap,
+            stroke.line_join,
+            resolution_scale,
+        );
+
+        let (radii, offsets) = variable_radii(path, profile, self.inv_res_scale)?;
+        let mut offsets = offsets.windows(2);
+
+        let mut last_segment_is_line = false;
+        let mut iter = path.segments();
+        iter.set_auto_close(true);
+        while let Some(segment) = iter.next() {
+            if segment != PathSegment::Close {
+                let range = offsets.next()?;
+                self.radii.clear();
+                self.radii.extend_from_slice(&radii[range[0]..range[1]]);
+                self.radius = self.radii[0];
+            }
+
+            match segment {
+                PathSegment::MoveTo(p) => {
+                    self.move_to(p);
+                }
+                PathSegment::LineTo(p) => {
+                    self.line_to(p, Some(&iter));
+                    last_segment_is_line = true;
+                }
+                PathSegment::QuadTo(p1, p2) => {
+                    self.quad_to(p1, p2);
+                    last_segment_is_line = false;
+                }
+                PathSegment::CubicTo(p1, p2, p3) => {
+                    self.cubic_to(p1, p2, p3);
+                    last_segment_is_line = false;
+                }
+                PathSegment::Close => {
+                    // The closing join and zero-length contours use the contour end radius.
+                    self.radii.clear();
+
+                    if stroke.line_cap != LineCap::Butt {
+                        if self.has_only_move_to() {
+                            self.line_to(self.move_to_pt(), None);
+                            last_segment_is_line = true;
+                            continue;
+                        }
+
+                        if self.is_current_contour_empty() {
+                            last_segment_is_line = true;
+                            continue;
+                        }
+                    }
+
+                    self.close(last_segment_is_line);
+                }
+            }
+
+            self.radius = self.end_radius();
+        }
+
+        self.radii.clear();
+        self.finish(last_segment_is_line)
+    }
+
     fn stroke_inner(
         &mut self,
         path: &Path,
         width: NonZeroPositiveF32,
         miter_limit: f32,
         line_cap: LineCap,
-        mut line_join: LineJoin,
+        line_join: LineJoin,
+        alignment: StrokeAlignment,
         res_scale: f32,
     ) -> Option<Path> {
         // TODO: stroke_rect optimization
 
+        self.init(
+            path,
+            width.get(),
+            miter_limit,
+            line_cap,
+            line_join,
+            res_scale,
+        );
+
+        let sides = contour_sides(path, alignment);
+        let mut sides = sides.iter();
+
+        let mut last_segment_is_line = false;
+        let mut iter = path.segments();
+        iter.set_auto_close(true);
+        while let Some(segment) = iter.next() {
+            match segment {
+                PathSegment::MoveTo(p) => {
+                    self.move_to(p);
+
+                    if let Some(side) = sides.next() {
+                        // One-sided strokes are produced by stroking with a doubled width
+                        // and replacing the other side with the contour itself.
+                        self.contour_side = *side;
+                        self.radius = if *side == ContourSide::Both {
+                            width.get().half()
+                        } else {
+                            width.get()
+                        };
+
+                        self.center.clear();
+                        self.center.move_to(p.x, p.y);
+                    }
+                }
+                PathSegment::LineTo(p) => {
+                    self.line_to(p, Some(&iter));
+                    if self.contour_side != ContourSide::Both {
+                        self.center.line_to(p.x, p.y);
+                    }
+                    last_segment_is_line = true;
+                }
+                PathSegment::QuadTo(p1, p2) => {
+                    self.quad_to(p1, p2);
+                    if self.contour_side != ContourSide::Both {
+                        self.center.quad_to(p1.x, p1.y, p2.x, p2.y);
+                    }
+                    last_segment_is_line = false;
+                }
+                PathSegment::CubicTo(p1, p2, p3) => {
+                    self.cubic_to(p1, p2, p3);
+                    if self.contour_side != ContourSide::Both {
+                        self.center.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+                    }
+                    last_segment_is_line = false;
+                }
+                PathSegment::Close => {
+                    if line_cap != LineCap::Butt {
+                        // If the stroke consists of a moveTo followed by a close, treat it
+                        // as if it were followed by a zero-length line. Lines without length
+                        // can have square and round end caps.
+                        if self.has_only_move_to() {
+                            self.line_to(self.move_to_pt(), None);
+                            last_segment_is_line = true;
+                            continue;
+                        }
+
+                        // If the stroke consists of a moveTo followed by one or more zero-length
+                        // verbs, then followed by a close, treat is as if it were followed by a
+                        // zero-length line. Lines without length can have square & round end caps.
+                        if self.is_current_contour_empty() {
+                            last_segment_is_line = true;
+                            continue;
+                        }
+                    }
+
+                    self.close(last_segment_is_line);
+                }
+            }
+        }
+
+        self.finish(last_segment_is_line)
+    }
+
+    fn init(
+        &mut self,
+        path: &Path,
+        width: f32,
+        miter_limit: f32,
+        line_cap: LineCap,
+        mut line_join: LineJoin,
+        res_scale: f32,
+    ) {
         let mut inv_miter_limit = 0.0;
 
         if line_join == LineJoin::Miter {
@@ -338,7 +610,7 @@ impl PathStroker {
         self.inv_res_scale = (res_scale * 4.0).invert();
         self.inv_res_scale_squared = self.inv_res_scale.sqr();
 
-        self.radius = width.get().half();
+        self.radius = width.half();
         self.inv_miter_limit = inv_miter_limit;
 
         self.first_normal = Point::zero();
@@ -351,6 +623,7 @@ impl PathStroker {
 
         self.first_outer_pt = Point::zero();
         self.first_outer_pt_index_in_contour = 0;
+        self.first_outer_verb_index_in_contour = 0;
         self.segment_count = -1;
         self.prev_is_line = false;
 
@@ -371,59 +644,15 @@ impl PathStroker {
             .reserve(path.verbs.len() * 3, path.points.len() * 3);
 
         self.cusper.clear();
+        self.center.clear();
+        self.contour_side = ContourSide::Both;
+        self.radii.clear();
 
         self.stroke_type = StrokeType::Outer;
 
         self.recursion_depth = 0;
         self.found_tangents = false;
         self.join_completed = false;
-
-        let mut last_segment_is_line = false;
-        let mut iter = path.segments();
-        iter.set_auto_close(true);
-        while let Some(segment) = iter.next() {
-            match segment {
-                PathSegment::MoveTo(p) => {
-                    self.move_to(p);
-                }
-                PathSegment::LineTo(p) => {
-                    self.line_to(p, Some(&iter));
-                    last_segment_is_line = true;
-                }
-                PathSegment::QuadTo(p1, p2) => {
-                    self.quad_to(p1, p2);
-                    last_segment_is_line = false;
-                }
-                PathSegment::CubicTo(p1, p2, p3) => {
-                    self.cubic_to(p1, p2, p3);
-                    last_segment_is_line = false;
-                }
-                PathSegment::Close => {
-                    if line_cap != LineCap::Butt {
-                        // If the stroke consists of a moveTo followed by a close, treat it
-                        // as if it were followed by a zero-length line. Lines without length
-                        // can have square and round end caps.
-                        if self.has_only_move_to() {
-                            self.line_to(self.move_to_pt(), None);
-                            last_segment_is_line = true;
-                            continue;
-                        }
-
-                        // If the stroke consists of a moveTo followed by one or more zero-length
-                        // verbs, then followed by a close, treat is as if it were followed by a
-                        // zero-length line. Lines without length can have square & round end caps.
-                        if self.is_current_contour_empty() {
-                            last_segment_is_line = true;
-                            continue;
-                        }
-                    }
-
-                    self.close(last_segment_is_line);
-                }
-            }
-        }
-
-        self.finish(last_segment_is_line)
     }
 
     fn builders(&mut self) -> SwappableBuilders<'_> {
@@ -467,6 +696,18 @@ impl PathStroker {
             return;
         }
 
+        // Variable-width lines are offset at each radius sample.
+        let last = self.radii.len().saturating_sub(1);
+        for i in 1..last {
+            let mid = self.prev_pt + (p - self.prev_pt).scaled(i as f32 / last as f32);
+            let mid_normal = unit_normal.scaled(self.radii[i]);
+            self.outer
+                .line_to(mid.x + mid_normal.x, mid.y + mid_normal.y);
+            self.inner
+                .line_to(mid.x - mid_normal.x, mid.y - mid_normal.y);
+        }
+
+        let normal = unit_normal.scaled(self.end_radius());
         self.outer.line_to(p.x + normal.x, p.y + normal.y);
         self.inner.line_to(p.x - normal.x, p.y - normal.y);
 
@@ -490,11 +731,7 @@ impl PathStroker {
         }
 
         if reduction_type == ReductionType::Degenerate {
-            self.line_to(reduction, None);
-            let save_joiner = self.joiner;
-            self.joiner = round_joiner;
-            self.line_to(p2, None);
-            self.joiner = save_joiner;
+            self.degenerate_lines_to(&[reduction, p2]);
             return;
         }
 
@@ -529,7 +766,7 @@ impl PathStroker {
             quad[1],
             quad[2],
             self.res_scale,
-            self.radius,
+            self.end_radius(),
             &mut normal_bc,
             &mut unit_bc,
         );
@@ -541,6 +778,37 @@ impl PathStroker {
         self.post_join_to(p2, normal_bc, unit_bc);
     }
 
+    // Strokes a curve that is degenerate to lines, with round joins between them.
+    fn degenerate_lines_to(&mut self, points: &[Point]) {
+        // A variable radius is split between the lines according to their lengths.
+        let radii = core::mem::take(&mut self.radii);
+        let mut total = 0.0;
+        let mut prev = self.prev_pt;
+        for p in points {
+            total += prev.distance(*p);
+            prev = *p;
+        }
+
+        let save_joiner = self.joiner;
+        let mut distance = 0.0;
+        for p in points {
+            if !radii.is_empty() && total > 0.0 {
+                let start = radius_at(&radii, distance / total);
+                distance += self.prev_pt.distance(*p);
+                let end = radius_at(&radii, distance / total);
+                self.radii.clear();
+                self.radii.extend_from_slice(&[start, end]);
+                self.radius = start;
+            }
+
+            self.line_to(*p, None);
+            self.joiner = round_joiner;
+        }
+
+        self.joiner = save_joiner;
+        self.radii = radii;
+    }
+
     fn cubic_to(&mut self, pt1: Point, pt2: Point, pt3: Point) {
         let cubic = [self.prev_pt, pt1, pt2, pt3];
         let mut reduction = [Point::zero(); 3];
@@ -562,19 +830,15 @@ impl PathStroker {
         if ReductionType::Degenerate <= reduction_type
             && ReductionType::Degenerate3 >= reduction_type
         {
-            self.line_to(reduction[0], None);
-            let save_joiner = self.joiner;
-            self.joiner = round_joiner;
-            if ReductionType::Degenerate2 <= reduction_type {
-                self.line_to(reduction[1], None);
-            }
-
-            if ReductionType::Degenerate3 == reduction_type {
-                self.line_to(reduction[2], None);
-            }
+            let count = match reduction_type {
+                ReductionType::Degenerate => 1,
+                ReductionType::Degenerate2 => 2,
+                _ => 3,
+            };
 
-            self.line_to(pt3, None);
-            self.joiner = save_joiner;
+            let mut points = [reduction[0], reduction[1], reduction[2], pt3];
+            points[count] = pt3;
+            self.degenerate_lines_to(&points[..=count]);
             return;
         }
 
@@ -608,7 +872,8 @@ impl PathStroker {
 
         if let Some(cusp) = path_geometry::find_cubic_cusp(&cubic) {
             let cusp_loc = path_geometry::eval_cubic_pos_at(&cubic, cusp.to_normalized());
-            self.cusper.push_circle(cusp_loc.x, cusp_loc.y, self.radius);
+            let radius = self.radius_at(cusp.to_normalized());
+            self.cusper.push_circle(cusp_loc.x, cusp_loc.y, radius);
         }
 
         // emit the join even if one stroke succeeded but the last one failed
@@ -746,7 +1011,9 @@ impl PathStroker {
             }
         }
 
-        self.set_ray_points(*t_pt, &mut dxy, on_pt, tangent);
+        // The cubic tangent is a third of the derivative.
+        let slope = self.radius_slope_at(t, dxy.length() * 3.0);
+        self.set_ray_points(*t_pt, &mut dxy, self.radius_at(t), slope, on_pt, tangent);
     }
 
     fn set_cubic_end_normal(
@@ -785,7 +1052,7 @@ impl PathStroker {
             return;
         }
 
-        let res = set_normal_unit_normal2(cd, self.radius, normal_cd, unit_normal_cd);
+        let res = set_normal_unit_normal2(cd, self.end_radius(), normal_cd, unit_normal_cd);
         debug_assert!(res);
     }
 
@@ -855,11 +1122,39 @@ impl PathStroker {
                 );
                 self.outer.close();
 
-                // now add inner as its own contour
-                let pt = self.inner.last_point().unwrap_or_default();
-                self.outer.move_to(pt.x, pt.y);
-                self.outer.reverse_path_to(&self.inner);
-                self.outer.close();
+                match self.contour_side {
+                    ContourSide::Both => {
+                        // now add inner as its own contour
+                        let pt = self.inner.last_point().unwrap_or_default();
+                        self.outer.move_to(pt.x, pt.y);
+                        self.outer.reverse_path_to(&self.inner);
+                        self.outer.close();
+                    }
+                    ContourSide::Outer => {
+                        // the contour itself replaces the inner side
+                        let pt = self.center.last_point().unwrap_or_default();
+                        self.outer.move_to(pt.x, pt.y);
+                        self.outer.reverse_path_to(&self.center);
+                        self.outer.close();
+                    }
+                    ContourSide::Inner => {
+                        // the contour itself replaces the outer side
+                        self.outer
+                            .points
+                            .truncate(self.first_outer_pt_index_in_contour);
+                        self.outer
+                            .verbs
+                            .truncate(self.first_outer_verb_index_in_contour);
+
+                        let pt = self.inner.last_point().unwrap_or_default();
+                        self.outer.move_to(pt.x, pt.y);
+                        self.outer.reverse_path_to(&self.inner);
+                        self.outer.close();
+
+                        self.outer.push_path_builder(&self.center);
+                        self.outer.close();
+                    }
+                }
             } else {
                 // add caps to start and end
 
@@ -906,6 +1201,7 @@ impl PathStroker {
         self.inner.clear();
         self.segment_count = -1;
         self.first_outer_pt_index_in_contour = self.outer.points.len();
+        self.first_outer_verb_index_in_contour = self.outer.verbs.len();
     }
 
     fn pre_join_to(
@@ -1073,15 +1369,20 @@ impl PathStroker {
 
     // Given a point on the curve and its derivative, scale the derivative by the radius, and
     // compute the perpendicular point and its tangent.
+    //
+    // `slope` is the radius change per curve length unit, which turns the tangent
+    // of a variable-width stroke away from the curve direction.
     fn set_ray_points(
         &self,
         tp: Point,
         dxy: &mut Point,
+        radius: f32,
+        slope: f32,
         on_p: &mut Point,
         mut tangent: Option<&mut Point>,
     ) {
-        if !dxy.set_length(self.radius) {
-            *dxy = Point::from_xy(self.radius, 0.0);
+        if !dxy.set_length(radius) {
+            *dxy = Point::from_xy(radius, 0.0);
         }
 
         let axis_flip = self.stroke_type as i32 as f32; // go opposite ways for outer, inner
@@ -1091,9 +1392,40 @@ impl PathStroker {
         if let Some(ref mut tangent) = tangent {
             tangent.x = on_p.x + dxy.x;
             tangent.y = on_p.y + dxy.y;
+
+            if slope != 0.0 {
+                tangent.x += axis_flip * dxy.y * slope;
+                tangent.y -= axis_flip * dxy.x * slope;
+            }
+        }
+    }
+
+    // Returns the radius at `t` along the current segment.
+    fn radius_at(&self, t: NormalizedF32) -> f32 {
+        if self.radii.is_empty() {
+            self.radius
+        } else {
+            radius_at(&self.radii, t.get())
         }
     }
 
+    // Returns the radius at the end of the current segment.
+    fn end_radius(&self) -> f32 {
+        self.radii.last().copied().unwrap_or(self.radius)
+    }
+
+    // Returns the radius change per curve length unit at `t` along the current segment,
+    // where `speed` is the curve derivative length.
+    fn radius_slope_at(&self, t: NormalizedF32, speed: f32) -> f32 {
+        if self.radii.len() < 2 || !(speed > 0.0) {
+            return 0.0;
+        }
+
+        let last = self.radii.len() - 1;
+        let i = ((t.get() * last as f32) as usize).min(last - 1);
+        (self.radii[i + 1] - self.radii[i]) * last as f32 / speed
+    }
+
     // Given a quad and t, return the point on curve,
     // its perpendicular, and the perpendicular tangent.
     fn quad_perp_ray(
@@ -1111,7 +1443,8 @@ impl PathStroker {
             dxy = quad[2] - quad[0];
         }
 
-        self.set_ray_points(*tp, &mut dxy, on_p, tangent);
+        let slope = self.radius_slope_at(t, dxy.length());
+        self.set_ray_points(*tp, &mut dxy, self.radius_at(t), slope, on_p, tangent);
     }
 
     fn add_degenerate_line(&mut self, quad_points: &QuadConstruct) {
@@ -1264,6 +1597,249 @@ impl PathStroker {
     }
 }
 
+/// Returns stroke sides for each path contour.
+///
+/// Returns an empty list for centered strokes.
+fn contour_sides(path: &Path, alignment: StrokeAlignment) -> Vec<ContourSide> {
+    if alignment == StrokeAlignment::Center {
+        return Vec::new();
+    }
+
+    let mut sides = Vec::new();
+    let mut push_contour = |contour: &[Point], closed: bool| {
+        if !closed {
+            sides.push(ContourSide::Both);
+            return;
+        }
+
+        // A signed area of the control polygon is enough to tell the direction.
+        let mut area = 0.0;
+        for (i, p0) in contour.iter().enumerate() {
+            let p1 = contour[(i + 1) % contour.len()];
+            area += p0.cross(p1);
+        }
+
+        // Normals are pointing outside of clockwise contours.
+        let is_clockwise = area > 0.0;
+        sides.push(match (alignment, is_clockwise) {
+            (StrokeAlignment::Outside, true) | (StrokeAlignment::Inside, false) => {
+                ContourSide::Outer
+            }
+            _ => ContourSide::Inner,
+        });
+    };
+
+    let mut points = path.points.iter().copied();
+    let mut contour = Vec::new();
+    for verb in &path.verbs {
+        match verb {
+            PathVerb::Move => {
+                if !contour.is_empty() {
+                    push_contour(&contour, false);
+                }
+
+                contour.clear();
+                contour.extend(points.next());
+            }
+            PathVerb::Line => contour.extend(points.next()),
+            PathVerb::Quad => contour.extend(points.by_ref().take(2)),
+            PathVerb::Cubic => contour.extend(points.by_ref().take(3)),
+            PathVerb::Close => {
+                push_contour(&contour, true);
+                contour.clear();
+            }
+        }
+    }
+
+    if !contour.is_empty() {
+        push_contour(&contour, false);
+    }
+
+    sides
+}
+
+/// Linearly interpolates radii sampled at uniform `t` steps.
+fn radius_at(radii: &[f32], t: f32) -> f32 {
+    if radii.len() < 2 {
+        return radii[0];
+    }
+
+    let last = radii.len() - 1;
+    let x = t * last as f32;
+    let i = (x as usize).min(last - 1);
+    radii[i] + (radii[i + 1] - radii[i]) * (x - i as f32)
+}
+
+/// The maximum number of parts a line is split into when sampling a length-based width profile.
+const LINE_PROFILE_SAMPLES: usize = 64;
+
+/// Computes radii along path segments for a variable-width stroke.
+///
+/// Each segment of an auto-closed path, except `Close`, is sampled at uniform `t` steps,
+/// both ends included. Radii of the segment `i` are stored at
+/// `radii[offsets[i]..offsets[i + 1]]`.
+///
+/// Returns `None` when the profile doesn't match the path or produces invalid widths.
+fn variable_radii(
+    path: &Path,
+    profile: WidthProfile,
+    tolerance: f32,
+) -> Option<(Vec<f32>, Vec<usize>)> {
+    let mut radii = VariableRadii {
+        radii: Vec::new(),
+        offsets: alloc::vec![0],
+    };
+
+    let mut contour = Vec::new();
+    let mut first_vertex = 0;
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(_) => {
+                if !contour.is_empty() {
+                    radii.push_contour(&contour, false, first_vertex, profile, tolerance)?;
+                    first_vertex += contour.len();
+                }
+
+                contour.clear();
+                contour.push(segment);
+            }
+            PathSegment::Close => {
+                radii.push_contour(&contour, true, first_vertex, profile, tolerance)?;
+                first_vertex += contour.len();
+                contour.clear();
+            }
+            _ => contour.push(segment),
+        }
+    }
+
+    if !contour.is_empty() {
+        radii.push_contour(&contour, false, first_vertex, profile, tolerance)?;
+    }
+
+    if !radii.radii.iter().all(|r| r.is_finite() && *r >= 0.0) {
+        return None;
+    }
+
+    Some((radii.radii, radii.offsets))
+}
+
+struct VariableRadii {
+    radii: Vec<f32>,
+    offsets: Vec<usize>,
+}
+
+impl VariableRadii {
+    fn push_contour(
+        &mut self,
+        contour: &[PathSegment],
+        closed: bool,
+        first_vertex: usize,
+        profile: WidthProfile,
+        tolerance: f32,
+    ) -> Option<()> {
+        // Sample segments at uniform `t` steps, the same way they are flattened.
+        let mut points = Vec::new();
+        let mut ends = Vec::with_capacity(contour.len() + 1);
+        for segment in contour {
+            let last = points.last().copied().unwrap_or_default();
+            match *segment {
+                PathSegment::MoveTo(p) | PathSegment::LineTo(p) => points.push(p),
+                PathSegment::QuadTo(p1, p2) => {
+                    flatten_quad(&[last, p1, p2], tolerance, &mut points)
+                }
+                PathSegment::CubicTo(p1, p2, p3) => {
+                    flatten_cubic(&[last, p1, p2, p3], tolerance, &mut points)
+                }
+                PathSegment::Close => {}
+            }
+
+            ends.push(points.len() - 1);
+        }
+
+        // The same closing line as emitted by an auto-closing segments iterator.
+        let vertices = ends.len();
+        if closed && points.last() != points.first() {
+            points.push(points[0]);
+            ends.push(points.len() - 1);
+        }
+
+        let mut distances = Vec::with_capacity(points.len());
+        let mut distance = 0.0;
+        distances.push(0.0);
+        for pair in points.windows(2) {
+            distance += pair[0].distance(pair[1]);
+            distances.push(distance);
+        }
+
+        let mut start = 0;
+        for (i, end) in ends.iter().copied().enumerate() {
+            let samples = &distances[start..=end];
+            let d0 = samples[0];
+            let len = samples[samples.len() - 1] - d0;
+            match profile {
+                WidthProfile::Vertices(widths) => {
+                    // The closing line ends at the first vertex.
+                    let start_width = *widths.get(first_vertex + i.saturating_sub(1))?;
+                    let end_width = *widths.get(first_vertex + i % vertices)?;
+                    let last = samples.len() - 1;
+                    for (j, d) in samples.iter().enumerate() {
+                        let t = if len > 0.0 {
+                            (d - d0) / len
+                        } else if last > 0 {
+                            j as f32 / last as f32
+                        } else {
+                            1.0
+                        };
+
+                        self.radii
+                            .push((start_width + (end_width - start_width) * t).half());
+                    }
+                }
+                WidthProfile::Length(func) => {
+                    let width_at = |d: f32| {
+                        let t = if distance > 0.0 { d / distance } else { 0.0 };
+                        func(t.min(1.0)).half()
+                    };
+
+                    let is_line = !matches!(
+                        contour.get(i),
+                        Some(PathSegment::MoveTo(_))
+                            | Some(PathSegment::QuadTo(..))
+                            | Some(PathSegment::CubicTo(..))
+                    );
+                    if is_line {
+                        // Lines have no samples of their own, therefore they are split
+                        // according to their share of the contour length.
+                        let n = (len / distance * LINE_PROFILE_SAMPLES as f32).ceil();
+                        let n = if n.is_finite() {
+                            (n as usize).clamp(1, LINE_PROFILE_SAMPLES)
+                        } else {
+                            1
+                        };
+
+                        let first = self.radii.len();
+                        for j in 0..=n {
+                            self.radii.push(width_at(d0 + len * (j as f32 / n as f32)));
+                        }
+
+                        // A constant width doesn't need intermediate samples.
+                        if self.radii[first..].iter().all(|r| *r == self.radii[first]) {
+                            self.radii.truncate(first + 2);
+                        }
+                    } else {
+                        self.radii.extend(samples.iter().map(|d| width_at(*d)));
+                    }
+                }
+            }
+
+            self.offsets.push(self.radii.len());
+            start = end;
+        }
+
+        Some(())
+    }
+}
+
 fn cap_factory(cap: LineCap) -> CapProc {
     match cap {
         LineCap::Butt => butt_capper,
@@ -0,0 +1,406 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use tiny_skia_path::{IntRect, Transform};
+
+use crate::png_format::{
+    frame_to_pixmap, make_custom_png_encoding_error, make_custom_png_error, setup_encoder,
+    TransferFunction,
+};
+use crate::{BlendMode, Clip, PixmapPaint, PngDecodeOptions, PngEncodeOptions};
+use crate::{PixelFormat, Pixmap, PixmapRef};
+
+/// Specifies how the frame area should be disposed before rendering the next frame.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum ApngDisposeOp {
+    /// Leaves the frame as is.
+    #[default]
+    None,
+    /// Clears the frame area to transparent black.
+    Background,
+    /// Reverts the frame area to its state before the current frame.
+    Previous,
+}
+
+/// Specifies how the frame should be blended with the previous one.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum ApngBlendOp {
+    /// Replaces the frame area.
+    #[default]
+    Source,
+    /// Composites the frame over the frame area.
+    Over,
+}
+
+/// An animation frame.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ApngFrame {
+    /// A fully composited frame image.
+    ///
+    /// All frames must have the same size.
+    pub pixmap: Pixmap,
+
+    /// Frame delay numerator.
+    pub delay_num: u16,
+
+    /// Frame delay denominator.
+    ///
+    /// The delay is `delay_num / delay_den` seconds.
+    /// Zero denominator is treated as 100.
+    pub delay_den: u16,
+
+    /// Frame dispose operation.
+    pub dispose_op: ApngDisposeOp,
+
+    /// Frame blend operation.
+    ///
+    /// When encoding, `Over` is used only when the frame can be represented exactly.
+    /// Otherwise, it will fallback to `Source`.
+    pub blend_op: ApngBlendOp,
+}
+
+impl ApngFrame {
+    /// Creates a new frame with the specified delay and default operations.
+    pub fn new(pixmap: Pixmap, delay_num: u16, delay_den: u16) -> Self {
+        ApngFrame {
+            pixmap,
+            delay_num,
+            delay_den,
+            dispose_op: ApngDisposeOp::None,
+            blend_op: ApngBlendOp::Source,
+        }
+    }
+}
+
+/// An animated PNG.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct Apng {
+    /// A list of frames.
+    pub frames: Vec<ApngFrame>,
+
+    /// Number of times the animation should be played. Zero means infinite.
+    pub plays: u32,
+}
+
+impl Apng {
+    /// Decodes an APNG data.
+    ///
+    /// Frames will be composited into full images.
+    /// The default image will be ignored, unless it's a part of the animation.
+    ///
+    /// A non-animated PNG is decoded as a single frame.
+    pub fn decode(data: &[u8]) -> Result<Self, png::DecodingError> {
+        Self::decode_with(data, &PngDecodeOptions::default())
+    }
+
+    /// Decodes an APNG data using the specified options.
+    ///
+    /// `PngDecodeOptions::keep_16_bit` is ignored.
+    pub fn decode_with(
+        data: &[u8],
+        options: &PngDecodeOptions,
+    ) -> Result<Self, png::DecodingError> {
+        let mut decoder = png::Decoder::new(std::io::BufReader::new(std::io::Cursor::new(data)));
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let mut reader = decoder.read_info()?;
+
+        let transfer = if options.color_management {
+            TransferFunction::from_info(reader.info())
+        } else {
+            None
+        };
+
+        let output_buffer_size = reader
+            .output_buffer_size()
+            .ok_or(png::DecodingError::LimitsExceeded)?;
+        let mut buf = vec![0; output_buffer_size];
+
+        let (num_frames, plays) = match reader.info().animation_control {
+            Some(actl) => (actl.num_frames, actl.num_plays),
+            None => {
+                let info = reader.next_frame(&mut buf)?;
+                buf.truncate(info.buffer_size());
+                let pixmap = frame_to_pixmap(buf, &info, transfer, false)?;
+                return Ok(Apng {
+                    frames: vec![ApngFrame::new(pixmap, 0, 0)],
+                    plays: 0,
+                });
+            }
+        };
+
+        // The default image is not a part of the animation.
+        if reader.info().frame_control.is_none() {
+            reader.next_frame(&mut buf)?;
+        }
+
+        let width = reader.info().width;
+        let height = reader.info().height;
+        let mut canvas = Pixmap::new(width, height)
+            .ok_or_else(|| make_custom_png_error("invalid image size"))?;
+
+        let mut frames = Vec::new();
+        for i in 0..num_frames {
+            let info = reader.next_frame(&mut buf)?;
+            let fctl = reader
+                .info()
+                .frame_control
+                .ok_or_else(|| make_custom_png_error("missing frame control"))?;
+
+            let rect = IntRect::from_xywh(
+                fctl.x_offset as i32,
+                fctl.y_offset as i32,
+                fctl.width,
+                fctl.height,
+            )
+            .filter(|r| r.right() as u32 <= width && r.bottom() as u32 <= height)
+            .ok_or_else(|| make_custom_png_error("invalid frame region"))?;
+
+            let frame_data = buf[..info.buffer_size()].to_vec();
+            let subframe = frame_to_pixmap(frame_data, &info, transfer, false)?;
+
+            let mut dispose_op = match fctl.dispose_op {
+                png::DisposeOp::None => ApngDisposeOp::None,
+                png::DisposeOp::Background => ApngDisposeOp::Background,
+                png::DisposeOp::Previous => ApngDisposeOp::Previous,
+            };
+            let blend_op = match fctl.blend_op {
+                png::BlendOp::Source => ApngBlendOp::Source,
+                png::BlendOp::Over => ApngBlendOp::Over,
+            };
+
+            let previous = match dispose_op {
+                ApngDisposeOp::Previous => canvas.clone_rect(rect),
+                _ => None,
+            };
+
+            let paint = PixmapPaint {
+                blend_mode: match blend_op {
+                    ApngBlendOp::Source => BlendMode::Source,
+                    ApngBlendOp::Over => BlendMode::SourceOver,
+                },
+                ..PixmapPaint::default()
+            };
+            canvas.draw_pixmap(
+                rect.x(),
+                rect.y(),
+                subframe.as_ref(),
+                &paint,
+                Transform::identity(),
+                Clip::None,
+            );
+
+            frames.push(ApngFrame {
+                pixmap: canvas.clone(),
+                delay_num: fctl.delay_num,
+                delay_den: fctl.delay_den,
+                dispose_op,
+                blend_op,
+            });
+
+            // The first frame cannot be reverted to the previous state.
+            if i == 0 && dispose_op == ApngDisposeOp::Previous {
+                dispose_op = ApngDisposeOp::Background;
+            }
+
+            dispose(&mut canvas, rect, dispose_op, previous.as_ref());
+        }
+
+        Ok(Apng { frames, plays })
+    }
+
+    /// Loads an APNG file.
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, png::DecodingError> {
+        let data = std::fs::read(path)?;
+        Self::decode(&data)
+    }
+
+    /// Encodes frames into an APNG data.
+    ///
+    /// Only the changed region of each frame will be stored.
+    pub fn encode(&self) -> Result<Vec<u8>, png::EncodingError> {
+        self.encode_with(&PngEncodeOptions::default())
+    }
+
+    /// Encodes frames into an APNG data using the specified options.
+    ///
+    /// Only the changed region of each frame will be stored.
+    ///
+    /// Frames in other pixel formats will be converted into RGBA first.
+    pub fn encode_with(&self, options: &PngEncodeOptions) -> Result<Vec<u8>, png::EncodingError> {
+        let first = self
+            .frames
+            .first()
+            .ok_or_else(|| make_custom_png_encoding_error("no frames"))?;
+        let (width, height) = (first.pixmap.width(), first.pixmap.height());
+        if self
+            .frames
+            .iter()
+            .any(|f| f.pixmap.width() != width || f.pixmap.height() != height)
+        {
+            return Err(make_custom_png_encoding_error(
+                "frames must have the same size",
+            ));
+        }
+
+        let mut data = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut data, width, height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            setup_encoder(&mut encoder, options)?;
+            encoder.set_animated(self.frames.len() as u32, self.plays)?;
+            let mut writer = encoder.write_header()?;
+
+            // The output buffer is transparent black at the start.
+            let mut canvas = Pixmap::new(width, height).unwrap();
+            for (i, frame) in self.frames.iter().enumerate() {
+                let converted;
+                let pixmap = if frame.pixmap.format() == PixelFormat::Rgba8888 {
+                    frame.pixmap.as_ref()
+                } else {
+                    converted = frame.pixmap.as_ref().to_rgba8888();
+                    converted.as_ref()
+                };
+
+                // The first frame must cover the whole image.
+                let rect = if i == 0 {
+                    first.pixmap.size().to_int_rect(0, 0)
+                } else {
+                    // A frame cannot be empty, so use a single pixel when nothing has changed.
+                    changed_rect(canvas.as_ref(), pixmap)
+                        .unwrap_or_else(|| IntRect::from_xywh(0, 0, 1, 1).unwrap())
+                };
+
+                let (subframe, blend_op) =
+                    make_subframe(canvas.as_ref(), pixmap, frame.blend_op, rect);
+
+                writer.reset_frame_position()?;
+                writer.set_frame_dimension(rect.width(), rect.height())?;
+                writer.set_frame_position(rect.x() as u32, rect.y() as u32)?;
+                writer.set_frame_delay(frame.delay_num, frame.delay_den)?;
+                writer.set_dispose_op(match frame.dispose_op {
+                    ApngDisposeOp::None => png::DisposeOp::None,
+                    ApngDisposeOp::Background => png::DisposeOp::Background,
+                    ApngDisposeOp::Previous => png::DisposeOp::Previous,
+                })?;
+                writer.set_blend_op(match blend_op {
+                    ApngBlendOp::Source => png::BlendOp::Source,
+                    ApngBlendOp::Over => png::BlendOp::Over,
+                })?;
+                writer.write_image_data(&subframe.take_demultiplied())?;
+
+                // Simulate the decoder.
+                let previous = canvas.clone_rect(rect);
+                canvas.as_mut().data_mut().copy_from_slice(pixmap.data());
+
+                let mut dispose_op = frame.dispose_op;
+                if i == 0 && dispose_op == ApngDisposeOp::Previous {
+                    dispose_op = ApngDisposeOp::Background;
+                }
+
+                dispose(&mut canvas, rect, dispose_op, previous.as_ref());
+            }
+
+            writer.finish()?;
+        }
+
+        Ok(data)
+    }
+
+    /// Saves frames as an APNG file.
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), png::EncodingError> {
+        let data = self.encode()?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+}
+
+fn dispose(canvas: &mut Pixmap, rect: IntRect, op: ApngDisposeOp, previous: Option<&Pixmap>) {
+    match op {
+        ApngDisposeOp::None => {}
+        ApngDisposeOp::Background => {
+            let transparent = Pixmap::new(rect.width(), rect.height()).unwrap();
+            copy_rect(canvas, transparent.as_ref(), rect);
+        }
+        ApngDisposeOp::Previous => {
+            if let Some(previous) = previous {
+                copy_rect(canvas, previous.as_ref(), rect);
+            }
+        }
+    }
+}
+
+/// Copies `src` into the `rect` of `dst`. Sizes must match.
+fn copy_rect(dst: &mut Pixmap, src: PixmapRef, rect: IntRect) {
+    let dst_width = dst.width() as usize;
+    let dst_pixels = dst.pixels_mut();
+    let src_pixels = src.pixels();
+    let width = rect.width() as usize;
+    for y in 0..rect.height() as usize {
+        let dst_idx = (rect.y() as usize + y) * dst_width + rect.x() as usize;
+        dst_pixels[dst_idx..dst_idx + width].copy_from_slice(&src_pixels[y * width..][..width]);
+    }
+}
+
+/// Returns a bounding rect of all changed pixels.
+fn changed_rect(a: PixmapRef, b: PixmapRef) -> Option<IntRect> {
+    let width = a.width() as usize;
+    let (a, b) = (a.pixels(), b.pixels());
+
+    let mut left = usize::MAX;
+    let mut top = usize::MAX;
+    let mut right = 0;
+    let mut bottom = 0;
+    for (y, (row_a, row_b)) in a.chunks_exact(width).zip(b.chunks_exact(width)).enumerate() {
+        let first = match row_a.iter().zip(row_b).position(|(a, b)| a != b) {
+            Some(v) => v,
+            None => continue,
+        };
+        // Always succeeds, since there is at least one changed pixel.
+        let last = row_a.iter().zip(row_b).rposition(|(a, b)| a != b).unwrap();
+
+        left = left.min(first);
+        right = right.max(last + 1);
+        top = top.min(y);
+        bottom = y + 1;
+    }
+
+    if left == usize::MAX {
+        return None;
+    }
+
+    IntRect::from_ltrb(left as i32, top as i32, right as i32, bottom as i32)
+}
+
+/// Extracts the frame region that should be stored.
+///
+/// `Over` is used only when it produces the exact result: changed pixels must be opaque.
+/// Unchanged pixels become transparent, which compresses better.
+fn make_subframe(
+    canvas: PixmapRef,
+    pixmap: PixmapRef,
+    blend_op: ApngBlendOp,
+    rect: IntRect,
+) -> (Pixmap, ApngBlendOp) {
+    let subframe = pixmap.clone_rect(rect).unwrap();
+    if blend_op == ApngBlendOp::Source {
+        return (subframe, ApngBlendOp::Source);
+    }
+
+    let previous = canvas.clone_rect(rect).unwrap();
+    let mut over = subframe.clone();
+    for (new, old) in over.pixels_mut().iter_mut().zip(previous.pixels()) {
+        if new == old {
+            *new = crate::PremultipliedColorU8::TRANSPARENT;
+        } else if !new.is_opaque() {
+            return (subframe, ApngBlendOp::Source);
+        }
+    }
+
+    (over, ApngBlendOp::Over)
+}
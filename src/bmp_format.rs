@@ -0,0 +1,295 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Uncompressed 24 and 32-bit BMP encoder and decoder.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+use crate::codec::{Channels, RawImage};
+use crate::{Mask, Pixmap, PixmapRef};
+
+const FILE_HEADER_SIZE: usize = 14;
+const V4_HEADER_SIZE: usize = 108;
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+impl Pixmap {
+    /// Decodes an uncompressed 24 or 32-bit BMP data into a `Pixmap`.
+    ///
+    /// Returns `None` when data is malformed or unsupported.
+    pub fn decode_bmp(data: &[u8]) -> Option<Self> {
+        decode(data)?.into_pixmap()
+    }
+
+    /// Loads an uncompressed 24 or 32-bit BMP file into a `Pixmap`.
+    #[cfg(feature = "std")]
+    pub fn load_bmp<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::decode_bmp(&data).ok_or_else(|| crate::codec::invalid_data_error("invalid BMP"))
+    }
+
+    /// Encodes pixmap into a 32-bit BMP data.
+    ///
+    /// Returns `None` when the image is too big for BMP.
+    pub fn encode_bmp(&self) -> Option<Vec<u8>> {
+        self.as_ref().encode_bmp()
+    }
+
+    /// Saves pixmap as a 32-bit BMP file.
+    #[cfg(feature = "std")]
+    pub fn save_bmp<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.as_ref().save_bmp(path)
+    }
+}
+
+impl PixmapRef<'_> {
+    /// Encodes pixmap into a 32-bit BMP data.
+    ///
+    /// Returns `None` when the image is too big for BMP.
+    pub fn encode_bmp(&self) -> Option<Vec<u8>> {
+        encode(RawImage::from_pixmap(*self))
+    }
+
+    /// Saves pixmap as a 32-bit BMP file.
+    #[cfg(feature = "std")]
+    pub fn save_bmp<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let data = self
+            .encode_bmp()
+            .ok_or_else(|| crate::codec::invalid_data_error("image is too big for BMP"))?;
+        std::fs::write(path, data)
+    }
+}
+
+impl Mask {
+    /// Decodes an uncompressed 24 or 32-bit BMP data into a `Mask`.
+    ///
+    /// Only opaque grayscale images are supported.
+    pub fn decode_bmp(data: &[u8]) -> Option<Self> {
+        decode(data)?.into_mask()
+    }
+
+    /// Loads an uncompressed 24 or 32-bit BMP file into a `Mask`.
+    ///
+    /// Only opaque grayscale images are supported.
+    #[cfg(feature = "std")]
+    pub fn load_bmp<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::decode_bmp(&data).ok_or_else(|| crate::codec::invalid_data_error("invalid BMP"))
+    }
+
+    /// Encodes mask into a 32-bit BMP data.
+    ///
+    /// The mask is stored as an opaque grayscale image.
+    ///
+    /// Returns `None` when the image is too big for BMP.
+    pub fn encode_bmp(&self) -> Option<Vec<u8>> {
+        encode(RawImage::from_mask(self))
+    }
+
+    /// Saves mask as a 32-bit BMP file.
+    #[cfg(feature = "std")]
+    pub fn save_bmp<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let data = self
+            .encode_bmp()
+            .ok_or_else(|| crate::codec::invalid_data_error("image is too big for BMP"))?;
+        std::fs::write(path, data)
+    }
+}
+
+fn encode(image: RawImage) -> Option<Vec<u8>> {
+    let size = image.size;
+    let rgba = image.into_rgba();
+
+    let width = i32::try_from(size.width()).ok()?;
+    let height = i32::try_from(size.height()).ok()?;
+    let offset = FILE_HEADER_SIZE + V4_HEADER_SIZE;
+    let image_size = u32::try_from(rgba.len()).ok()?;
+    let file_size = image_size.checked_add(offset as u32)?;
+
+    let mut data = Vec::with_capacity(file_size as usize);
+
+    // BITMAPFILEHEADER
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&file_size.to_le_bytes());
+    data.extend_from_slice(&[0; 4]); // reserved
+    data.extend_from_slice(&(offset as u32).to_le_bytes());
+
+    // BITMAPV4HEADER
+    data.extend_from_slice(&(V4_HEADER_SIZE as u32).to_le_bytes());
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes()); // bottom-up
+    data.extend_from_slice(&1u16.to_le_bytes()); // planes
+    data.extend_from_slice(&32u16.to_le_bytes()); // bits per pixel
+    data.extend_from_slice(&BI_BITFIELDS.to_le_bytes());
+    data.extend_from_slice(&image_size.to_le_bytes());
+    data.extend_from_slice(&2835i32.to_le_bytes()); // 72 DPI
+    data.extend_from_slice(&2835i32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes()); // colors used
+    data.extend_from_slice(&0u32.to_le_bytes()); // important colors
+    data.extend_from_slice(&0x00ff0000u32.to_le_bytes());
+    data.extend_from_slice(&0x0000ff00u32.to_le_bytes());
+    data.extend_from_slice(&0x000000ffu32.to_le_bytes());
+    data.extend_from_slice(&0xff000000u32.to_le_bytes());
+    data.extend_from_slice(b"BGRs"); // LCS_sRGB
+    data.extend_from_slice(&[0; 36]); // endpoints
+    data.extend_from_slice(&[0; 12]); // gamma
+
+    let row_len = size.width() as usize * 4;
+    for row in rgba.chunks_exact(row_len).rev() {
+        for p in row.chunks_exact(4) {
+            data.extend_from_slice(&[p[2], p[1], p[0], p[3]]);
+        }
+    }
+
+    Some(data)
+}
+
+fn decode(data: &[u8]) -> Option<RawImage> {
+    let u16_at = |pos: usize| -> Option<u16> {
+        let v = data.get(pos..pos + 2)?;
+        Some(u16::from_le_bytes([v[0], v[1]]))
+    };
+    let u32_at = |pos: usize| -> Option<u32> {
+        let v = data.get(pos..pos + 4)?;
+        Some(u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+    };
+
+    if data.get(0..2) != Some(b"BM") {
+        log::warn!("not a BMP image");
+        return None;
+    }
+
+    let offset = u32_at(10)? as usize;
+    let header_size = u32_at(FILE_HEADER_SIZE)? as usize;
+    if header_size < 40 {
+        log::warn!("unsupported BMP header");
+        return None;
+    }
+
+    let width = u32_at(18)? as i32;
+    let height = u32_at(22)? as i32;
+    let bits_per_pixel = u16_at(28)?;
+    let compression = u32_at(30)?;
+
+    if width <= 0 || height == 0 || height == i32::MIN {
+        log::warn!("invalid BMP size");
+        return None;
+    }
+
+    let is_top_down = height < 0;
+    let size = IntSize::from_wh(width as u32, height.unsigned_abs())?;
+
+    // BI_BITFIELDS masks are stored after the header,
+    // unless the header is big enough to contain them.
+    let masks_pos = FILE_HEADER_SIZE + 40;
+    let masks = match (compression, bits_per_pixel) {
+        (BI_RGB, 24) | (BI_RGB, 32) => None,
+        (BI_BITFIELDS, 32) | (BI_ALPHABITFIELDS, 32) => {
+            let has_alpha = compression == BI_ALPHABITFIELDS || header_size >= 56;
+            Some([
+                u32_at(masks_pos)?,
+                u32_at(masks_pos + 4)?,
+                u32_at(masks_pos + 8)?,
+                if has_alpha {
+                    u32_at(masks_pos + 12)?
+                } else {
+                    0
+                },
+            ])
+        }
+        _ => {
+            log::warn!(
+                "unsupported BMP format: {} bits, compression {}",
+                bits_per_pixel,
+                compression
+            );
+            return None;
+        }
+    };
+
+    let bytes_per_pixel = usize::from(bits_per_pixel / 8);
+    let width = size.width() as usize;
+    let height = size.height() as usize;
+    // Rows are aligned to 4 bytes.
+    let row_len = width
+        .checked_mul(bytes_per_pixel)
+        .and_then(|n| n.checked_add(3))
+        .map(|n| n & !3);
+    let data_len = width.checked_mul(height).and_then(|n| n.checked_mul(4));
+    let (row_len, data_len) = match (row_len, data_len) {
+        (Some(row_len), Some(data_len)) => (row_len, data_len),
+        _ => {
+            log::warn!("BMP image is too big");
+            return None;
+        }
+    };
+
+    let pixels = data.get(offset..)?;
+    if pixels.len() / row_len < height {
+        log::warn!("BMP data is too short");
+        return None;
+    }
+
+    let mut rgba = vec![0; data_len];
+    let src_rows = pixels.chunks_exact(row_len).take(height);
+    let mut has_alpha = false;
+    for (i, src_row) in src_rows.enumerate() {
+        let y = if is_top_down { i } else { height - 1 - i };
+        let dst_row = &mut rgba[y * width * 4..][..width * 4];
+        let src = src_row.chunks_exact(bytes_per_pixel);
+        for (dst, src) in dst_row.chunks_exact_mut(4).zip(src) {
+            match masks {
+                Some(masks) => {
+                    let v = u32::from_le_bytes([src[0], src[1], src[2], src[3]]);
+                    for (c, mask) in dst.iter_mut().zip(masks) {
+                        *c = extract_channel(v, mask);
+                    }
+
+                    if masks[3] == 0 {
+                        dst[3] = 255;
+                    }
+                }
+                None => {
+                    dst[0] = src[2];
+                    dst[1] = src[1];
+                    dst[2] = src[0];
+                    dst[3] = if bytes_per_pixel == 4 { src[3] } else { 255 };
+                    has_alpha |= dst[3] != 0;
+                }
+            }
+        }
+    }
+
+    // The fourth byte of BI_RGB 32-bit images is officially unused,
+    // but some encoders store alpha there anyway.
+    if masks.is_none() && !has_alpha {
+        for p in rgba.chunks_exact_mut(4) {
+            p[3] = 255;
+        }
+    }
+
+    Some(RawImage {
+        size,
+        channels: Channels::Rgba,
+        data: rgba,
+    })
+}
+
+/// Extracts a channel using a bitfield mask and scales it to 8 bits.
+fn extract_channel(v: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+
+    let shift = mask.trailing_zeros();
+    let max = u64::from(mask >> shift);
+    let v = u64::from((v & mask) >> shift);
+    ((v * 255 + max / 2) / max) as u8
+}
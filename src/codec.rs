@@ -0,0 +1,115 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Shared code for the built-in uncompressed image formats.
+
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+use crate::{Mask, Pixmap, PixmapRef};
+
+/// A channels layout of a decoded image.
+// Only PNM can store all of them.
+#[cfg_attr(not(feature = "pnm-format"), allow(dead_code))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum Channels {
+    Gray,
+    GrayAlpha,
+    Rgb,
+    Rgba,
+}
+
+impl Channels {
+    pub fn count(self) -> usize {
+        match self {
+            Channels::Gray => 1,
+            Channels::GrayAlpha => 2,
+            Channels::Rgb => 3,
+            Channels::Rgba => 4,
+        }
+    }
+}
+
+/// An 8-bit image with non-premultiplied alpha.
+pub(crate) struct RawImage {
+    pub size: IntSize,
+    pub channels: Channels,
+    pub data: Vec<u8>,
+}
+
+impl RawImage {
+    pub fn from_pixmap(pixmap: PixmapRef) -> Self {
+        RawImage {
+            size: pixmap.size(),
+            channels: Channels::Rgba,
+            data: pixmap.to_owned().take_demultiplied(),
+        }
+    }
+
+    #[cfg(any(feature = "bmp-format", feature = "qoi-format"))]
+    pub fn from_mask(mask: &Mask) -> Self {
+        RawImage {
+            size: mask.size(),
+            channels: Channels::Gray,
+            data: mask.data().to_vec(),
+        }
+    }
+
+    /// Returns the image data as RGBA.
+    pub fn into_rgba(self) -> Vec<u8> {
+        match self.channels {
+            Channels::Rgba => self.data,
+            Channels::Rgb => crate::expand_rgb8_to_rgba8(&self.data),
+            Channels::Gray => self.data.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+            Channels::GrayAlpha => self
+                .data
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+        }
+    }
+
+    pub fn into_pixmap(self) -> Option<Pixmap> {
+        let size = self.size;
+        Pixmap::from_rgba8_unpremultiplied(self.into_rgba(), size)
+    }
+
+    /// Converts an opaque grayscale image into a mask.
+    ///
+    /// Color images are allowed as long as all pixels are gray and opaque.
+    pub fn into_mask(self) -> Option<Mask> {
+        let size = self.size;
+        let data = match self.channels {
+            Channels::Gray => self.data,
+            Channels::GrayAlpha => {
+                log::warn!("only grayscale masks are supported");
+                return None;
+            }
+            Channels::Rgb | Channels::Rgba => {
+                let count = self.channels.count();
+                let mut data = Vec::with_capacity(self.data.len() / count);
+                for p in self.data.chunks_exact(count) {
+                    let is_opaque = count == 3 || p[3] == 255;
+                    if p[0] != p[1] || p[0] != p[2] || !is_opaque {
+                        log::warn!("only grayscale masks are supported");
+                        return None;
+                    }
+
+                    data.push(p[0]);
+                }
+
+                data
+            }
+        };
+
+        Mask::from_vec(data, size)
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn invalid_data_error(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
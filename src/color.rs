@@ -162,11 +162,10 @@ impl PremultipliedColorU8 {
         if alpha == ALPHA_U8_OPAQUE {
             ColorU8(self.0)
         } else {
-            let a = alpha as f64 / 255.0;
             ColorU8::from_rgba(
-                (self.red() as f64 / a + 0.5) as u8,
-                (self.green() as f64 / a + 0.5) as u8,
-                (self.blue() as f64 / a + 0.5) as u8,
+                demultiply_u8(self.red(), alpha),
+                demultiply_u8(self.green(), alpha),
+                demultiply_u8(self.blue(), alpha),
                 alpha,
             )
         }
@@ -434,6 +433,16 @@ pub fn premultiply_u8(c: u8, a: u8) -> u8 {
     ((prod + (prod >> 8)) >> 8) as u8
 }
 
+/// Return c/a, where a is in the 0..=1 range.
+pub(crate) fn demultiply_u8(c: u8, a: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+
+    let a = a as f64 / 255.0;
+    (c as f64 / a + 0.5) as u8
+}
+
 fn color_f32_to_u8(
     r: NormalizedF32,
     g: NormalizedF32,
@@ -0,0 +1,183 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use tiny_skia_path::NoStdFloat;
+
+use crate::{Pixmap, PixmapRef, PremultipliedColorU8};
+
+/// SSIM window size.
+const WINDOW_SIZE: u32 = 8;
+/// Distance between SSIM windows. Windows are overlapping.
+const WINDOW_STEP: u32 = 4;
+
+/// A difference between two pixmaps.
+///
+/// Can be used to compare rendering results with a tolerance.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DiffReport {
+    /// The maximum difference of a single channel of premultiplied colors.
+    pub max_channel_delta: u8,
+
+    /// The number of pixels that differ in at least one channel.
+    pub different_pixels: usize,
+
+    /// Peak signal-to-noise ratio in decibels.
+    ///
+    /// Calculated over all channels of premultiplied colors.
+    /// Infinity when pixmaps are identical.
+    pub psnr: f32,
+
+    /// A structural similarity index (SSIM).
+    ///
+    /// A perceptual metric in a -1..=1 range, where 1 means identical.
+    /// Differences below ~0.99 are usually visible.
+    ///
+    /// Calculated for luminance and alpha separately, using 8x8 windows.
+    /// The smallest one is returned.
+    pub ssim: f32,
+
+    /// A visual diff.
+    ///
+    /// Different pixels are red, with opacity proportional to the difference.
+    /// Identical ones are faded grayscale.
+    pub diff: Pixmap,
+}
+
+impl DiffReport {
+    /// Checks that pixmaps are identical.
+    pub fn is_identical(&self) -> bool {
+        self.different_pixels == 0
+    }
+}
+
+pub(crate) fn compare(a: PixmapRef, b: PixmapRef) -> Option<DiffReport> {
+    if a.size() != b.size() {
+        log::warn!("pixmaps of different sizes cannot be compared");
+        return None;
+    }
+
+    let a = a.to_rgba8888();
+    let b = b.to_rgba8888();
+
+    let mut diff = Pixmap::new(a.width(), a.height())?;
+    let mut max_channel_delta = 0;
+    let mut different_pixels = 0;
+    let mut squared_error_sum = 0u64;
+    let iter = a.pixels().iter().zip(b.pixels()).zip(diff.pixels_mut());
+    for ((c1, c2), d) in iter {
+        let c1 = [c1.red(), c1.green(), c1.blue(), c1.alpha()];
+        let c2 = [c2.red(), c2.green(), c2.blue(), c2.alpha()];
+
+        let mut pixel_delta = 0;
+        for (v1, v2) in c1.iter().zip(c2.iter()) {
+            let delta = v1.abs_diff(*v2);
+            pixel_delta = pixel_delta.max(delta);
+            squared_error_sum += u64::from(delta) * u64::from(delta);
+        }
+
+        max_channel_delta = max_channel_delta.max(pixel_delta);
+        *d = if pixel_delta != 0 {
+            different_pixels += 1;
+            // Keep even the smallest differences visible.
+            let a = 64 + (u16::from(pixel_delta) * 191 / 255) as u8;
+            PremultipliedColorU8::from_rgba_unchecked(a, 0, 0, a)
+        } else {
+            // Luminance blended with white at 10% opacity.
+            let v = 230 + (u32::from(luma(c1)) * 25 / 255) as u8;
+            PremultipliedColorU8::from_rgba_unchecked(v, v, v, 255)
+        };
+    }
+
+    let samples = f64::from(a.width()) * f64::from(a.height()) * 4.0;
+    let psnr = if squared_error_sum == 0 {
+        f32::INFINITY
+    } else {
+        let mse = squared_error_sum as f64 / samples;
+        (10.0 * (255.0 * 255.0 / mse).log10()) as f32
+    };
+
+    let ssim = if different_pixels == 0 {
+        1.0
+    } else {
+        let luma_ssim = ssim(a.as_ref(), b.as_ref(), luma);
+        let alpha_ssim = ssim(a.as_ref(), b.as_ref(), |c| c[3]);
+        luma_ssim.min(alpha_ssim)
+    };
+
+    Some(DiffReport {
+        max_channel_delta,
+        different_pixels,
+        psnr,
+        ssim,
+        diff,
+    })
+}
+
+/// Premultiplied colors luminance. Same as compositing over black.
+fn luma(c: [u8; 4]) -> u8 {
+    let v = u32::from(c[0]) * 54 + u32::from(c[1]) * 183 + u32::from(c[2]) * 19;
+    ((v + 128) >> 8) as u8
+}
+
+/// Calculates a mean SSIM of a single channel.
+fn ssim(a: PixmapRef, b: PixmapRef, channel: impl Fn([u8; 4]) -> u8) -> f32 {
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    let width = a.width();
+    let height = a.height();
+    let window_width = WINDOW_SIZE.min(width);
+    let window_height = WINDOW_SIZE.min(height);
+
+    let get = |p: PixmapRef, x: u32, y: u32| -> f64 {
+        let c = p.pixels()[(y * width + x) as usize];
+        f64::from(channel([c.red(), c.green(), c.blue(), c.alpha()]))
+    };
+
+    let mut sum = 0.0;
+    let mut count = 0;
+    for wy in window_positions(height, window_height) {
+        for wx in window_positions(width, window_width) {
+            let n = f64::from(window_width * window_height);
+            let mut sum_a = 0.0;
+            let mut sum_b = 0.0;
+            let mut sum_aa = 0.0;
+            let mut sum_bb = 0.0;
+            let mut sum_ab = 0.0;
+            for y in wy..wy + window_height {
+                for x in wx..wx + window_width {
+                    let va = get(a, x, y);
+                    let vb = get(b, x, y);
+                    sum_a += va;
+                    sum_b += vb;
+                    sum_aa += va * va;
+                    sum_bb += vb * vb;
+                    sum_ab += va * vb;
+                }
+            }
+
+            let mean_a = sum_a / n;
+            let mean_b = sum_b / n;
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_b = sum_bb / n - mean_b * mean_b;
+            let covar = sum_ab / n - mean_a * mean_b;
+
+            sum += ((2.0 * mean_a * mean_b + C1) * (2.0 * covar + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            count += 1;
+        }
+    }
+
+    (sum / f64::from(count)) as f32
+}
+
+/// Returns window offsets that cover the whole `len`.
+fn window_positions(len: u32, window: u32) -> impl Iterator<Item = u32> {
+    let last = len - window;
+    (0..last)
+        .step_by(WINDOW_STEP as usize)
+        .chain(core::iter::once(last))
+}
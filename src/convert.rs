@@ -0,0 +1,228 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use crate::color::{demultiply_u8, premultiply_u8, ALPHA_U8_OPAQUE};
+use crate::wide::{f32x8, u16x16, u32x8};
+
+/// Premultiplies RGBA pixels in place.
+///
+/// Produces the same results as [`ColorU8::premultiply`](crate::ColorU8::premultiply),
+/// but processes multiple pixels at once.
+///
+/// An incomplete trailing pixel is ignored.
+pub fn premultiply_rgba8(data: &mut [u8]) {
+    let mut chunks = data.chunks_exact_mut(16);
+    for chunk in &mut chunks {
+        let mut c = u16x16::default();
+        let mut a = u16x16::default();
+        for i in 0..16 {
+            c.0[i] = u16::from(chunk[i]);
+            // Alpha is "premultiplied" by 255, which is a no-op.
+            a.0[i] = if i % 4 == 3 {
+                255
+            } else {
+                u16::from(chunk[i | 3])
+            };
+        }
+
+        // Exact `c * a / 255`, rounded. Cannot overflow, since 255 * 255 + 128 + 254 < 65536.
+        let prod = c * a + u16x16::splat(128);
+        let v = (prod + (prod >> u16x16::splat(8))) >> u16x16::splat(8);
+        for i in 0..16 {
+            chunk[i] = v.0[i] as u8;
+        }
+    }
+
+    for pixel in chunks.into_remainder().chunks_exact_mut(4) {
+        let a = pixel[3];
+        pixel[0] = premultiply_u8(pixel[0], a);
+        pixel[1] = premultiply_u8(pixel[1], a);
+        pixel[2] = premultiply_u8(pixel[2], a);
+    }
+}
+
+/// Demultiplies RGBA pixels in place.
+///
+/// Produces the same results as
+/// [`PremultipliedColorU8::demultiply`](crate::PremultipliedColorU8::demultiply).
+///
+/// An incomplete trailing pixel is ignored.
+pub fn demultiply_rgba8(data: &mut [u8]) {
+    let mut chunks = data.chunks_exact_mut(8);
+    for chunk in &mut chunks {
+        let mut c = [0.0; 8];
+        let mut a = [0.0; 8];
+        let mut alpha = [0u8; 8];
+        for i in 0..8 {
+            // Alpha is "demultiplied" by 255, which is a no-op.
+            alpha[i] = if i % 4 == 3 { 255 } else { chunk[i | 3] };
+            // Transparent pixels become black, like in `demultiply_u8`.
+            let (v, d) = if alpha[i] == 0 {
+                (0, 1)
+            } else {
+                (u16::from(chunk[i]), u16::from(alpha[i]))
+            };
+
+            // `c * 255 / a`, rounded. Cannot overflow, since 255 * 255 + 127 < 65536.
+            c[i] = f32::from(v * 255 + d / 2);
+            a[i] = f32::from(d);
+        }
+
+        // All values are exact integers and a correctly rounded division
+        // cannot reach the next integer, since the quotient is below 2^16.
+        let c = f32x8::from(c);
+        let a = f32x8::from(a);
+        let q = (c / a).floor();
+        let v: [f32; 8] = bytemuck::cast(q.min(f32x8::splat(255.0)));
+        let exact: [u32; 8] = bytemuck::cast((q * a).cmp_eq(c));
+        for i in 0..8 {
+            // `demultiply_u8` rounds halves via `f64`, which isn't always up.
+            // Halves are possible only for even alpha and are rare enough.
+            chunk[i] = if exact[i] != 0 && alpha[i] % 2 == 0 {
+                demultiply_u8(chunk[i], alpha[i])
+            } else {
+                v[i] as u8
+            };
+        }
+    }
+
+    for pixel in chunks.into_remainder().chunks_exact_mut(4) {
+        let a = pixel[3];
+        if a != ALPHA_U8_OPAQUE {
+            pixel[0] = demultiply_u8(pixel[0], a);
+            pixel[1] = demultiply_u8(pixel[1], a);
+            pixel[2] = demultiply_u8(pixel[2], a);
+        }
+    }
+}
+
+/// Swaps the red and blue channels in place.
+///
+/// Converts RGBA to BGRA and vice versa.
+///
+/// An incomplete trailing pixel is ignored.
+pub fn swizzle_rgba_bgra(data: &mut [u8]) {
+    let mut chunks = data.chunks_exact_mut(32);
+    for chunk in &mut chunks {
+        let mut v = [0u32; 8];
+        for (n, pixel) in v.iter_mut().zip(chunk.chunks_exact(4)) {
+            *n = u32::from_le_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+        }
+
+        let v: u32x8 = bytemuck::cast(v);
+        let ga = v & u32x8::splat(0xff00ff00);
+        let r = (v & u32x8::splat(0x000000ff)).shl::<16>();
+        let b = v.shr::<16>() & u32x8::splat(0x000000ff);
+        let v: [u32; 8] = bytemuck::cast(ga | r | b);
+
+        for (n, pixel) in v.iter().zip(chunk.chunks_exact_mut(4)) {
+            pixel.copy_from_slice(&n.to_le_bytes());
+        }
+    }
+
+    for pixel in chunks.into_remainder().chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
+
+/// Converts RGB pixels into opaque RGBA ones.
+///
+/// An incomplete trailing pixel is ignored.
+pub fn expand_rgb8_to_rgba8(rgb: &[u8]) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(rgb.len() / 3 * 4);
+
+    let mut chunks = rgb.chunks_exact(24);
+    for chunk in &mut chunks {
+        let mut v = [0u32; 8];
+        for (n, pixel) in v.iter_mut().zip(chunk.chunks_exact(3)) {
+            *n = u32::from_le_bytes([pixel[0], pixel[1], pixel[2], 0]);
+        }
+
+        let alpha = u32x8::splat(u32::from(ALPHA_U8_OPAQUE) << 24);
+        let v: [u32; 8] = bytemuck::cast(bytemuck::cast::<_, u32x8>(v) | alpha);
+        for n in v {
+            rgba.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+
+    for pixel in chunks.remainder().chunks_exact(3) {
+        rgba.extend_from_slice(&[pixel[0], pixel[1], pixel[2], ALPHA_U8_OPAQUE]);
+    }
+
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn premultiply_matches_scalar() {
+        let mut data = Vec::new();
+        for a in 0..=255 {
+            for c in 0..=255 {
+                data.extend_from_slice(&[c, 255 - c, c / 2, a]);
+            }
+        }
+        // Make sure that the scalar tail is used as well.
+        data.extend_from_slice(&[10, 20, 30, 40, 50, 60, 70, 80]);
+
+        let mut expected = data.clone();
+        for pixel in expected.chunks_mut(4) {
+            let c = crate::ColorU8::from_rgba(pixel[0], pixel[1], pixel[2], pixel[3]).premultiply();
+            pixel.copy_from_slice(&[c.red(), c.green(), c.blue(), c.alpha()]);
+        }
+
+        premultiply_rgba8(&mut data);
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn demultiply_matches_scalar() {
+        let mut data = Vec::new();
+        for a in 0..=255 {
+            for c in 0..=255 {
+                data.extend_from_slice(&[c, 255 - c, c / 2, a]);
+            }
+        }
+        // Make sure that the scalar tail is used as well.
+        data.extend_from_slice(&[10, 20, 30, 40]);
+
+        let mut expected = data.clone();
+        for pixel in expected.chunks_mut(4) {
+            let c = crate::PremultipliedColorU8::from_rgba_unchecked(
+                pixel[0], pixel[1], pixel[2], pixel[3],
+            )
+            .demultiply();
+            pixel.copy_from_slice(&[c.red(), c.green(), c.blue(), c.alpha()]);
+        }
+
+        demultiply_rgba8(&mut data);
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn swizzle() {
+        let mut data: Vec<u8> = (0..4 * 11).collect();
+        swizzle_rgba_bgra(&mut data);
+        for (i, pixel) in data.chunks(4).enumerate() {
+            let n = i as u8 * 4;
+            assert_eq!(pixel, &[n + 2, n + 1, n, n + 3]);
+        }
+    }
+
+    #[test]
+    fn expand() {
+        let data: Vec<u8> = (0..3 * 11 + 2).collect();
+        let rgba = expand_rgb8_to_rgba8(&data);
+        assert_eq!(rgba.len(), 4 * 11);
+        for (i, pixel) in rgba.chunks(4).enumerate() {
+            let n = i as u8 * 3;
+            assert_eq!(pixel, &[n, n + 1, n + 2, 255]);
+        }
+    }
+}
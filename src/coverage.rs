@@ -0,0 +1,174 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use tiny_skia_path::{IntRect, Path, Scalar, Transform};
+
+use crate::alpha_runs::AlphaRun;
+use crate::blitter::Blitter;
+use crate::color::AlphaU8;
+use crate::geom::{IntSizeExt, ScreenIntRect};
+use crate::painter::{is_too_big_for_math, DrawTiler};
+use crate::{scan, FillRule, LengthU32};
+
+/// A receiver of coverage spans produced by [`rasterize_path`].
+///
+/// Allows using `tiny-skia` rasterization without rendering into a `Pixmap`.
+/// For example, to upload coverage to GPU or to build a custom mask format.
+pub trait CoverageSink {
+    /// Receives a horizontal run of pixels with the same coverage.
+    ///
+    /// `coverage` is in a 1..=255 range, where 255 means fully covered.
+    /// Spans never overlap.
+    fn span(&mut self, x: i32, y: i32, width: u32, coverage: u8);
+
+    /// Receives a rectangle of fully covered pixels.
+    ///
+    /// Calls [`CoverageSink::span`] for each row by default.
+    fn rect(&mut self, rect: IntRect) {
+        for y in rect.top()..rect.bottom() {
+            self.span(rect.x(), y, rect.width(), 255);
+        }
+    }
+}
+
+/// Rasterizes a filled path and passes coverage to the `sink`.
+///
+/// Only pixels inside the `clip` rect are reported.
+///
+/// The same rasterizer is used by [`PixmapMut::fill_path`](crate::PixmapMut::fill_path),
+/// therefore the coverage is identical.
+pub fn rasterize_path(
+    path: &Path,
+    fill_rule: FillRule,
+    transform: Transform,
+    clip: IntRect,
+    anti_alias: bool,
+    sink: &mut dyn CoverageSink,
+) {
+    // Rasterize in clip coordinates, since the scan converters support only
+    // non-negative coordinates.
+    let ts = transform.post_translate(-clip.x() as f32, -clip.y() as f32);
+    let mut path = match path.clone().transform(ts) {
+        Some(v) => v,
+        None => {
+            log::warn!("path transformation failed");
+            return;
+        }
+    };
+
+    // Skip empty paths and horizontal/vertical lines.
+    let path_bounds = path.bounds();
+    if path_bounds.width().is_nearly_zero() || path_bounds.height().is_nearly_zero() {
+        log::warn!("empty paths and horizontal/vertical lines cannot be filled");
+        return;
+    }
+
+    if is_too_big_for_math(&path) {
+        log::warn!("path coordinates are too big");
+        return;
+    }
+
+    let mut blitter = SinkBlitter {
+        sink,
+        dx: clip.x(),
+        dy: clip.y(),
+    };
+
+    if let Some(tiler) = DrawTiler::new(clip.width(), clip.height()) {
+        for tile in tiler {
+            let ts = Transform::from_translate(-(tile.x() as f32), -(tile.y() as f32));
+            path = match path.transform(ts) {
+                Some(v) => v,
+                None => {
+                    log::warn!("path transformation failed");
+                    return;
+                }
+            };
+
+            blitter.dx = clip.x() + tile.x() as i32;
+            blitter.dy = clip.y() + tile.y() as i32;
+            let clip_rect = tile.size().to_screen_int_rect(0, 0);
+            fill_path(&path, fill_rule, &clip_rect, anti_alias, &mut blitter);
+
+            let ts = Transform::from_translate(tile.x() as f32, tile.y() as f32);
+            path = match path.transform(ts) {
+                Some(v) => v,
+                None => return, // technically unreachable
+            };
+        }
+    } else {
+        let clip_rect = clip.size().to_screen_int_rect(0, 0);
+        fill_path(&path, fill_rule, &clip_rect, anti_alias, &mut blitter);
+    }
+}
+
+fn fill_path(
+    path: &Path,
+    fill_rule: FillRule,
+    clip: &ScreenIntRect,
+    anti_alias: bool,
+    blitter: &mut dyn Blitter,
+) {
+    if anti_alias {
+        scan::path_aa::fill_path(path, fill_rule, clip, blitter);
+    } else {
+        scan::path::fill_path(path, fill_rule, clip, blitter);
+    }
+}
+
+/// Converts blitter calls into sink calls, while translating them back
+/// into the original coordinates.
+struct SinkBlitter<'a> {
+    sink: &'a mut dyn CoverageSink,
+    dx: i32,
+    dy: i32,
+}
+
+impl SinkBlitter<'_> {
+    fn span(&mut self, x: u32, y: u32, width: u32, coverage: AlphaU8) {
+        if coverage != 0 {
+            self.sink
+                .span(x as i32 + self.dx, y as i32 + self.dy, width, coverage);
+        }
+    }
+}
+
+impl Blitter for SinkBlitter<'_> {
+    fn blit_h(&mut self, x: u32, y: u32, width: LengthU32) {
+        self.span(x, y, width.get(), 255);
+    }
+
+    fn blit_anti_h(&mut self, mut x: u32, y: u32, aa: &mut [AlphaU8], runs: &mut [AlphaRun]) {
+        let mut offset = 0;
+        while let Some(run) = runs[offset] {
+            let width = u32::from(run.get());
+            self.span(x, y, width, aa[offset]);
+            x += width;
+            offset += usize::from(run.get());
+        }
+    }
+
+    fn blit_v(&mut self, x: u32, y: u32, height: LengthU32, alpha: AlphaU8) {
+        for row in y..y + height.get() {
+            self.span(x, row, 1, alpha);
+        }
+    }
+
+    fn blit_anti_h2(&mut self, x: u32, y: u32, alpha0: AlphaU8, alpha1: AlphaU8) {
+        self.span(x, y, 1, alpha0);
+        self.span(x + 1, y, 1, alpha1);
+    }
+
+    fn blit_anti_v2(&mut self, x: u32, y: u32, alpha0: AlphaU8, alpha1: AlphaU8) {
+        self.span(x, y, 1, alpha0);
+        self.span(x, y + 1, 1, alpha1);
+    }
+
+    fn blit_rect(&mut self, rect: &ScreenIntRect) {
+        if let Some(rect) = rect.to_int_rect().translate(self.dx, self.dy) {
+            self.sink.rect(rect);
+        }
+    }
+}
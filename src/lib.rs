@@ -35,9 +35,18 @@ extern crate std;
 extern crate alloc;
 
 mod alpha_runs;
+#[cfg(feature = "png-format")]
+mod apng;
 mod blend_mode;
 mod blitter;
+#[cfg(feature = "bmp-format")]
+mod bmp_format;
+#[cfg(any(feature = "bmp-format", feature = "pnm-format", feature = "qoi-format"))]
+mod codec;
 mod color;
+mod compare;
+mod convert;
+mod coverage;
 mod edge;
 mod edge_builder;
 mod edge_clipper;
@@ -46,28 +55,51 @@ mod geom;
 mod line_clipper;
 mod mask;
 mod math;
-mod path64;
 mod path_geometry;
 mod pipeline;
+mod pixel_format;
 mod pixmap;
+#[cfg(feature = "png-format")]
+mod png_format;
+#[cfg(feature = "pnm-format")]
+mod pnm_format;
+#[cfg(feature = "qoi-format")]
+mod qoi_format;
+mod region;
+mod resize;
 mod scan;
 mod shaders;
 mod wide;
 
 mod painter; // Keep it under `pixmap` for a better order in the docs.
 
+#[cfg(feature = "png-format")]
+pub use apng::{Apng, ApngBlendOp, ApngDisposeOp, ApngFrame};
 pub use blend_mode::BlendMode;
 pub use color::{Color, ColorSpace, ColorU8, PremultipliedColor, PremultipliedColorU8};
 pub use color::{ALPHA_OPAQUE, ALPHA_TRANSPARENT, ALPHA_U8_OPAQUE, ALPHA_U8_TRANSPARENT};
-pub use mask::{Mask, MaskType};
-pub use painter::{FillRule, Paint};
+pub use compare::DiffReport;
+pub use convert::{demultiply_rgba8, expand_rgb8_to_rgba8, premultiply_rgba8, swizzle_rgba_bgra};
+pub use coverage::{rasterize_path, CoverageSink};
+pub use mask::{Mask, MaskOp, MaskType};
+pub use painter::{Clip, FillRule, Paint};
+pub use pixel_format::PixelFormat;
 pub use pixmap::{Pixmap, PixmapMut, PixmapRef, BYTES_PER_PIXEL};
-pub use shaders::{FilterQuality, GradientStop, PixmapPaint, SpreadMode};
-pub use shaders::{LinearGradient, Pattern, RadialGradient, Shader, SweepGradient};
+#[cfg(feature = "png-format")]
+pub use png_format::{PngCompression, PngDecodeOptions, PngEncodeOptions, PngFilter};
+pub use region::Region;
+pub use resize::ResizeFilter;
+pub use shaders::{CubicResampler, FilterQuality, GradientStop, Mipmaps, PixmapPaint, SpreadMode};
+pub use shaders::{CustomShader, PerlinNoise};
+pub use shaders::{LinearGradient, Pattern, RadialGradient, Shader, ShaderImpl, SweepGradient};
 
+pub use tiny_skia_path::{Curve, CurveIntersection, NearestPoint, PathCurvesIter};
 pub use tiny_skia_path::{IntRect, IntSize, NonZeroRect, Point, Rect, Size, Transform};
-pub use tiny_skia_path::{LineCap, LineJoin, Stroke, StrokeDash};
-pub use tiny_skia_path::{Path, PathBuilder, PathSegment, PathSegmentsIter, PathStroker};
+pub use tiny_skia_path::{LineCap, LineJoin, Stroke, StrokeAlignment, StrokeDash, WidthProfile};
+pub use tiny_skia_path::{MarkerShape, Markers};
+pub use tiny_skia_path::{
+    Path, PathBuilder, PathContoursIter, PathDirection, PathSegment, PathSegmentsIter, PathStroker,
+};
 
 /// An integer length that is guarantee to be > 0
 type LengthU32 = core::num::NonZeroU32;
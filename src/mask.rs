@@ -9,14 +9,41 @@ use tiny_skia_path::NoStdFloat;
 use alloc::vec;
 use alloc::vec::Vec;
 
-use tiny_skia_path::{IntRect, IntSize, Path, Scalar, Transform};
+use tiny_skia_path::{IntRect, IntSize, Path, Rect, Scalar, Stroke, Transform};
 
+use crate::color::premultiply_u8;
 use crate::geom::IntSizeExt;
 use crate::painter::DrawTiler;
 use crate::pipeline::RasterPipelineBlitter;
 use crate::pixmap::SubPixmapMut;
 use crate::scan;
-use crate::{FillRule, PixmapRef};
+use crate::wide::u16x16;
+use crate::{Clip, Color, FillRule, Paint, PixelFormat, PixmapMut, PixmapPaint, PixmapRef, Shader};
+
+/// A mask compositing operation.
+///
+/// Describes how `other` mask is combined with the current one, `a` being the current value
+/// and `b` being the `other` one.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MaskOp {
+    /// `a * b`
+    Intersect,
+    /// `a + b - a * b`
+    Union,
+    /// `a * (1 - b)`
+    Difference,
+    /// `a + b - 2 * a * b`
+    Xor,
+    /// `b * (1 - a)`
+    ReverseDifference,
+}
+
+impl MaskOp {
+    /// Checks that the operation produces zero when `b` is zero.
+    fn clears_uncovered(&self) -> bool {
+        matches!(self, MaskOp::Intersect | MaskOp::ReverseDifference)
+    }
+}
 
 /// A mask type.
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -37,10 +64,16 @@ pub enum MaskType {
 ///
 /// Unlike Skia, we're using just a simple 8bit alpha mask.
 /// It's way slower, but easier to implement.
-#[derive(Clone, PartialEq)]
+///
+/// To compensate, the mask tracks a rectangle that contains all non-zero values,
+/// so drawing can skip regions that are fully masked-out.
+#[derive(Clone)]
 pub struct Mask {
     data: Vec<u8>,
     size: IntSize,
+    // A conservative rect that contains all non-zero values.
+    // `None` when the mask is empty.
+    bounds: Option<IntRect>,
 }
 
 impl Mask {
@@ -50,15 +83,21 @@ impl Mask {
         Some(Mask {
             data: vec![0; width as usize * height as usize],
             size,
+            bounds: None,
         })
     }
 
     /// Creates a new mask from a `PixmapRef`.
     pub fn from_pixmap(pixmap: PixmapRef, mask_type: MaskType) -> Self {
+        if !pixmap.is_packed_rgba8888() {
+            return Self::from_pixmap(pixmap.to_rgba8888().as_ref(), mask_type);
+        }
+
         let data_len = pixmap.width() as usize * pixmap.height() as usize;
         let mut mask = Mask {
             data: vec![0; data_len],
             size: pixmap.size(),
+            bounds: None,
         };
 
         // TODO: optimize
@@ -89,6 +128,7 @@ impl Mask {
             }
         }
 
+        mask.update_bounds();
         mask
     }
 
@@ -101,7 +141,47 @@ impl Mask {
             return None;
         }
 
-        Some(Mask { data, size })
+        let mut mask = Mask {
+            data,
+            size,
+            bounds: None,
+        };
+        mask.update_bounds();
+        Some(mask)
+    }
+
+    /// Creates a new mask by copying a buffer with padded rows.
+    ///
+    /// `row_bytes` is the distance between the starts of two rows in bytes
+    /// and must be at least `width`.
+    ///
+    /// The size must be at least `row_bytes * (height - 1) + width`.
+    /// Unlike `PixmapRef`, the data will be copied and the padding removed.
+    pub fn from_bytes_with_stride(
+        data: &[u8],
+        width: u32,
+        height: u32,
+        row_bytes: usize,
+    ) -> Option<Self> {
+        let size = IntSize::from_wh(width, height)?;
+        let width = width as usize;
+        if row_bytes < width {
+            return None;
+        }
+
+        let data_len = row_bytes
+            .checked_mul(height as usize - 1)?
+            .checked_add(width)?;
+        if data.len() < data_len {
+            return None;
+        }
+
+        let mut mask_data = Vec::with_capacity(width * height as usize);
+        for y in 0..height as usize {
+            mask_data.extend_from_slice(&data[y * row_bytes..][..width]);
+        }
+
+        Mask::from_vec(mask_data, size)
     }
 
     /// Returns mask's width.
@@ -128,10 +208,60 @@ impl Mask {
     }
 
     /// Returns the mutable internal data.
+    ///
+    /// Resets [`Mask::bounds`] to the whole mask.
+    /// Use [`Mask::update_bounds`] afterwards to shrink them back.
     pub fn data_mut(&mut self) -> &mut [u8] {
+        self.bounds = Some(self.size.to_int_rect(0, 0));
         self.data.as_mut_slice()
     }
 
+    /// Returns a rect that contains all non-zero values.
+    ///
+    /// Drawing outside of this rect is skipped.
+    ///
+    /// The rect is conservative and can be larger than the actual non-zero region.
+    /// Returns `None` when the mask is empty.
+    pub fn bounds(&self) -> Option<IntRect> {
+        self.bounds
+    }
+
+    /// Recalculates [`Mask::bounds`] to tightly fit non-zero values.
+    pub fn update_bounds(&mut self) {
+        let width = self.width() as usize;
+        let mut left = width;
+        let mut right = 0;
+        let mut top = None;
+        let mut bottom = 0;
+        for (y, row) in self.data.chunks_exact(width).enumerate() {
+            let first = match row.iter().position(|a| *a != 0) {
+                Some(v) => v,
+                None => continue,
+            };
+            // Always succeeds, since there is at least one non-zero value.
+            let last = row.iter().rposition(|a| *a != 0).unwrap();
+
+            left = left.min(first);
+            right = right.max(last + 1);
+            top.get_or_insert(y);
+            bottom = y + 1;
+        }
+
+        self.bounds = top.and_then(|top| {
+            IntRect::from_ltrb(left as i32, top as i32, right as i32, bottom as i32)
+        });
+    }
+
+    /// Adds a rect to bounds.
+    fn extend_bounds(&mut self, rect: IntRect) {
+        let rect = match self.size.to_int_rect(0, 0).intersect(&rect) {
+            Some(v) => v,
+            None => return,
+        };
+
+        self.bounds = union_rects(self.bounds, Some(rect));
+    }
+
     /// Consumes the mask and returns its owned internal data.
     pub fn take(self) -> Vec<u8> {
         self.data
@@ -142,6 +272,7 @@ impl Mask {
             size: self.size,
             real_width: self.size.width(),
             data: &self.data,
+            bounds: self.bounds,
         }
     }
 
@@ -154,6 +285,10 @@ impl Mask {
             size: rect.size(),
             real_width: self.size.width(),
             data: &self.data[offset..],
+            bounds: self
+                .bounds
+                .and_then(|bounds| bounds.intersect(&rect))
+                .and_then(|bounds| bounds.translate(-rect.x(), -rect.y())),
         })
     }
 
@@ -161,6 +296,7 @@ impl Mask {
         SubPixmapMut {
             size: self.size,
             real_width: self.size.width() as usize,
+            format: PixelFormat::A8,
             data: &mut self.data,
         }
     }
@@ -173,6 +309,7 @@ impl Mask {
         Some(SubPixmapMut {
             size: rect.size(),
             real_width: self.size.width() as usize,
+            format: PixelFormat::A8,
             data: &mut self.data[offset..],
         })
     }
@@ -245,7 +382,6 @@ impl Mask {
         Ok(())
     }
 
-    // Almost a direct copy of PixmapMut::fill_path
     /// Draws a filled path onto the mask.
     ///
     /// In terms of RGB (no alpha) image, draws a white path on top of black mask.
@@ -255,13 +391,284 @@ impl Mask {
     /// If the above behavior is undesired, [`Mask::clear()`] should be called first.
     ///
     /// This method is intended to be used for simple cases. For more complex masks
-    /// prefer [`Mask::from_pixmap()`].
+    /// prefer [`Mask::fill_path_with_paint()`].
     pub fn fill_path(
         &mut self,
         path: &Path,
         fill_rule: FillRule,
         anti_alias: bool,
         transform: Transform,
+    ) {
+        self.fill_path_impl(path, fill_rule, anti_alias, transform, Color::WHITE);
+    }
+
+    /// Intersects the provided path with the current clipping path.
+    ///
+    /// A temporary mask covering the intersection of the path
+    /// and [`Mask::bounds`] will be created.
+    pub fn intersect_path(
+        &mut self,
+        path: &Path,
+        fill_rule: FillRule,
+        anti_alias: bool,
+        transform: Transform,
+    ) {
+        // Like with an empty path, nothing would be drawn into a temporary mask,
+        // so an intersection with it is empty.
+        let path = match path.clone().transform(transform) {
+            Some(v) => v,
+            None => {
+                log::warn!("path transformation failed");
+                self.clear();
+                return;
+            }
+        };
+
+        let region = path_device_bounds(&path)
+            .and_then(|rect| self.bounds.and_then(|bounds| bounds.intersect(&rect)));
+        let region = match region {
+            Some(v) => v,
+            None => {
+                self.clear();
+                return;
+            }
+        };
+
+        let mut submask = Mask::new(region.width(), region.height()).unwrap();
+        let ts = Transform::from_translate(-region.x() as f32, -region.y() as f32);
+        submask.fill_path(&path, fill_rule, anti_alias, ts);
+        self.combine(&submask, MaskOp::Intersect, region.x(), region.y());
+    }
+
+    /// Adds the provided path to the current clipping path.
+    ///
+    /// Same as [`Mask::fill_path`]. Doesn't allocate.
+    pub fn union_path(
+        &mut self,
+        path: &Path,
+        fill_rule: FillRule,
+        anti_alias: bool,
+        transform: Transform,
+    ) {
+        self.fill_path(path, fill_rule, anti_alias, transform);
+    }
+
+    /// Removes the provided path from the current clipping path.
+    ///
+    /// Unlike [`Mask::intersect_path`], doesn't allocate a temporary mask.
+    pub fn subtract_path(
+        &mut self,
+        path: &Path,
+        fill_rule: FillRule,
+        anti_alias: bool,
+        transform: Transform,
+    ) {
+        self.fill_path_impl(path, fill_rule, anti_alias, transform, Color::TRANSPARENT);
+    }
+
+    /// Draws a stroked path onto the mask.
+    ///
+    /// In terms of RGB (no alpha) image, draws a white stroke on top of black mask.
+    ///
+    /// See [`PixmapMut::stroke_path`] for details.
+    pub fn stroke_path(
+        &mut self,
+        path: &Path,
+        stroke: &Stroke,
+        anti_alias: bool,
+        transform: Transform,
+    ) {
+        let paint = Paint {
+            shader: Shader::SolidColor(Color::WHITE),
+            anti_alias,
+            ..Paint::default()
+        };
+        self.stroke_path_with_paint(path, &paint, stroke, transform, Clip::None);
+    }
+
+    /// Draws a filled rectangle onto the mask.
+    ///
+    /// Only the alpha channel of the paint's shader is used,
+    /// which allows drawing gradients and patterns into a mask.
+    /// The blend mode is applied to mask values as well.
+    ///
+    /// A `Clip::Mask` must have the same size as the current mask.
+    pub fn fill_rect_with_paint(
+        &mut self,
+        rect: Rect,
+        paint: &Paint,
+        transform: Transform,
+        clip: Clip,
+    ) {
+        self.draw_as_pixmap(|pixmap| pixmap.fill_rect(rect, paint, transform, clip));
+    }
+
+    /// Draws a filled path onto the mask.
+    ///
+    /// Only the alpha channel of the paint's shader is used,
+    /// which allows drawing gradients and patterns into a mask.
+    /// The blend mode is applied to mask values as well.
+    ///
+    /// A `Clip::Mask` must have the same size as the current mask.
+    pub fn fill_path_with_paint(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: Transform,
+        clip: Clip,
+    ) {
+        self.draw_as_pixmap(|pixmap| pixmap.fill_path(path, paint, fill_rule, transform, clip));
+    }
+
+    /// Draws a stroked path onto the mask.
+    ///
+    /// Only the alpha channel of the paint's shader is used,
+    /// which allows drawing gradients and patterns into a mask.
+    /// The blend mode is applied to mask values as well.
+    ///
+    /// A `Clip::Mask` must have the same size as the current mask.
+    pub fn stroke_path_with_paint(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        stroke: &Stroke,
+        transform: Transform,
+        clip: Clip,
+    ) {
+        self.draw_as_pixmap(|pixmap| pixmap.stroke_path(path, paint, stroke, transform, clip));
+    }
+
+    /// Draws a `Mask` on top of the current `Mask`.
+    ///
+    /// Mask values are treated as alpha. Useful for building masks from
+    /// prerendered shapes.
+    ///
+    /// A `Clip::Mask` must have the same size as the current mask.
+    pub fn draw_mask(
+        &mut self,
+        x: i32,
+        y: i32,
+        mask: &Mask,
+        paint: &PixmapPaint,
+        transform: Transform,
+        clip: Clip,
+    ) {
+        // Always succeeds, since the mask data has a valid size.
+        let pixmap = PixmapRef::from_bytes_with_format(
+            &mask.data,
+            mask.width(),
+            mask.height(),
+            PixelFormat::A8,
+        )
+        .unwrap();
+        self.draw_as_pixmap(|dst| dst.draw_pixmap(x, y, pixmap, paint, transform, clip));
+    }
+
+    /// Draws onto the mask as onto an `A8` pixmap and updates bounds.
+    fn draw_as_pixmap(&mut self, draw: impl FnOnce(&mut PixmapMut)) {
+        let (width, height) = (self.width(), self.height());
+        // Always succeeds, since the mask data has a valid size.
+        let mut pixmap =
+            PixmapMut::from_bytes_with_format(&mut self.data, width, height, PixelFormat::A8)
+                .unwrap();
+        pixmap.enable_damage_tracking();
+        draw(&mut pixmap);
+
+        if let Some(rect) = pixmap.take_damage().and_then(|damage| damage.bounds()) {
+            self.extend_bounds(rect);
+        }
+    }
+
+    /// Combines the `other` mask with the current one.
+    ///
+    /// The `other` mask is positioned at `x` and `y`. Values outside of it are treated as zero.
+    pub fn combine(&mut self, other: &Mask, op: MaskOp, x: i32, y: i32) {
+        let mask_rect = self.size.to_int_rect(0, 0);
+        let other_bounds = other
+            .bounds
+            .and_then(|rect| rect.translate(x, y))
+            .and_then(|rect| rect.intersect(&mask_rect));
+
+        // Pixels that were zero and stay zero can be skipped.
+        let (region, new_bounds) = match op {
+            MaskOp::Intersect => {
+                let bounds = intersect_rects(self.bounds, other_bounds);
+                (bounds, bounds)
+            }
+            MaskOp::Difference => (intersect_rects(self.bounds, other_bounds), self.bounds),
+            MaskOp::Union | MaskOp::Xor => (other_bounds, union_rects(self.bounds, other_bounds)),
+            MaskOp::ReverseDifference => (other_bounds, other_bounds),
+        };
+
+        if op.clears_uncovered() {
+            if let Some(bounds) = self.bounds {
+                self.clear_outside(bounds, other_bounds);
+            }
+        }
+
+        self.bounds = new_bounds;
+
+        let region = match region {
+            Some(v) => v,
+            None => return,
+        };
+
+        let width = self.width() as usize;
+        let left = region.x() as usize;
+        let right = region.right() as usize;
+        for row_y in region.y()..region.bottom() {
+            let row = &mut self.data[row_y as usize * width..][..width];
+            let src_x = (region.x() - x) as usize;
+            let src_y = (row_y - y) as usize;
+            let src_start = src_y * other.width() as usize + src_x;
+            let src = &other.data[src_start..src_start + (right - left)];
+            combine_row(&mut row[left..right], src, op);
+        }
+    }
+
+    /// Zeroes values inside `bounds`, but outside `keep`.
+    fn clear_outside(&mut self, bounds: IntRect, keep: Option<IntRect>) {
+        let width = self.width() as usize;
+        for y in bounds.y()..bounds.bottom() {
+            let row = &mut self.data[y as usize * width..][..width];
+            let row = &mut row[bounds.x() as usize..bounds.right() as usize];
+            match keep {
+                Some(keep) if y >= keep.y() && y < keep.bottom() => {
+                    let keep_left = (keep.x() - bounds.x()).clamp(0, row.len() as i32) as usize;
+                    let keep_right =
+                        (keep.right() - bounds.x()).clamp(0, row.len() as i32) as usize;
+                    row[..keep_left].fill(0);
+                    row[keep_right.max(keep_left)..].fill(0);
+                }
+                _ => row.fill(0),
+            }
+        }
+    }
+
+    /// Inverts the mask.
+    pub fn invert(&mut self) {
+        self.data.iter_mut().for_each(|a| *a = 255 - *a);
+        self.update_bounds();
+    }
+
+    /// Clears the mask.
+    ///
+    /// Zero-fills the internal data buffer.
+    pub fn clear(&mut self) {
+        self.data.fill(0);
+        self.bounds = None;
+    }
+
+    // Almost a direct copy of PixmapMut::fill_path
+    /// Fills a path by interpolating the mask values towards `color` using path's coverage.
+    fn fill_path_impl(
+        &mut self,
+        path: &Path,
+        fill_rule: FillRule,
+        anti_alias: bool,
+        transform: Transform,
+        color: Color,
     ) {
         if transform.is_identity() {
             // This is sort of similar to SkDraw::drawPath
@@ -278,6 +685,14 @@ impl Mask {
                 return;
             }
 
+            // Only adding coverage can affect bounds.
+            if color != Color::TRANSPARENT {
+                match path_device_bounds(path) {
+                    Some(rect) => self.extend_bounds(rect),
+                    None => self.bounds = Some(self.size.to_int_rect(0, 0)),
+                }
+            }
+
             // TODO: ignore paths outside the pixmap
 
             if let Some(tiler) = DrawTiler::new(self.width(), self.height()) {
@@ -299,7 +714,7 @@ impl Mask {
                         None => continue, // technically unreachable
                     };
 
-                    let mut blitter = match RasterPipelineBlitter::new_mask(&mut subpix) {
+                    let mut blitter = match RasterPipelineBlitter::new_mask(&mut subpix, color) {
                         Some(v) => v,
                         None => continue, // nothing to do, all good
                     };
@@ -322,7 +737,7 @@ impl Mask {
             } else {
                 let clip_rect = self.size().to_screen_int_rect(0, 0);
                 let mut subpix = self.as_subpixmap();
-                let mut blitter = match RasterPipelineBlitter::new_mask(&mut subpix) {
+                let mut blitter = match RasterPipelineBlitter::new_mask(&mut subpix, color) {
                     Some(v) => v,
                     None => return, // nothing to do, all good
                 };
@@ -342,38 +757,82 @@ impl Mask {
                 }
             };
 
-            self.fill_path(&path, fill_rule, anti_alias, Transform::identity());
+            self.fill_path_impl(&path, fill_rule, anti_alias, Transform::identity(), color);
         }
     }
+}
 
-    /// Intersects the provided path with the current clipping path.
-    ///
-    /// A temporary mask with the same size as the current one will be created.
-    pub fn intersect_path(
-        &mut self,
-        path: &Path,
-        fill_rule: FillRule,
-        anti_alias: bool,
-        transform: Transform,
-    ) {
-        let mut submask = Mask::new(self.width(), self.height()).unwrap();
-        submask.fill_path(path, fill_rule, anti_alias, transform);
+impl PartialEq for Mask {
+    fn eq(&self, other: &Self) -> bool {
+        // Bounds are conservative and not a part of the content.
+        self.size == other.size && self.data == other.data
+    }
+}
 
-        for (a, b) in self.data.iter_mut().zip(submask.data.iter()) {
-            *a = crate::color::premultiply_u8(*a, *b);
-        }
+/// Returns pixels that can be affected by filling the path, including anti-aliasing.
+fn path_device_bounds(path: &Path) -> Option<IntRect> {
+    path.bounds().round_out()?.make_outset(1, 1)
+}
+
+fn intersect_rects(a: Option<IntRect>, b: Option<IntRect>) -> Option<IntRect> {
+    a?.intersect(&b?)
+}
+
+fn union_rects(a: Option<IntRect>, b: Option<IntRect>) -> Option<IntRect> {
+    match (a, b) {
+        (Some(a), Some(b)) => IntRect::from_ltrb(
+            a.left().min(b.left()),
+            a.top().min(b.top()),
+            a.right().max(b.right()),
+            a.bottom().max(b.bottom()),
+        ),
+        (a, None) => a,
+        (None, b) => b,
     }
+}
 
-    /// Inverts the mask.
-    pub fn invert(&mut self) {
-        self.data.iter_mut().for_each(|a| *a = 255 - *a);
+/// Exact `v / 255`, rounded.
+#[inline]
+fn div255(v: u16x16) -> u16x16 {
+    let v = v + u16x16::splat(128);
+    (v + (v >> u16x16::splat(8))) >> u16x16::splat(8)
+}
+
+fn combine_row(dst: &mut [u8], src: &[u8], op: MaskOp) {
+    let mut dst_chunks = dst.chunks_exact_mut(16);
+    let mut src_chunks = src.chunks_exact(16);
+    for (d, s) in (&mut dst_chunks).zip(&mut src_chunks) {
+        let mut a = u16x16::default();
+        let mut b = u16x16::default();
+        for i in 0..16 {
+            a.0[i] = u16::from(d[i]);
+            b.0[i] = u16::from(s[i]);
+        }
+
+        let ab = div255(a * b);
+        let v = match op {
+            MaskOp::Intersect => ab,
+            MaskOp::Union => a + b - ab,
+            MaskOp::Difference => a - ab,
+            MaskOp::Xor => a + b - ab - ab,
+            MaskOp::ReverseDifference => b - ab,
+        };
+
+        for i in 0..16 {
+            d[i] = v.0[i] as u8;
+        }
     }
 
-    /// Clears the mask.
-    ///
-    /// Zero-fills the internal data buffer.
-    pub fn clear(&mut self) {
-        self.data.fill(0);
+    let dst = dst_chunks.into_remainder();
+    for (a, b) in dst.iter_mut().zip(src_chunks.remainder()) {
+        let ab = premultiply_u8(*a, *b);
+        *a = match op {
+            MaskOp::Intersect => ab,
+            MaskOp::Union => *a + (*b - ab),
+            MaskOp::Difference => *a - ab,
+            MaskOp::Xor => *a + (*b - ab) - ab,
+            MaskOp::ReverseDifference => *b - ab,
+        };
     }
 }
 
@@ -392,6 +851,8 @@ pub struct SubMaskRef<'a> {
     pub data: &'a [u8],
     pub size: IntSize,
     pub real_width: u32,
+    // Non-zero values bounds relative to the submask.
+    pub bounds: Option<IntRect>,
 }
 
 impl<'a> SubMaskRef<'a> {
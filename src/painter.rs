@@ -14,7 +14,7 @@ use crate::pipeline::{RasterPipelineBlitter, RasterPipelineBuilder};
 use crate::pixmap::SubPixmapMut;
 use crate::scan;
 
-use crate::geom::IntSizeExt;
+use crate::geom::{IntRectExt, IntSizeExt};
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
 use tiny_skia_path::NoStdFloat;
 
@@ -105,18 +105,50 @@ impl Paint<'_> {
     }
 }
 
+/// A clipping region.
+///
+/// Rectangular clips are way faster than masks, since they don't require
+/// a separate pipeline stage.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub enum Clip<'a> {
+    /// No clipping.
+    #[default]
+    None,
+    /// Clips to a pixel-aligned rectangle.
+    Rect(IntRect),
+    /// Clips to a rectangle with anti-aliased edges.
+    ///
+    /// Pixel-aligned rectangles are handled the same way as [`Clip::Rect`].
+    /// Otherwise, a temporary mask covering the rectangle will be allocated.
+    AntiAliasedRect(Rect),
+    /// Clips using a mask.
+    ///
+    /// `Mask` must have the same size as the pixmap.
+    Mask(&'a Mask),
+}
+
+impl<'a> Clip<'a> {
+    fn rect(&self) -> Option<IntRect> {
+        match self {
+            Clip::Rect(rect) => Some(*rect),
+            _ => None,
+        }
+    }
+
+    fn mask(&self) -> Option<&'a Mask> {
+        match self {
+            Clip::Mask(mask) => Some(mask),
+            _ => None,
+        }
+    }
+}
+
 impl Pixmap {
     /// Draws a filled rectangle onto the pixmap.
     ///
     /// See [`PixmapMut::fill_rect`](struct.PixmapMut.html#method.fill_rect) for details.
-    pub fn fill_rect(
-        &mut self,
-        rect: Rect,
-        paint: &Paint,
-        transform: Transform,
-        mask: Option<&Mask>,
-    ) {
-        self.as_mut().fill_rect(rect, paint, transform, mask);
+    pub fn fill_rect(&mut self, rect: Rect, paint: &Paint, transform: Transform, clip: Clip) {
+        self.as_mut().fill_rect(rect, paint, transform, clip);
     }
 
     /// Draws a filled path onto the pixmap.
@@ -128,10 +160,10 @@ impl Pixmap {
         paint: &Paint,
         fill_rule: FillRule,
         transform: Transform,
-        mask: Option<&Mask>,
+        clip: Clip,
     ) {
         self.as_mut()
-            .fill_path(path, paint, fill_rule, transform, mask);
+            .fill_path(path, paint, fill_rule, transform, clip);
     }
 
     /// Strokes a path.
@@ -143,10 +175,10 @@ impl Pixmap {
         paint: &Paint,
         stroke: &Stroke,
         transform: Transform,
-        mask: Option<&Mask>,
+        clip: Clip,
     ) {
         self.as_mut()
-            .stroke_path(path, paint, stroke, transform, mask);
+            .stroke_path(path, paint, stroke, transform, clip);
     }
 
     /// Draws a `Pixmap` on top of the current `Pixmap`.
@@ -159,10 +191,10 @@ impl Pixmap {
         pixmap: PixmapRef,
         paint: &PixmapPaint,
         transform: Transform,
-        mask: Option<&Mask>,
+        clip: Clip,
     ) {
         self.as_mut()
-            .draw_pixmap(x, y, pixmap, paint, transform, mask);
+            .draw_pixmap(x, y, pixmap, paint, transform, clip);
     }
 
     /// Applies a masks.
@@ -182,20 +214,38 @@ impl PixmapMut<'_> {
     /// clipping of horizontal/vertical edges.
     ///
     /// Used mainly to render a pixmap onto a pixmap.
-    pub fn fill_rect(
-        &mut self,
-        rect: Rect,
-        paint: &Paint,
-        transform: Transform,
-        mask: Option<&Mask>,
-    ) {
+    pub fn fill_rect(&mut self, rect: Rect, paint: &Paint, transform: Transform, clip: Clip) {
+        if let Clip::AntiAliasedRect(clip_rect) = clip {
+            self.draw_with_aa_rect_clip(clip_rect, |pixmap, ts, clip| {
+                if transform.is_identity() {
+                    // Keep using the rect filling instead of path filling.
+                    let rect = match rect.transform(ts) {
+                        Some(v) => v,
+                        None => return,
+                    };
+                    let mut paint = paint.clone();
+                    paint.shader.transform(ts);
+                    pixmap.fill_rect(rect, &paint, Transform::identity(), clip);
+                } else {
+                    pixmap.fill_rect(rect, paint, ts.pre_concat(transform), clip);
+                }
+            });
+            return;
+        }
+
         // TODO: we probably can use tiler for rect too
         if transform.is_identity() && !DrawTiler::required(self.width(), self.height()) {
             // TODO: ignore rects outside the pixmap
 
-            let clip = self.size().to_screen_int_rect(0, 0);
+            self.record_damage(rect, 0.0, clip);
+
+            let mask = clip.mask().map(|mask| mask.as_submask());
+            let region = self.size().to_screen_int_rect(0, 0);
+            let clip = match clip_rect(region, clip.rect(), mask) {
+                Some(v) => v,
+                None => return, // fully clipped-out
+            };
 
-            let mask = mask.map(|mask| mask.as_submask());
             let mut subpix = self.as_subpixmap();
             let mut blitter = match RasterPipelineBlitter::new(paint, mask, &mut subpix) {
                 Some(v) => v,
@@ -209,7 +259,7 @@ impl PixmapMut<'_> {
             }
         } else {
             let path = PathBuilder::from_rect(rect);
-            self.fill_path(&path, paint, FillRule::Winding, transform, mask);
+            self.fill_path(&path, paint, FillRule::Winding, transform, clip);
         }
     }
 
@@ -220,8 +270,15 @@ impl PixmapMut<'_> {
         paint: &Paint,
         fill_rule: FillRule,
         transform: Transform,
-        mask: Option<&Mask>,
+        clip: Clip,
     ) {
+        if let Clip::AntiAliasedRect(clip_rect) = clip {
+            self.draw_with_aa_rect_clip(clip_rect, |pixmap, ts, clip| {
+                pixmap.fill_path(path, paint, fill_rule, ts.pre_concat(transform), clip);
+            });
+            return;
+        }
+
         if transform.is_identity() {
             // This is sort of similar to SkDraw::drawPath
 
@@ -237,6 +294,9 @@ impl PixmapMut<'_> {
                 return;
             }
 
+            let outset = if paint.anti_alias { 1.0 } else { 0.0 };
+            self.record_damage(path_bounds, outset, clip);
+
             // TODO: ignore paths outside the pixmap
 
             if let Some(tiler) = DrawTiler::new(self.width(), self.height()) {
@@ -244,6 +304,14 @@ impl PixmapMut<'_> {
                 let mut paint = paint.clone();
 
                 for tile in tiler {
+                    let submask = clip
+                        .mask()
+                        .and_then(|mask| mask.submask(tile.to_int_rect()));
+                    let clip_rect = match clip_rect(tile, clip.rect(), submask) {
+                        Some(v) => v,
+                        None => continue, // fully clipped-out
+                    };
+
                     let ts = Transform::from_translate(-(tile.x() as f32), -(tile.y() as f32));
                     path = match path.transform(ts) {
                         Some(v) => v,
@@ -254,26 +322,22 @@ impl PixmapMut<'_> {
                     };
                     paint.shader.transform(ts);
 
-                    let clip_rect = tile.size().to_screen_int_rect(0, 0);
                     let mut subpix = match self.subpixmap(tile.to_int_rect()) {
                         Some(v) => v,
                         None => continue, // technically unreachable
                     };
 
-                    let submask = mask.and_then(|mask| mask.submask(tile.to_int_rect()));
-                    let mut blitter = match RasterPipelineBlitter::new(&paint, submask, &mut subpix)
-                    {
-                        Some(v) => v,
-                        None => continue, // nothing to do, all good
-                    };
-
                     // We're ignoring "errors" here, because `fill_path` will return `None`
                     // when rendering a tile that doesn't have a path on it.
                     // Which is not an error in this case.
-                    if paint.anti_alias {
-                        scan::path_aa::fill_path(&path, fill_rule, &clip_rect, &mut blitter);
-                    } else {
-                        scan::path::fill_path(&path, fill_rule, &clip_rect, &mut blitter);
+                    if let Some(mut blitter) =
+                        RasterPipelineBlitter::new(&paint, submask, &mut subpix)
+                    {
+                        if paint.anti_alias {
+                            scan::path_aa::fill_path(&path, fill_rule, &clip_rect, &mut blitter);
+                        } else {
+                            scan::path::fill_path(&path, fill_rule, &clip_rect, &mut blitter);
+                        }
                     }
 
                     let ts = Transform::from_translate(tile.x() as f32, tile.y() as f32);
@@ -284,8 +348,13 @@ impl PixmapMut<'_> {
                     paint.shader.transform(ts);
                 }
             } else {
-                let clip_rect = self.size().to_screen_int_rect(0, 0);
-                let submask = mask.map(|mask| mask.as_submask());
+                let submask = clip.mask().map(|mask| mask.as_submask());
+                let region = self.size().to_screen_int_rect(0, 0);
+                let clip_rect = match clip_rect(region, clip.rect(), submask) {
+                    Some(v) => v,
+                    None => return, // fully clipped-out
+                };
+
                 let mut subpix = self.as_subpixmap();
                 let mut blitter = match RasterPipelineBlitter::new(paint, submask, &mut subpix) {
                     Some(v) => v,
@@ -310,7 +379,7 @@ impl PixmapMut<'_> {
             let mut paint = paint.clone();
             paint.shader.transform(transform);
 
-            self.fill_path(&path, &paint, fill_rule, Transform::identity(), mask)
+            self.fill_path(&path, &paint, fill_rule, Transform::identity(), clip)
         }
     }
 
@@ -324,6 +393,7 @@ impl PixmapMut<'_> {
     ///    then the original path.
     /// 2. If a stroke width is thinner than 1px (after applying the transformation),
     ///    we will use hairline stroking, which doesn't involve a separate path allocation.
+    ///    Strokes that aren't centered are converted into a path, unless their width is zero.
     ///
     /// Also, if a `stroke` has a dash array, then path will be converted into
     /// a dashed path first and then stroked. Which means a yet another allocation.
@@ -333,13 +403,20 @@ impl PixmapMut<'_> {
         paint: &Paint,
         stroke: &Stroke,
         transform: Transform,
-        mask: Option<&Mask>,
+        clip: Clip,
     ) {
         if stroke.width < 0.0 {
             log::warn!("negative stroke width isn't allowed");
             return;
         }
 
+        if let Clip::AntiAliasedRect(clip_rect) = clip {
+            self.draw_with_aa_rect_clip(clip_rect, |pixmap, ts, clip| {
+                pixmap.stroke_path(path, paint, stroke, ts.pre_concat(transform), clip);
+            });
+            return;
+        }
+
         let res_scale = PathStroker::compute_resolution_scale(&transform);
 
         let dash_path;
@@ -369,6 +446,11 @@ impl PixmapMut<'_> {
                 paint.shader.apply_opacity(new_alpha as f32 / 255.0);
             }
 
+            if let Some(bounds) = path.bounds().transform(transform) {
+                // Hairlines are up to 1px wide, plus caps and anti-aliasing.
+                self.record_damage(bounds, 2.0, clip);
+            }
+
             if let Some(tiler) = DrawTiler::new(self.width(), self.height()) {
                 let mut path = path.clone(); // TODO: avoid cloning
                 let mut paint = paint.clone();
@@ -385,6 +467,14 @@ impl PixmapMut<'_> {
                 }
 
                 for tile in tiler {
+                    let submask = clip
+                        .mask()
+                        .and_then(|mask| mask.submask(tile.to_int_rect()));
+                    let clip_rect = match clip_rect(tile, clip.rect(), submask) {
+                        Some(v) => v,
+                        None => continue, // fully clipped-out
+                    };
+
                     let ts = Transform::from_translate(-(tile.x() as f32), -(tile.y() as f32));
                     path = match path.transform(ts) {
                         Some(v) => v,
@@ -399,12 +489,18 @@ impl PixmapMut<'_> {
                         Some(v) => v,
                         None => continue, // technically unreachable
                     };
-                    let submask = mask.and_then(|mask| mask.submask(tile.to_int_rect()));
 
                     // We're ignoring "errors" here, because `stroke_hairline` will return `None`
                     // when rendering a tile that doesn't have a path on it.
                     // Which is not an error in this case.
-                    Self::stroke_hairline(&path, &paint, stroke.line_cap, submask, &mut subpix);
+                    Self::stroke_hairline(
+                        &path,
+                        &paint,
+                        stroke.line_cap,
+                        &clip_rect,
+                        submask,
+                        &mut subpix,
+                    );
 
                     let ts = Transform::from_translate(tile.x() as f32, tile.y() as f32);
                     path = match path.transform(ts) {
@@ -414,8 +510,14 @@ impl PixmapMut<'_> {
                     paint.shader.transform(ts);
                 }
             } else {
+                let submask = clip.mask().map(|mask| mask.as_submask());
+                let region = self.size().to_screen_int_rect(0, 0);
+                let clip_rect = match clip_rect(region, clip.rect(), submask) {
+                    Some(v) => v,
+                    None => return, // fully clipped-out
+                };
+
                 let subpix = &mut self.as_subpixmap();
-                let submask = mask.map(|mask| mask.as_submask());
                 if !transform.is_identity() {
                     paint.shader.transform(transform);
 
@@ -428,9 +530,23 @@ impl PixmapMut<'_> {
                         }
                     };
 
-                    Self::stroke_hairline(&path, &paint, stroke.line_cap, submask, subpix);
+                    Self::stroke_hairline(
+                        &path,
+                        &paint,
+                        stroke.line_cap,
+                        &clip_rect,
+                        submask,
+                        subpix,
+                    );
                 } else {
-                    Self::stroke_hairline(path, &paint, stroke.line_cap, submask, subpix);
+                    Self::stroke_hairline(
+                        path,
+                        &paint,
+                        stroke.line_cap,
+                        &clip_rect,
+                        submask,
+                        subpix,
+                    );
                 }
             }
         } else {
@@ -442,7 +558,7 @@ impl PixmapMut<'_> {
                 }
             };
 
-            self.fill_path(&path, paint, FillRule::Winding, transform, mask);
+            self.fill_path(&path, paint, FillRule::Winding, transform, clip);
         }
     }
 
@@ -451,18 +567,94 @@ impl PixmapMut<'_> {
         path: &Path,
         paint: &Paint,
         line_cap: LineCap,
+        clip: &ScreenIntRect,
         mask: Option<SubMaskRef>,
         pixmap: &mut SubPixmapMut,
     ) {
-        let clip = pixmap.size.to_screen_int_rect(0, 0);
         let mut blitter = match RasterPipelineBlitter::new(paint, mask, pixmap) {
             Some(v) => v,
             None => return, // nothing to do, all good
         };
         if paint.anti_alias {
-            scan::hairline_aa::stroke_path(path, line_cap, &clip, &mut blitter);
+            scan::hairline_aa::stroke_path(path, line_cap, clip, &mut blitter);
         } else {
-            scan::hairline::stroke_path(path, line_cap, &clip, &mut blitter);
+            scan::hairline::stroke_path(path, line_cap, clip, &mut blitter);
+        }
+    }
+
+    /// Renders using an anti-aliased rect clip.
+    ///
+    /// Integer rects are converted into `Clip::Rect`. Otherwise, a temporary mask
+    /// covering only the rect is created and the `draw` callback is executed
+    /// on the corresponding pixmap region. In which case, the callback must apply
+    /// the provided translation to the geometry and shader.
+    fn draw_with_aa_rect_clip(
+        &mut self,
+        rect: Rect,
+        draw: impl FnOnce(&mut PixmapMut, Transform, Clip),
+    ) {
+        let region = match rect
+            .round_out()
+            .and_then(|r| r.intersect(&self.size().to_int_rect(0, 0)))
+        {
+            Some(v) => v,
+            None => return, // fully clipped-out
+        };
+
+        if rect.round() == rect.round_out() && rect.round().map(|r| r.to_rect()) == Some(rect) {
+            draw(self, Transform::identity(), Clip::Rect(region));
+            return;
+        }
+
+        let ts = Transform::from_translate(-region.x() as f32, -region.y() as f32);
+        let mut mask = match Mask::new(region.width(), region.height()) {
+            Some(v) => v,
+            None => return, // technically unreachable
+        };
+        mask.fill_path(&PathBuilder::from_rect(rect), FillRule::Winding, true, ts);
+
+        let is_tracking_damage = self.damage().is_some();
+        let damage = match self.region_mut(region) {
+            Some(mut pixmap) => {
+                if is_tracking_damage {
+                    pixmap.enable_damage_tracking();
+                }
+
+                draw(&mut pixmap, ts, Clip::Mask(&mask));
+                pixmap.take_damage()
+            }
+            None => None,
+        };
+
+        if let Some(damage) = damage {
+            for rect in damage.rects() {
+                if let Some(rect) = rect.translate(region.x(), region.y()) {
+                    self.add_damage(rect);
+                }
+            }
+        }
+    }
+
+    /// Adds device-space shape bounds to the damage region.
+    ///
+    /// `outset` accounts for anti-aliasing and hairlines.
+    fn record_damage(&mut self, bounds: Rect, outset: f32, clip: Clip) {
+        if self.damage().is_none() {
+            return;
+        }
+
+        let mask = clip.mask().map(|mask| mask.as_submask());
+        let clip = match clip_rect(self.size().to_screen_int_rect(0, 0), clip.rect(), mask) {
+            Some(v) => v,
+            None => return, // fully clipped-out
+        };
+
+        let rect = bounds
+            .outset(outset, outset)
+            .and_then(|r| r.round_out())
+            .and_then(|r| r.intersect(&clip.to_int_rect()));
+        if let Some(rect) = rect {
+            self.add_damage(rect);
         }
     }
 
@@ -476,7 +668,7 @@ impl PixmapMut<'_> {
         pixmap: PixmapRef,
         paint: &PixmapPaint,
         transform: Transform,
-        mask: Option<&Mask>,
+        clip: Clip,
     ) {
         let rect = pixmap.size().to_int_rect(x, y).to_rect();
 
@@ -501,7 +693,7 @@ impl PixmapMut<'_> {
             colorspace: ColorSpace::default(),
         };
 
-        self.fill_rect(rect, &paint, transform, mask);
+        self.fill_rect(rect, &paint, transform, clip);
     }
 
     /// Applies a masks.
@@ -521,14 +713,16 @@ impl PixmapMut<'_> {
             return;
         }
 
+        self.add_damage(self.size().to_int_rect(0, 0));
+
         // Just a dummy.
         let pixmap_src = PixmapRef::from_bytes(&[0, 0, 0, 0], 1, 1).unwrap();
 
         let mut p = RasterPipelineBuilder::new();
         p.push(pipeline::Stage::LoadMaskU8);
-        p.push(pipeline::Stage::LoadDestination);
+        p.push(self.format().load_dst_stage());
         p.push(pipeline::Stage::DestinationIn);
-        p.push(pipeline::Stage::Store);
+        p.push(self.format().store_stage());
         let mut p = p.compile();
         let rect = self.size().to_screen_int_rect(0, 0);
         p.run(
@@ -536,11 +730,36 @@ impl PixmapMut<'_> {
             pipeline::AAMaskCtx::default(),
             mask.as_submask().mask_ctx(),
             pixmap_src,
+            None,
             &mut self.as_subpixmap(),
         );
     }
 }
 
+/// Returns a clip rect for a pixmap region, relative to the region.
+///
+/// `rect` is in pixmap coordinates, while `mask` must be already limited to the region.
+///
+/// Returns `None` when nothing can be drawn.
+fn clip_rect(
+    region: ScreenIntRect,
+    rect: Option<IntRect>,
+    mask: Option<SubMaskRef>,
+) -> Option<ScreenIntRect> {
+    let mut clip = region.size().to_int_rect(0, 0);
+    if let Some(rect) = rect {
+        let rect = rect.translate(-(region.x() as i32), -(region.y() as i32))?;
+        clip = clip.intersect(&rect)?;
+    }
+
+    if let Some(mask) = mask {
+        // Limit to non-zero mask values.
+        clip = clip.intersect(&mask.bounds?)?;
+    }
+
+    clip.to_screen_int_rect()
+}
+
 fn treat_as_hairline(paint: &Paint, stroke: &Stroke, mut ts: Transform) -> Option<f32> {
     fn fast_len(p: Point) -> f32 {
         let mut x = p.x.abs();
@@ -562,6 +781,11 @@ fn treat_as_hairline(paint: &Paint, stroke: &Stroke, mut ts: Transform) -> Optio
         return None;
     }
 
+    // A modulated hairline is always centered.
+    if stroke.alignment != StrokeAlignment::Center {
+        return None;
+    }
+
     // We don't care about translate.
     ts.tx = 0.0;
     ts.ty = 0.0;
@@ -10,8 +10,8 @@ use tiny_skia_path::{NormalizedF32, NormalizedF32Exclusive, Point};
 use tiny_skia_path::NoStdFloat;
 
 pub use tiny_skia_path::path_geometry::{
-    chop_cubic_at2, chop_quad_at, find_cubic_max_curvature, find_unit_quad_roots, new_t_values,
-    CubicCoeff, QuadCoeff,
+    chop_cubic_at2, chop_mono_cubic_at_x, chop_mono_cubic_at_y, chop_quad_at,
+    find_cubic_max_curvature, find_unit_quad_roots, new_t_values, CubicCoeff, QuadCoeff,
 };
 
 use tiny_skia_path::path_geometry::valid_unit_divide;
@@ -238,49 +238,6 @@ pub fn chop_cubic_at_max_curvature(
     count + 1
 }
 
-pub fn chop_mono_cubic_at_x(src: &[Point; 4], x: f32, dst: &mut [Point; 7]) -> bool {
-    cubic_dchop_at_intercept(src, x, true, dst)
-}
-
-pub fn chop_mono_cubic_at_y(src: &[Point; 4], y: f32, dst: &mut [Point; 7]) -> bool {
-    cubic_dchop_at_intercept(src, y, false, dst)
-}
-
-fn cubic_dchop_at_intercept(
-    src: &[Point; 4],
-    intercept: f32,
-    is_vertical: bool,
-    dst: &mut [Point; 7],
-) -> bool {
-    use crate::path64::{cubic64::Cubic64, line_cubic_intersections, point64::Point64};
-
-    let src = [
-        Point64::from_point(src[0]),
-        Point64::from_point(src[1]),
-        Point64::from_point(src[2]),
-        Point64::from_point(src[3]),
-    ];
-
-    let cubic = Cubic64::new(src);
-    let mut roots = [0.0; 3];
-    let count = if is_vertical {
-        line_cubic_intersections::vertical_intersect(&cubic, f64::from(intercept), &mut roots)
-    } else {
-        line_cubic_intersections::horizontal_intersect(&cubic, f64::from(intercept), &mut roots)
-    };
-
-    if count > 0 {
-        let pair = cubic.chop_at(roots[0]);
-        for i in 0..7 {
-            dst[i] = pair.points[i].to_point();
-        }
-
-        true
-    } else {
-        false
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,7 +4,8 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
-use crate::{BlendMode, Color, LengthU32, Paint, PixmapRef, PremultipliedColorU8, Shader};
+use crate::{BlendMode, Color, LengthU32, Paint, PixelFormat, Pixmap, PixmapRef};
+use crate::{PremultipliedColorU8, Shader, ShaderImpl};
 use crate::{ALPHA_U8_OPAQUE, ALPHA_U8_TRANSPARENT};
 
 use crate::alpha_runs::AlphaRun;
@@ -19,6 +20,9 @@ use crate::pixmap::SubPixmapMut;
 pub struct RasterPipelineBlitter<'a, 'b: 'a> {
     mask: Option<SubMaskRef<'a>>,
     pixmap_src: PixmapRef<'a>,
+    // A mipmap level generated for this blitter only. Overrides `pixmap_src`.
+    pixmap_src_level: Option<Pixmap>,
+    custom_shader: Option<&'a dyn ShaderImpl>,
     pixmap: &'a mut SubPixmapMut<'b>,
     memset2d_color: Option<PremultipliedColorU8>,
     blit_anti_h_rp: RasterPipeline,
@@ -54,6 +58,10 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
             _ => {}
         }
 
+        let format = pixmap.format;
+        let load_dst_stage = format.load_dst_stage();
+        let store_stage = format.store_stage();
+
         // We can strength-reduce SourceOver into Source when opaque.
         let mut blend_mode = paint.blend_mode;
         if paint.shader.is_opaque() && blend_mode == BlendMode::SourceOver && mask.is_none() {
@@ -61,17 +69,29 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
         }
 
         // When we're drawing a constant color in Source mode, we can sometimes just memset.
+        // Only 4 bytes per pixel formats are supported.
+        let can_memset = matches!(format, PixelFormat::Rgba8888 | PixelFormat::Bgra8888);
         let mut memset2d_color = None;
-        if paint.is_solid_color() && blend_mode == BlendMode::Source && mask.is_none() {
+        if can_memset && paint.is_solid_color() && blend_mode == BlendMode::Source && mask.is_none()
+        {
             // Unlike Skia, our shader cannot be constant.
             // Therefore there is no need to run a raster pipeline to get shader's color.
             if let Shader::SolidColor(ref color) = paint.shader {
-                memset2d_color = Some(color.premultiply().to_color_u8());
+                let c = color.premultiply().to_color_u8();
+                memset2d_color = Some(match format {
+                    PixelFormat::Bgra8888 => PremultipliedColorU8::from_rgba_unchecked(
+                        c.blue(),
+                        c.green(),
+                        c.red(),
+                        c.alpha(),
+                    ),
+                    _ => c,
+                });
             }
         };
 
         // Clear is just a transparent color memset.
-        if blend_mode == BlendMode::Clear && !paint.anti_alias && mask.is_none() {
+        if can_memset && blend_mode == BlendMode::Clear && !paint.anti_alias && mask.is_none() {
             blend_mode = BlendMode::Source;
             memset2d_color = Some(PremultipliedColorU8::TRANSPARENT);
         }
@@ -89,7 +109,7 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
 
             if blend_mode.should_pre_scale_coverage() {
                 p.push(pipeline::Stage::Scale1Float);
-                p.push(pipeline::Stage::LoadDestination);
+                p.push(load_dst_stage);
                 if let Some(stage) = paint.colorspace.expand_dest_stage() {
                     p.push(stage);
                 }
@@ -97,7 +117,7 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
                     p.push(blend_stage);
                 }
             } else {
-                p.push(pipeline::Stage::LoadDestination);
+                p.push(load_dst_stage);
                 if let Some(stage) = paint.colorspace.expand_dest_stage() {
                     p.push(stage);
                 }
@@ -111,7 +131,7 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
             if let Some(stage) = paint.colorspace.compress_stage() {
                 p.push(stage);
             }
-            p.push(pipeline::Stage::Store);
+            p.push(store_stage);
 
             p.compile()
         };
@@ -127,7 +147,10 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
                 p.push(pipeline::Stage::MaskU8);
             }
 
-            if blend_mode == BlendMode::SourceOver && mask.is_none() {
+            if blend_mode == BlendMode::SourceOver
+                && mask.is_none()
+                && format == PixelFormat::Rgba8888
+            {
                 if let Some(stage) = paint.colorspace.compress_stage() {
                     p.push(stage);
                 }
@@ -135,7 +158,7 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
                 p.push(pipeline::Stage::SourceOverRgba);
             } else {
                 if blend_mode != BlendMode::Source {
-                    p.push(pipeline::Stage::LoadDestination);
+                    p.push(load_dst_stage);
                     if let Some(blend_stage) = blend_mode.to_stage() {
                         if let Some(stage) = paint.colorspace.expand_dest_stage() {
                             p.push(stage);
@@ -147,7 +170,7 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
                 if let Some(stage) = paint.colorspace.compress_stage() {
                     p.push(stage);
                 }
-                p.push(pipeline::Stage::Store);
+                p.push(store_stage);
             }
 
             p.compile()
@@ -166,7 +189,7 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
 
             if blend_mode.should_pre_scale_coverage() {
                 p.push(pipeline::Stage::ScaleU8);
-                p.push(pipeline::Stage::LoadDestination);
+                p.push(load_dst_stage);
                 if let Some(stage) = paint.colorspace.expand_dest_stage() {
                     p.push(stage);
                 }
@@ -174,7 +197,7 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
                     p.push(blend_stage);
                 }
             } else {
-                p.push(pipeline::Stage::LoadDestination);
+                p.push(load_dst_stage);
                 if let Some(stage) = paint.colorspace.expand_dest_stage() {
                     p.push(stage);
                 }
@@ -188,20 +211,25 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
             if let Some(stage) = paint.colorspace.compress_stage() {
                 p.push(stage);
             }
-            p.push(pipeline::Stage::Store);
+            p.push(store_stage);
 
             p.compile()
         };
 
-        let pixmap_src = match paint.shader {
-            Shader::Pattern(ref patt) => patt.pixmap,
+        let (pixmap_src, pixmap_src_level) = match paint.shader {
+            Shader::Pattern(ref patt) => patt.source_pixmap(),
             // Just a dummy one.
-            _ => PixmapRef::from_bytes(&[0, 0, 0, 0], 1, 1).unwrap(),
+            _ => (PixmapRef::from_bytes(&[0, 0, 0, 0], 1, 1).unwrap(), None),
         };
 
         Some(RasterPipelineBlitter {
             mask,
             pixmap_src,
+            pixmap_src_level,
+            custom_shader: match paint.shader {
+                Shader::Custom(ref c) => Some(c.shader),
+                _ => None,
+            },
             pixmap,
             memset2d_color,
             blit_anti_h_rp,
@@ -211,8 +239,11 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
         })
     }
 
-    pub fn new_mask(pixmap: &'a mut SubPixmapMut<'b>) -> Option<Self> {
-        let color = Color::WHITE.premultiply();
+    /// Creates a mask blitter that interpolates coverage towards `color`.
+    ///
+    /// White adds coverage and transparent removes it.
+    pub fn new_mask(pixmap: &'a mut SubPixmapMut<'b>, color: Color) -> Option<Self> {
+        let color = color.premultiply();
 
         let memset2d_color = Some(color.to_color_u8());
 
@@ -244,6 +275,8 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
         Some(RasterPipelineBlitter {
             mask: None,
             pixmap_src: PixmapRef::from_bytes(&[0, 0, 0, 0], 1, 1).unwrap(),
+            pixmap_src_level: None,
+            custom_shader: None,
             pixmap,
             memset2d_color,
             blit_anti_h_rp,
@@ -282,7 +315,8 @@ impl Blitter for RasterPipelineBlitter<'_, '_> {
                         &rect,
                         pipeline::AAMaskCtx::default(),
                         mask_ctx,
-                        self.pixmap_src,
+                        source_pixmap(self.pixmap_src, &self.pixmap_src_level),
+                        self.custom_shader,
                         self.pixmap,
                     );
                 }
@@ -364,7 +398,8 @@ impl Blitter for RasterPipelineBlitter<'_, '_> {
             rect,
             pipeline::AAMaskCtx::default(),
             mask_ctx,
-            self.pixmap_src,
+            source_pixmap(self.pixmap_src, &self.pixmap_src_level),
+            self.custom_shader,
             self.pixmap,
         );
     }
@@ -378,7 +413,21 @@ impl Blitter for RasterPipelineBlitter<'_, '_> {
 
         let mask_ctx = self.mask.map(|c| c.mask_ctx()).unwrap_or_default();
 
-        self.blit_mask_rp
-            .run(clip, aa_mask_ctx, mask_ctx, self.pixmap_src, self.pixmap);
+        self.blit_mask_rp.run(
+            clip,
+            aa_mask_ctx,
+            mask_ctx,
+            source_pixmap(self.pixmap_src, &self.pixmap_src_level),
+            self.custom_shader,
+            self.pixmap,
+        );
+    }
+}
+
+#[inline]
+fn source_pixmap<'a>(pixmap: PixmapRef<'a>, level: &'a Option<Pixmap>) -> PixmapRef<'a> {
+    match level {
+        Some(level) => level.as_ref(),
+        None => pixmap,
     }
 }
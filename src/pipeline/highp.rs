@@ -15,9 +15,10 @@ For some reason, we are almost 2x slower. Maybe because Skia uses clang's vector
 and we're using a manual implementation.
 */
 
-use crate::{PremultipliedColorU8, SpreadMode, PixmapRef};
+use crate::{PremultipliedColorU8, ShaderImpl, SpreadMode, PixmapRef};
 
 use crate::geom::ScreenIntRect;
+use crate::pixel_format::{f16_to_f32, f32_to_f16, pack_565, unpack_565};
 use crate::pixmap::SubPixmapMut;
 use crate::wide::{f32x8, i32x8, u32x8};
 
@@ -29,6 +30,7 @@ pub struct Pipeline<'a, 'b: 'a> {
     index: usize,
     functions: &'a [StageFn],
     pixmap_src: PixmapRef<'a>,
+    custom_shader: Option<&'a dyn ShaderImpl>,
     pixmap_dst: &'a mut SubPixmapMut<'b>,
     ctx: &'a mut super::Context, // TODO: remove mut
     mask_ctx: super::MaskCtx<'a>,
@@ -68,6 +70,16 @@ pub const STAGES: &[StageFn; super::STAGES_COUNT] = &[
     store,
     load_dst_u8,
     store_u8,
+    load_dst_bgra,
+    store_bgra,
+    load_dst_565,
+    store_565,
+    load_dst_g8,
+    store_g8,
+    load_dst_16161616,
+    store_16161616,
+    load_dst_f16,
+    store_f16,
     gather,
     load_mask_u8,
     mask_u8,
@@ -136,6 +148,8 @@ pub const STAGES: &[StageFn; super::STAGES_COUNT] = &[
     gamma_expand_srgb,
     gamma_expand_dst_srgb,
     gamma_compress_srgb,
+    custom_shader,
+    perlin_noise,
 ];
 
 pub fn fn_ptr(f: StageFn) -> *const () {
@@ -151,12 +165,14 @@ pub fn start(
     mask_ctx: super::MaskCtx,
     ctx: &mut super::Context,
     pixmap_src: PixmapRef,
+    custom_shader: Option<&dyn ShaderImpl>,
     pixmap_dst: &mut SubPixmapMut,
 ) {
     let mut p = Pipeline {
         index: 0,
         functions: &[],
         pixmap_src,
+        custom_shader,
         pixmap_dst,
         mask_ctx,
         aa_mask_ctx,
@@ -289,21 +305,187 @@ pub fn store_tail(p: &mut Pipeline) {
     p.next_stage();
 }
 
-// Currently, all mask/A8 pixmaps are handled by lowp.
-pub fn load_dst_u8(_: &mut Pipeline) {
-    // unreachable
+pub fn load_dst_u8(p: &mut Pipeline) {
+    load_8(p.tail, p.pixmap_dst.slice_mask_at_xy(p.dx, p.dy), &mut p.da);
+    p.dr = f32x8::default();
+    p.dg = f32x8::default();
+    p.db = f32x8::default();
+    p.next_stage();
 }
 
-pub fn load_dst_u8_tail(_: &mut Pipeline) {
-    // unreachable
+pub fn load_dst_u8_tail(p: &mut Pipeline) {
+    load_dst_u8(p)
 }
 
-pub fn store_u8(_: &mut Pipeline) {
-    // unreachable
+pub fn store_u8(p: &mut Pipeline) {
+    store_8(&p.a, p.tail, p.pixmap_dst.slice_mask_at_xy(p.dx, p.dy));
+    p.next_stage();
 }
 
-pub fn store_u8_tail(_: &mut Pipeline) {
-    // unreachable
+pub fn store_u8_tail(p: &mut Pipeline) {
+    store_u8(p)
+}
+
+pub fn load_dst_bgra(p: &mut Pipeline) {
+    load_8888(p.pixmap_dst.slice4_at_xy(p.dx, p.dy), &mut p.db, &mut p.dg, &mut p.dr, &mut p.da);
+    p.next_stage();
+}
+
+pub fn load_dst_bgra_tail(p: &mut Pipeline) {
+    load_8888_tail(p.tail, p.pixmap_dst.slice_at_xy(p.dx, p.dy), &mut p.db, &mut p.dg, &mut p.dr, &mut p.da);
+    p.next_stage();
+}
+
+pub fn store_bgra(p: &mut Pipeline) {
+    store_8888(&p.b, &p.g, &p.r, &p.a, p.pixmap_dst.slice4_at_xy(p.dx, p.dy));
+    p.next_stage();
+}
+
+pub fn store_bgra_tail(p: &mut Pipeline) {
+    store_8888_tail(&p.b, &p.g, &p.r, &p.a, p.tail, p.pixmap_dst.slice_at_xy(p.dx, p.dy));
+    p.next_stage();
+}
+
+// Formats below are handling the tail manually, since they are not performance critical.
+
+fn load_dst_565(p: &mut Pipeline) {
+    let data = p.pixmap_dst.slice_bytes_at_xy(p.dx, p.dy);
+    let mut r = [0.0; STAGE_WIDTH];
+    let mut g = [0.0; STAGE_WIDTH];
+    let mut b = [0.0; STAGE_WIDTH];
+    for i in 0..STAGE_WIDTH {
+        let (r5, g6, b5) = unpack_565(u16::from_ne_bytes([data[i * 2], data[i * 2 + 1]]));
+        r[i] = r5 as f32 * (1.0 / 31.0);
+        g[i] = g6 as f32 * (1.0 / 63.0);
+        b[i] = b5 as f32 * (1.0 / 31.0);
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.dr = f32x8::from(r);
+    p.dg = f32x8::from(g);
+    p.db = f32x8::from(b);
+    p.da = f32x8::splat(1.0);
+
+    p.next_stage();
+}
+
+fn store_565(p: &mut Pipeline) {
+    let r: [f32; STAGE_WIDTH] = p.r.into();
+    let g: [f32; STAGE_WIDTH] = p.g.into();
+    let b: [f32; STAGE_WIDTH] = p.b.into();
+    let data = p.pixmap_dst.slice_bytes_at_xy(p.dx, p.dy);
+    for i in 0..STAGE_WIDTH {
+        let v = pack_565(r[i], g[i], b[i]);
+        data[i * 2..i * 2 + 2].copy_from_slice(&v.to_ne_bytes());
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.next_stage();
+}
+
+fn load_dst_g8(p: &mut Pipeline) {
+    let mut v = f32x8::default();
+    load_8(p.tail, p.pixmap_dst.slice_bytes_at_xy(p.dx, p.dy), &mut v);
+    p.dr = v;
+    p.dg = v;
+    p.db = v;
+    p.da = f32x8::splat(1.0);
+
+    p.next_stage();
+}
+
+fn store_g8(p: &mut Pipeline) {
+    let luma = p.r * f32x8::splat(0.2126) + p.g * f32x8::splat(0.7152) + p.b * f32x8::splat(0.0722);
+    store_8(&luma, p.tail, p.pixmap_dst.slice_bytes_at_xy(p.dx, p.dy));
+
+    p.next_stage();
+}
+
+fn load_dst_16161616(p: &mut Pipeline) {
+    let data = p.pixmap_dst.slice_bytes_at_xy(p.dx, p.dy);
+    let mut c = [[0.0; STAGE_WIDTH]; 4];
+    for i in 0..STAGE_WIDTH {
+        for (n, channel) in c.iter_mut().enumerate() {
+            let idx = i * 8 + n * 2;
+            channel[i] = u16::from_ne_bytes([data[idx], data[idx + 1]]) as f32 * (1.0 / 65535.0);
+        }
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.dr = f32x8::from(c[0]);
+    p.dg = f32x8::from(c[1]);
+    p.db = f32x8::from(c[2]);
+    p.da = f32x8::from(c[3]);
+
+    p.next_stage();
+}
+
+fn store_16161616(p: &mut Pipeline) {
+    let norm = |v: f32x8| -> [f32; STAGE_WIDTH] {
+        (v.max(f32x8::default()).min(f32x8::splat(1.0)) * f32x8::splat(65535.0)).round().into()
+    };
+    let c = [norm(p.r), norm(p.g), norm(p.b), norm(p.a)];
+    let data = p.pixmap_dst.slice_bytes_at_xy(p.dx, p.dy);
+    for i in 0..STAGE_WIDTH {
+        for (n, channel) in c.iter().enumerate() {
+            let idx = i * 8 + n * 2;
+            data[idx..idx + 2].copy_from_slice(&(channel[i] as u16).to_ne_bytes());
+        }
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.next_stage();
+}
+
+fn load_dst_f16(p: &mut Pipeline) {
+    let data = p.pixmap_dst.slice_bytes_at_xy(p.dx, p.dy);
+    let mut c = [[0.0; STAGE_WIDTH]; 4];
+    for i in 0..STAGE_WIDTH {
+        for (n, channel) in c.iter_mut().enumerate() {
+            let idx = i * 8 + n * 2;
+            channel[i] = f16_to_f32(u16::from_ne_bytes([data[idx], data[idx + 1]]));
+        }
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.dr = f32x8::from(c[0]);
+    p.dg = f32x8::from(c[1]);
+    p.db = f32x8::from(c[2]);
+    p.da = f32x8::from(c[3]);
+
+    p.next_stage();
+}
+
+fn store_f16(p: &mut Pipeline) {
+    let c: [[f32; STAGE_WIDTH]; 4] = [p.r.into(), p.g.into(), p.b.into(), p.a.into()];
+    let data = p.pixmap_dst.slice_bytes_at_xy(p.dx, p.dy);
+    for i in 0..STAGE_WIDTH {
+        for (n, channel) in c.iter().enumerate() {
+            let idx = i * 8 + n * 2;
+            data[idx..idx + 2].copy_from_slice(&f32_to_f16(channel[i]).to_ne_bytes());
+        }
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.next_stage();
 }
 
 pub fn gather(p: &mut Pipeline) {
@@ -321,7 +503,7 @@ fn gather_ix(pixmap: PixmapRef, mut x: f32x8, mut y: f32x8) -> u32x8 {
     x = x.max(f32x8::default()).min(f32x8::splat(w));
     y = y.max(f32x8::default()).min(f32x8::splat(h));
 
-    (y.trunc_int() * i32x8::splat(pixmap.width() as i32) + x.trunc_int()).to_u32x8_bitcast()
+    (y.trunc_int() * i32x8::splat(pixmap.real_width() as i32) + x.trunc_int()).to_u32x8_bitcast()
 }
 
 #[inline(always)]
@@ -336,9 +518,17 @@ fn load_mask_u8(_: &mut Pipeline) {
 
 fn mask_u8(p: &mut Pipeline) {
     let offset = p.mask_ctx.offset(p.dx, p.dy);
+    let data = &p.mask_ctx.data[offset..offset + p.tail];
+
+    // Fully opaque mask doesn't affect the color.
+    if data.iter().all(|a| *a == 255) {
+        p.next_stage();
+        return;
+    }
+
     let mut c = [0.0; 8];
-    for i in 0..p.tail {
-        c[i] = p.mask_ctx.data[offset + i] as f32;
+    for (c, a) in c.iter_mut().zip(data) {
+        *c = *a as f32;
     }
     let c = f32x8::from(c) / f32x8::splat(255.0);
 
@@ -758,8 +948,19 @@ fn bicubic(p: &mut Pipeline) {
     let y = p.g;
     let fy = (y + f32x8::splat(0.5)).fract();
     let one = f32x8::splat(1.0);
-    let wx = [bicubic_far(one - fx), bicubic_near(one - fx), bicubic_near(fx), bicubic_far(fx)];
-    let wy = [bicubic_far(one - fy), bicubic_near(one - fy), bicubic_near(fy), bicubic_far(fy)];
+    let ctx = &p.ctx.sampler;
+    let wx = [
+        bicubic_far(&ctx.cubic_far, one - fx),
+        bicubic_near(&ctx.cubic_near, one - fx),
+        bicubic_near(&ctx.cubic_near, fx),
+        bicubic_far(&ctx.cubic_far, fx),
+    ];
+    let wy = [
+        bicubic_far(&ctx.cubic_far, one - fy),
+        bicubic_near(&ctx.cubic_near, one - fy),
+        bicubic_near(&ctx.cubic_near, fy),
+        bicubic_far(&ctx.cubic_far, fy),
+    ];
 
     sampler_4x4(p.pixmap_src, &p.ctx.sampler, x, y, &wx, &wy, &mut p.r, &mut p.g, &mut p.b, &mut p.a);
 
@@ -770,28 +971,29 @@ fn bicubic(p: &mut Pipeline) {
 // pixel center are combined with a non-uniform cubic filter, with higher values near the center.
 //
 // We break this function into two parts, one for near 0.5 offsets and one for far 1.5 offsets.
+// Both are cubic polynomials defined by the B/C parameters. See `CubicResampler`.
 
 #[inline(always)]
-fn bicubic_near(t: f32x8) -> f32x8 {
-    // 1/18 + 9/18t + 27/18t^2 - 21/18t^3 == t ( t ( -21/18t + 27/18) + 9/18) + 1/18
+fn bicubic_near(coeffs: &[f32; 4], t: f32x8) -> f32x8 {
+    // c0 + c1*t + c2*t^2 + c3*t^3 == t ( t ( c3*t + c2) + c1) + c0
     mad(
         t,
         mad(t,
             mad(
-                f32x8::splat(-21.0/18.0),
+                f32x8::splat(coeffs[3]),
                 t,
-                f32x8::splat(27.0/18.0),
+                f32x8::splat(coeffs[2]),
             ),
-            f32x8::splat(9.0/18.0),
+            f32x8::splat(coeffs[1]),
         ),
-        f32x8::splat(1.0/18.0),
+        f32x8::splat(coeffs[0]),
     )
 }
 
 #[inline(always)]
-fn bicubic_far(t: f32x8) -> f32x8 {
-    // 0/18 + 0/18*t - 6/18t^2 + 7/18t^3 == t^2 (7/18t - 6/18)
-    (t * t) * mad(f32x8::splat(7.0/18.0), t, f32x8::splat(-6.0/18.0))
+fn bicubic_far(coeffs: &[f32; 2], t: f32x8) -> f32x8 {
+    // 0 + 0*t + c2*t^2 + c3*t^3 == t^2 (c3*t + c2)
+    (t * t) * mad(f32x8::splat(coeffs[1]), t, f32x8::splat(coeffs[0]))
 }
 
 #[inline(always)]
@@ -1222,6 +1424,140 @@ fn gamma_compress_srgb(p: &mut Pipeline) {
     p.next_stage();
 }
 
+fn custom_shader(p: &mut Pipeline) {
+    let x: [f32; STAGE_WIDTH] = p.r.into();
+    let y: [f32; STAGE_WIDTH] = p.g.into();
+    let mut colors = [crate::Color::TRANSPARENT; STAGE_WIDTH];
+    if let Some(shader) = p.custom_shader {
+        shader.shade(&x, &y, &mut colors);
+    }
+
+    let opacity = p.ctx.custom_shader_opacity;
+
+    p.r = f32x8::from(colors.map(|c| c.red()));
+    p.g = f32x8::from(colors.map(|c| c.green()));
+    p.b = f32x8::from(colors.map(|c| c.blue()));
+    p.a = f32x8::from(colors.map(|c| c.alpha() * opacity));
+
+    p.next_stage();
+}
+
+// This is a port of SkPerlinNoiseShader's CPU implementation.
+// Unlike other stages, it processes lanes one by one, since each lane requires
+// multiple lookups into the lattice and gradient tables.
+fn perlin_noise(p: &mut Pipeline) {
+    let ctx = &p.ctx.perlin_noise;
+
+    // Skia rounds the sample point.
+    let x = (p.r + f32x8::splat(0.5)).floor();
+    let y = (p.g + f32x8::splat(0.5)).floor();
+
+    // Just like Skia, the result is quantized to 8 bits before premultiplication.
+    let quantize = |v: f32x8| (v * f32x8::splat(255.0)).floor() * f32x8::splat(1.0 / 255.0);
+    p.r = quantize(turbulence(ctx, 0, x, y));
+    p.g = quantize(turbulence(ctx, 1, x, y));
+    p.b = quantize(turbulence(ctx, 2, x, y));
+    p.a = quantize(turbulence(ctx, 3, x, y) * f32x8::splat(ctx.opacity));
+
+    p.next_stage();
+}
+
+fn turbulence(ctx: &super::PerlinNoiseCtx, channel: usize, x: f32x8, y: f32x8) -> f32x8 {
+    let mut stitch = ctx.stitch;
+    let mut noise_x = x * f32x8::splat(ctx.base_frequency_x);
+    let mut noise_y = y * f32x8::splat(ctx.base_frequency_y);
+    let mut ratio = 1.0;
+    let mut sum = f32x8::default();
+    for _ in 0..ctx.octaves {
+        let noise = noise_2d(ctx, channel, stitch, noise_x, noise_y);
+        let noise = if ctx.fractal_noise { noise } else { noise.abs() };
+        sum += noise * f32x8::splat(1.0 / ratio);
+
+        noise_x *= f32x8::splat(2.0);
+        noise_y *= f32x8::splat(2.0);
+        ratio *= 2.0;
+        stitch = stitch.map(|s| s.doubled());
+    }
+
+    if ctx.fractal_noise {
+        // For fractal noise the result is: noise[-1,1] * 0.5 + 0.5
+        sum = (sum + f32x8::splat(1.0)) * f32x8::splat(0.5);
+    }
+
+    sum.max(f32x8::default()).min(f32x8::splat(1.0))
+}
+
+fn noise_2d(
+    ctx: &super::PerlinNoiseCtx,
+    channel: usize,
+    stitch: Option<super::StitchData>,
+    x: f32x8,
+    y: f32x8,
+) -> f32x8 {
+    const BLOCK_MASK: i32 = 255;
+
+    let x = x + f32x8::splat(super::StitchData::PERLIN_NOISE as f32);
+    let y = y + f32x8::splat(super::StitchData::PERLIN_NOISE as f32);
+    let fx = x - x.floor();
+    let fy = y - y.floor();
+    let x_int: [f32; STAGE_WIDTH] = x.floor().into();
+    let y_int: [f32; STAGE_WIDTH] = y.floor().into();
+
+    let gradients = &ctx.gradients[channel * 256..(channel + 1) * 256];
+    let mut g00 = [[0.0; STAGE_WIDTH]; 2];
+    let mut g10 = [[0.0; STAGE_WIDTH]; 2];
+    let mut g01 = [[0.0; STAGE_WIDTH]; 2];
+    let mut g11 = [[0.0; STAGE_WIDTH]; 2];
+    for i in 0..STAGE_WIDTH {
+        let mut x0 = x_int[i] as i32;
+        let mut y0 = y_int[i] as i32;
+        let mut x1 = x0.wrapping_add(1);
+        let mut y1 = y0.wrapping_add(1);
+
+        // If stitching, adjust lattice points accordingly.
+        if let Some(stitch) = stitch {
+            let check = |v: i32, limit: i32, size: i32| if v >= limit { v - size } else { v };
+            x0 = check(x0, stitch.wrap_x, stitch.width);
+            y0 = check(y0, stitch.wrap_y, stitch.height);
+            x1 = check(x1, stitch.wrap_x, stitch.width);
+            y1 = check(y1, stitch.wrap_y, stitch.height);
+        }
+
+        let li = ctx.lattice_selector[(x0 & BLOCK_MASK) as usize] as i32;
+        let lj = ctx.lattice_selector[(x1 & BLOCK_MASK) as usize] as i32;
+        let y0 = y0 & BLOCK_MASK;
+        let y1 = y1 & BLOCK_MASK;
+
+        let load = |g: &mut [[f32; STAGE_WIDTH]; 2], index: i32| {
+            let v = gradients[(index & BLOCK_MASK) as usize];
+            g[0][i] = v.x;
+            g[1][i] = v.y;
+        };
+        load(&mut g00, li + y0);
+        load(&mut g10, lj + y0);
+        load(&mut g01, li + y1);
+        load(&mut g11, lj + y1);
+    }
+
+    let dot = |g: [[f32; STAGE_WIDTH]; 2], x: f32x8, y: f32x8| {
+        f32x8::from(g[0]) * x + f32x8::from(g[1]) * y
+    };
+    let lerp = |a: f32x8, b: f32x8, t: f32x8| a + (b - a) * t;
+    let smooth = |t: f32x8| t * t * (f32x8::splat(3.0) - f32x8::splat(2.0) * t);
+
+    let one = f32x8::splat(1.0);
+    let sx = smooth(fx);
+    let sy = smooth(fy);
+    let a = lerp(dot(g00, fx, fy), dot(g10, fx - one, fy), sx);
+    let b = lerp(dot(g01, fx, fy - one), dot(g11, fx - one, fy - one), sx);
+    let noise = lerp(a, b, sy);
+
+    // Very large or very small values produce invalid fractions.
+    let zero = f32x8::default();
+    let is_valid = sx.cmp_ge(zero) & sx.cmp_le(one) & sy.cmp_ge(zero) & sy.cmp_le(one);
+    is_valid.blend(noise, zero)
+}
+
 pub fn just_return(_: &mut Pipeline) {
     // Ends the loop.
 }
@@ -1338,6 +1674,32 @@ fn store_8888_tail(
     }
 }
 
+#[inline(always)]
+fn load_8(tail: usize, data: &[u8], v: &mut f32x8) {
+    let mut tmp = [0.0; STAGE_WIDTH];
+    for i in 0..STAGE_WIDTH {
+        tmp[i] = data[i] as f32 * (1.0 / 255.0);
+
+        if i + 1 == tail {
+            break;
+        }
+    }
+
+    *v = f32x8::from(tmp);
+}
+
+#[inline(always)]
+fn store_8(v: &f32x8, tail: usize, data: &mut [u8]) {
+    let v: [i32; 8] = unnorm(v).into();
+    for i in 0..STAGE_WIDTH {
+        data[i] = v[i] as u8;
+
+        if i + 1 == tail {
+            break;
+        }
+    }
+}
+
 #[inline(always)]
 fn unnorm(v: &f32x8) -> i32x8 {
     (v.max(f32x8::default()).min(f32x8::splat(1.0)) * f32x8::splat(255.0)).round_int()
@@ -30,6 +30,7 @@ On ARM AArch64 the story is different and explicit SIMD make our code up to 2-3x
 
 use crate::PremultipliedColorU8;
 
+use crate::pixel_format::unpack_565;
 use crate::pixmap::SubPixmapMut;
 use crate::wide::{f32x8, u16x16, f32x16};
 use crate::geom::ScreenIntRect;
@@ -81,6 +82,16 @@ pub const STAGES: &[StageFn; super::STAGES_COUNT] = &[
     store,
     load_dst_u8,
     store_u8,
+    load_dst_bgra,
+    store_bgra,
+    load_dst_565,
+    store_565,
+    load_dst_g8,
+    store_g8,
+    null_fn, // LoadDestination16161616
+    null_fn, // Store16161616
+    null_fn, // LoadDestinationF16
+    null_fn, // StoreF16
     null_fn, // Gather
     load_mask_u8,
     mask_u8,
@@ -151,6 +162,8 @@ pub const STAGES: &[StageFn; super::STAGES_COUNT] = &[
     null_fn, // GammaExpandSrgb
     null_fn, // GammaExpandDestinationSrgb
     null_fn, // GammaCompressSrgb
+    null_fn, // CustomShader
+    null_fn, // PerlinNoise
 ];
 
 pub fn fn_ptr(f: StageFn) -> *const () {
@@ -363,10 +376,17 @@ fn load_mask_u8(p: &mut Pipeline) {
 
 fn mask_u8(p: &mut Pipeline) {
     let offset = p.mask_ctx.offset(p.dx, p.dy);
+    let data = &p.mask_ctx.data[offset..offset + p.tail];
+
+    // Fully opaque mask doesn't affect the color.
+    if data.iter().all(|a| *a == 255) {
+        p.next_stage();
+        return;
+    }
 
     let mut c = u16x16::default();
-    for i in 0..p.tail {
-        c.0[i] = u16::from(p.mask_ctx.data[offset + i]);
+    for (c, a) in c.0.iter_mut().zip(data) {
+        *c = u16::from(*a);
     }
 
     if c == u16x16::default() {
@@ -733,6 +753,103 @@ pub fn just_return(_: &mut Pipeline) {
     // Ends the loop.
 }
 
+pub fn load_dst_bgra(p: &mut Pipeline) {
+    load_8888(p.pixmap.slice16_at_xy(p.dx, p.dy), &mut p.db, &mut p.dg, &mut p.dr, &mut p.da);
+    p.next_stage();
+}
+
+pub fn load_dst_bgra_tail(p: &mut Pipeline) {
+    load_8888_tail(p.tail, p.pixmap.slice_at_xy(p.dx, p.dy), &mut p.db, &mut p.dg, &mut p.dr, &mut p.da);
+    p.next_stage();
+}
+
+pub fn store_bgra(p: &mut Pipeline) {
+    store_8888(&p.b, &p.g, &p.r, &p.a, p.pixmap.slice16_at_xy(p.dx, p.dy));
+    p.next_stage();
+}
+
+pub fn store_bgra_tail(p: &mut Pipeline) {
+    store_8888_tail(&p.b, &p.g, &p.r, &p.a, p.tail, p.pixmap.slice_at_xy(p.dx, p.dy));
+    p.next_stage();
+}
+
+// Formats below are handling the tail manually, since they are not performance critical.
+
+fn load_dst_565(p: &mut Pipeline) {
+    let data = p.pixmap.slice_bytes_at_xy(p.dx, p.dy);
+    for i in 0..STAGE_WIDTH {
+        let (r5, g6, b5) = unpack_565(u16::from_ne_bytes([data[i * 2], data[i * 2 + 1]]));
+        // Round to nearest, like `r5 * 255 / 31`.
+        p.dr.0[i] = (r5 * 255 + 15) / 31;
+        p.dg.0[i] = (g6 * 255 + 31) / 63;
+        p.db.0[i] = (b5 * 255 + 15) / 31;
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.da = u16x16::splat(255);
+
+    p.next_stage();
+}
+
+fn store_565(p: &mut Pipeline) {
+    let r = p.r.as_slice();
+    let g = p.g.as_slice();
+    let b = p.b.as_slice();
+    let data = p.pixmap.slice_bytes_at_xy(p.dx, p.dy);
+    for i in 0..STAGE_WIDTH {
+        // Round to nearest, like `r * 31 / 255`.
+        let v = (((r[i] as u32 * 31 + 127) / 255) << 11)
+            | (((g[i] as u32 * 63 + 127) / 255) << 5)
+            | ((b[i] as u32 * 31 + 127) / 255);
+        data[i * 2..i * 2 + 2].copy_from_slice(&(v as u16).to_ne_bytes());
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.next_stage();
+}
+
+fn load_dst_g8(p: &mut Pipeline) {
+    let data = p.pixmap.slice_bytes_at_xy(p.dx, p.dy);
+    let mut tmp = [0u8; STAGE_WIDTH];
+    for i in 0..STAGE_WIDTH {
+        tmp[i] = data[i];
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    load_8(&tmp, &mut p.dr);
+    p.dg = p.dr;
+    p.db = p.dr;
+    p.da = u16x16::splat(255);
+
+    p.next_stage();
+}
+
+fn store_g8(p: &mut Pipeline) {
+    // 0.2126, 0.7152 and 0.0722 in 1/256 units.
+    let luma = (p.r * u16x16::splat(54) + p.g * u16x16::splat(183) + p.b * u16x16::splat(19)
+        + u16x16::splat(128)) >> u16x16::splat(8);
+    let luma = luma.as_slice();
+    let data = p.pixmap.slice_bytes_at_xy(p.dx, p.dy);
+    for i in 0..STAGE_WIDTH {
+        data[i] = luma[i] as u8;
+
+        if i + 1 == p.tail {
+            break;
+        }
+    }
+
+    p.next_stage();
+}
+
 pub fn null_fn(_: &mut Pipeline) {
     // Just for unsupported functions in STAGES.
 }
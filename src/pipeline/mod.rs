@@ -48,9 +48,9 @@ use alloc::vec::Vec;
 
 use arrayvec::ArrayVec;
 
-use tiny_skia_path::NormalizedF32;
+use tiny_skia_path::{NormalizedF32, Point};
 
-use crate::{Color, PremultipliedColor, PremultipliedColorU8, SpreadMode};
+use crate::{Color, PremultipliedColor, PremultipliedColorU8, ShaderImpl, SpreadMode};
 use crate::{PixmapRef, Transform};
 
 pub use blitter::RasterPipelineBlitter;
@@ -79,6 +79,16 @@ pub enum Stage {
     Store,
     LoadDestinationU8,
     StoreU8,
+    LoadDestinationBgra,
+    StoreBgra,
+    LoadDestination565,
+    Store565,
+    LoadDestinationGray8,
+    StoreGray8,
+    LoadDestination16161616,
+    Store16161616,
+    LoadDestinationF16,
+    StoreF16,
     Gather,
     LoadMaskU8,
     MaskU8,
@@ -147,9 +157,11 @@ pub enum Stage {
     GammaExpandSrgb,
     GammaExpandDestinationSrgb,
     GammaCompressSrgb,
+    CustomShader,
+    PerlinNoise,
 }
 
-pub const STAGES_COUNT: usize = Stage::GammaCompressSrgb as usize + 1;
+pub const STAGES_COUNT: usize = Stage::PerlinNoise as usize + 1;
 
 impl PixmapRef<'_> {
     #[inline(always)]
@@ -181,6 +193,12 @@ impl SubPixmapMut<'_> {
         &mut self.pixels_mut()[offset..]
     }
 
+    #[inline(always)]
+    pub(crate) fn slice_bytes_at_xy(&mut self, dx: usize, dy: usize) -> &mut [u8] {
+        let offset = self.offset(dx, dy) * self.format.bytes_per_pixel();
+        &mut self.data[offset..]
+    }
+
     #[inline(always)]
     pub(crate) fn slice_mask_at_xy(&mut self, dx: usize, dy: usize) -> &mut [u8] {
         let offset = self.offset(dx, dy);
@@ -260,6 +278,8 @@ pub struct Context {
     pub limit_x: TileCtx,
     pub limit_y: TileCtx,
     pub transform: Transform,
+    pub perlin_noise: PerlinNoiseCtx,
+    pub custom_shader_opacity: f32,
 }
 
 #[derive(Copy, Clone, Default, Debug)]
@@ -267,6 +287,9 @@ pub struct SamplerCtx {
     pub spread_mode: SpreadMode,
     pub inv_width: f32,
     pub inv_height: f32,
+    // Bicubic weights polynomials for the 0.5 and 1.5 offsets.
+    pub cubic_near: [f32; 4],
+    pub cubic_far: [f32; 2],
 }
 
 #[derive(Copy, Clone, Default, Debug)]
@@ -344,6 +367,48 @@ pub struct TileCtx {
     pub inv_scale: f32, // cache of 1/scale
 }
 
+#[derive(Clone, Default, Debug)]
+pub struct PerlinNoiseCtx {
+    pub fractal_noise: bool,
+    pub octaves: u32,
+    pub base_frequency_x: f32,
+    pub base_frequency_y: f32,
+    // `None` when tiles are not stitched.
+    pub stitch: Option<StitchData>,
+    pub opacity: f32,
+    pub lattice_selector: Vec<u8>,
+    // 256 normalized gradients per channel.
+    pub gradients: Vec<Point>,
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct StitchData {
+    pub width: i32,
+    pub height: i32,
+    pub wrap_x: i32,
+    pub wrap_y: i32,
+}
+
+impl StitchData {
+    pub const PERLIN_NOISE: i32 = 4096;
+
+    pub fn new(width: i32, height: i32) -> Self {
+        let width = width.min(i32::MAX - Self::PERLIN_NOISE);
+        let height = height.min(i32::MAX - Self::PERLIN_NOISE);
+        StitchData {
+            width,
+            height,
+            wrap_x: Self::PERLIN_NOISE + width,
+            wrap_y: Self::PERLIN_NOISE + height,
+        }
+    }
+
+    // Stitch data for the next octave.
+    pub fn doubled(self) -> Self {
+        StitchData::new(self.width.saturating_mul(2), self.height.saturating_mul(2))
+    }
+}
+
 pub struct RasterPipelineBuilder {
     stages: ArrayVec<Stage, MAX_STAGES>,
     force_hq_pipeline: bool,
@@ -431,6 +496,10 @@ impl RasterPipelineBuilder {
                     *fun = highp::load_dst_u8_tail as highp::StageFn;
                 } else if highp::fn_ptr(*fun) == highp::fn_ptr(highp::store_u8) {
                     *fun = highp::store_u8_tail as highp::StageFn;
+                } else if highp::fn_ptr(*fun) == highp::fn_ptr(highp::load_dst_bgra) {
+                    *fun = highp::load_dst_bgra_tail as highp::StageFn;
+                } else if highp::fn_ptr(*fun) == highp::fn_ptr(highp::store_bgra) {
+                    *fun = highp::store_bgra_tail as highp::StageFn;
                 } else if highp::fn_ptr(*fun) == highp::fn_ptr(highp::source_over_rgba) {
                     // SourceOverRgba calls load/store manually, without the pipeline,
                     // therefore we have to switch it too.
@@ -464,6 +533,10 @@ impl RasterPipelineBuilder {
                     *fun = lowp::load_dst_u8_tail as lowp::StageFn;
                 } else if lowp::fn_ptr(*fun) == lowp::fn_ptr(lowp::store_u8) {
                     *fun = lowp::store_u8_tail as lowp::StageFn;
+                } else if lowp::fn_ptr(*fun) == lowp::fn_ptr(lowp::load_dst_bgra) {
+                    *fun = lowp::load_dst_bgra_tail as lowp::StageFn;
+                } else if lowp::fn_ptr(*fun) == lowp::fn_ptr(lowp::store_bgra) {
+                    *fun = lowp::store_bgra_tail as lowp::StageFn;
                 } else if lowp::fn_ptr(*fun) == lowp::fn_ptr(lowp::source_over_rgba) {
                     // SourceOverRgba calls load/store manually, without the pipeline,
                     // therefore we have to switch it too.
@@ -505,6 +578,7 @@ impl RasterPipeline {
         aa_mask_ctx: AAMaskCtx,
         mask_ctx: MaskCtx,
         pixmap_src: PixmapRef,
+        custom_shader: Option<&dyn ShaderImpl>,
         pixmap_dst: &mut SubPixmapMut,
     ) {
         match self.kind {
@@ -520,6 +594,7 @@ impl RasterPipeline {
                     mask_ctx,
                     &mut self.ctx,
                     pixmap_src,
+                    custom_shader,
                     pixmap_dst,
                 );
             }
@@ -534,7 +609,8 @@ impl RasterPipeline {
                     aa_mask_ctx,
                     mask_ctx,
                     &mut self.ctx,
-                    // lowp doesn't support pattern, so no `pixmap_src` for it.
+                    // lowp doesn't support pattern and custom shaders,
+                    // so no `pixmap_src` and `custom_shader` for it.
                     pixmap_dst,
                 );
             }
@@ -574,7 +650,7 @@ mod blend_tests {
                 p.push(Stage::Store);
                 let mut p = p.compile();
                 let rect = pixmap.size().to_screen_int_rect(0, 0);
-                p.run(&rect, AAMaskCtx::default(), MaskCtx::default(), pixmap_src,
+                p.run(&rect, AAMaskCtx::default(), MaskCtx::default(), pixmap_src, None,
                       &mut pixmap.as_mut().as_subpixmap());
 
                 assert_eq!(
@@ -0,0 +1,284 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use crate::color::{PremultipliedColor, PremultipliedColorU8};
+use crate::pipeline;
+
+/// A pixels memory layout.
+///
+/// All formats with an alpha channel store premultiplied colors.
+/// Multibyte values are stored in the native byte order.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum PixelFormat {
+    /// 8-bit RGBA. The default one.
+    #[default]
+    Rgba8888,
+    /// 8-bit BGRA. Commonly used by window systems.
+    Bgra8888,
+    /// Opaque 16-bit RGB. 5 bits for red and blue, 6 bits for green.
+    /// Red is stored in the most significant bits.
+    Rgb565,
+    /// 8-bit alpha only.
+    ///
+    /// Color channels are ignored during drawing.
+    A8,
+    /// Opaque 8-bit luminance.
+    ///
+    /// Formula: `Y = 0.2126 * R + 0.7152 * G + 0.0722 * B`
+    Gray8,
+    /// 16-bit per channel RGBA.
+    ///
+    /// Always rendered using the high precision pipeline.
+    Rgba16161616,
+    /// Half-float per channel RGBA.
+    ///
+    /// Always rendered using the high precision pipeline.
+    RgbaF16,
+}
+
+impl PixelFormat {
+    /// Returns the number of bytes per pixel.
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Rgba8888 | PixelFormat::Bgra8888 => 4,
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::A8 | PixelFormat::Gray8 => 1,
+            PixelFormat::Rgba16161616 | PixelFormat::RgbaF16 => 8,
+        }
+    }
+
+    /// Checks that the format doesn't have an alpha channel.
+    pub fn is_opaque(&self) -> bool {
+        matches!(self, PixelFormat::Rgb565 | PixelFormat::Gray8)
+    }
+
+    pub(crate) fn load_dst_stage(&self) -> pipeline::Stage {
+        match self {
+            PixelFormat::Rgba8888 => pipeline::Stage::LoadDestination,
+            PixelFormat::Bgra8888 => pipeline::Stage::LoadDestinationBgra,
+            PixelFormat::Rgb565 => pipeline::Stage::LoadDestination565,
+            PixelFormat::A8 => pipeline::Stage::LoadDestinationU8,
+            PixelFormat::Gray8 => pipeline::Stage::LoadDestinationGray8,
+            PixelFormat::Rgba16161616 => pipeline::Stage::LoadDestination16161616,
+            PixelFormat::RgbaF16 => pipeline::Stage::LoadDestinationF16,
+        }
+    }
+
+    pub(crate) fn store_stage(&self) -> pipeline::Stage {
+        match self {
+            PixelFormat::Rgba8888 => pipeline::Stage::Store,
+            PixelFormat::Bgra8888 => pipeline::Stage::StoreBgra,
+            PixelFormat::Rgb565 => pipeline::Stage::Store565,
+            PixelFormat::A8 => pipeline::Stage::StoreU8,
+            PixelFormat::Gray8 => pipeline::Stage::StoreGray8,
+            PixelFormat::Rgba16161616 => pipeline::Stage::Store16161616,
+            PixelFormat::RgbaF16 => pipeline::Stage::StoreF16,
+        }
+    }
+
+    /// Reads a single pixel.
+    ///
+    /// `data` must contain at least `bytes_per_pixel` bytes.
+    pub(crate) fn read_pixel(&self, data: &[u8]) -> PremultipliedColorU8 {
+        let c = PremultipliedColorU8::from_rgba_unchecked;
+        match self {
+            PixelFormat::Rgba8888 => c(data[0], data[1], data[2], data[3]),
+            PixelFormat::Bgra8888 => c(data[2], data[1], data[0], data[3]),
+            PixelFormat::Rgb565 => {
+                let v = u16::from_ne_bytes([data[0], data[1]]);
+                let (r, g, b) = unpack_565(v);
+                c(expand_bits(r, 5), expand_bits(g, 6), expand_bits(b, 5), 255)
+            }
+            PixelFormat::A8 => c(0, 0, 0, data[0]),
+            PixelFormat::Gray8 => c(data[0], data[0], data[0], 255),
+            PixelFormat::Rgba16161616 => {
+                let v = |i: usize| {
+                    let n = u16::from_ne_bytes([data[i * 2], data[i * 2 + 1]]);
+                    // Round to nearest, like `n * 255 / 65535`.
+                    ((u32::from(n) * 255 + 32767) / 65535) as u8
+                };
+                c(v(0), v(1), v(2), v(3))
+            }
+            PixelFormat::RgbaF16 => {
+                let v = |i: usize| f16_to_f32(u16::from_ne_bytes([data[i * 2], data[i * 2 + 1]]));
+                let a = to_u8(v(3));
+                // Out of range values are allowed in F16, so we have to clamp.
+                c(
+                    to_u8(v(0)).min(a),
+                    to_u8(v(1)).min(a),
+                    to_u8(v(2)).min(a),
+                    a,
+                )
+            }
+        }
+    }
+
+    /// Writes a single pixel.
+    ///
+    /// `data` must contain at least `bytes_per_pixel` bytes.
+    pub(crate) fn write_pixel(&self, color: PremultipliedColor, data: &mut [u8]) {
+        let (r, g, b, a) = (color.red(), color.green(), color.blue(), color.alpha());
+        match self {
+            PixelFormat::Rgba8888 => {
+                data[..4].copy_from_slice(&[to_u8(r), to_u8(g), to_u8(b), to_u8(a)])
+            }
+            PixelFormat::Bgra8888 => {
+                data[..4].copy_from_slice(&[to_u8(b), to_u8(g), to_u8(r), to_u8(a)])
+            }
+            PixelFormat::Rgb565 => {
+                let v = pack_565(r, g, b);
+                data[..2].copy_from_slice(&v.to_ne_bytes());
+            }
+            PixelFormat::A8 => data[0] = to_u8(a),
+            PixelFormat::Gray8 => data[0] = to_u8(luminance(r, g, b)),
+            PixelFormat::Rgba16161616 => {
+                for (i, v) in [r, g, b, a].iter().enumerate() {
+                    let n = (v * 65535.0 + 0.5) as u16;
+                    data[i * 2..i * 2 + 2].copy_from_slice(&n.to_ne_bytes());
+                }
+            }
+            PixelFormat::RgbaF16 => {
+                for (i, v) in [r, g, b, a].iter().enumerate() {
+                    let n = f32_to_f16(*v);
+                    data[i * 2..i * 2 + 2].copy_from_slice(&n.to_ne_bytes());
+                }
+            }
+        }
+    }
+}
+
+#[inline]
+fn to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+#[inline]
+fn luminance(r: f32, g: f32, b: f32) -> f32 {
+    r * 0.2126 + g * 0.7152 + b * 0.0722
+}
+
+#[inline]
+pub(crate) fn unpack_565(v: u16) -> (u16, u16, u16) {
+    (v >> 11, (v >> 5) & 0x3f, v & 0x1f)
+}
+
+#[inline]
+pub(crate) fn pack_565(r: f32, g: f32, b: f32) -> u16 {
+    let conv = |v: f32, max: f32| (v.clamp(0.0, 1.0) * max + 0.5) as u16;
+    (conv(r, 31.0) << 11) | (conv(g, 63.0) << 5) | conv(b, 31.0)
+}
+
+/// Converts a 5 or 6 bit value into 8 bit, rounding to nearest.
+#[inline]
+fn expand_bits(v: u16, bits: u32) -> u8 {
+    let max = (1 << bits) - 1;
+    ((u32::from(v) * 255 + max / 2) / max) as u8
+}
+
+/// Converts an IEEE 754 half-precision float into `f32`.
+pub(crate) fn f16_to_f32(h: u16) -> f32 {
+    let sign = u32::from(h >> 15) << 31;
+    let exp = u32::from((h >> 10) & 0x1f);
+    let mantissa = u32::from(h & 0x3ff);
+
+    let bits = if exp == 0 {
+        if mantissa == 0 {
+            sign
+        } else {
+            // Subnormal. Can be represented exactly in f32.
+            let v = mantissa as f32 * (1.0 / (1 << 24) as f32);
+            return if sign != 0 { -v } else { v };
+        }
+    } else if exp == 0x1f {
+        // Inf or NaN.
+        sign | 0x7f80_0000 | (mantissa << 13)
+    } else {
+        sign | ((exp + 127 - 15) << 23) | (mantissa << 13)
+    };
+
+    f32::from_bits(bits)
+}
+
+/// Converts `f32` into an IEEE 754 half-precision float, rounding to nearest even.
+pub(crate) fn f32_to_f16(f: f32) -> u16 {
+    let bits = f.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exp == 0xff {
+        // Inf or NaN.
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let exp = exp - 127 + 15;
+    if exp >= 0x1f {
+        // Overflow. Becomes Inf.
+        return sign | 0x7c00;
+    }
+
+    if exp <= 0 {
+        // Subnormal or zero.
+        if exp < -10 {
+            return sign;
+        }
+
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exp) as u32;
+        let half = 1 << (shift - 1);
+        let rest = mantissa & ((1 << shift) - 1);
+        let mut v = mantissa >> shift;
+        if rest > half || (rest == half && v & 1 != 0) {
+            v += 1;
+        }
+
+        return sign | v as u16;
+    }
+
+    let mut v = ((exp as u32) << 10) | (mantissa >> 13);
+    let rest = mantissa & 0x1fff;
+    // Rounding can overflow into the exponent, which is exactly what we want.
+    if rest > 0x1000 || (rest == 0x1000 && v & 1 != 0) {
+        v += 1;
+    }
+
+    sign | v as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f16_round_trip() {
+        for v in [0.0, 1.0, -1.0, 0.5, 0.25, 1.5, 65504.0, 0.000061035156] {
+            assert_eq!(f16_to_f32(f32_to_f16(v)), v);
+        }
+
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.0), 0xc000);
+        assert_eq!(f32_to_f16(100000.0), 0x7c00);
+        assert_eq!(f32_to_f16(0.00000005960464), 0x0001);
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+
+        for h in 0..0x7c00 {
+            assert_eq!(f32_to_f16(f16_to_f32(h)), h);
+        }
+    }
+
+    #[test]
+    fn rgb565() {
+        let format = PixelFormat::Rgb565;
+        let mut data = [0; 2];
+        let color = crate::Color::from_rgba8(255, 128, 0, 255).premultiply();
+        format.write_pixel(color, &mut data);
+        assert_eq!(u16::from_ne_bytes(data), 0xfc00);
+        assert_eq!(
+            format.read_pixel(&data),
+            PremultipliedColorU8::from_rgba(255, 130, 0, 255).unwrap()
+        );
+    }
+}
@@ -12,24 +12,31 @@ use core::num::NonZeroUsize;
 
 use tiny_skia_path::IntSize;
 
-use crate::{Color, IntRect};
+use crate::{Color, DiffReport, IntRect, PixelFormat, Region};
 
 use crate::color::PremultipliedColorU8;
+use crate::convert::{demultiply_rgba8, premultiply_rgba8};
 use crate::geom::{IntSizeExt, ScreenIntRect};
 
 #[cfg(feature = "png-format")]
-use crate::color::{premultiply_u8, ALPHA_U8_OPAQUE};
+use crate::{PngDecodeOptions, PngEncodeOptions};
 
 /// Number of bytes per pixel.
+///
+/// For the default [`PixelFormat::Rgba8888`] format.
 pub const BYTES_PER_PIXEL: usize = 4;
 
 /// A container that owns premultiplied RGBA pixels.
 ///
+/// Other pixel formats are supported as well. See [`Pixmap::new_with_format`].
+///
 /// The data is not aligned, therefore width == stride.
-#[derive(Clone, PartialEq)]
+#[derive(Clone)]
 pub struct Pixmap {
     data: Vec<u8>,
     size: IntSize,
+    format: PixelFormat,
+    damage: Option<Region>,
 }
 
 impl Pixmap {
@@ -41,8 +48,18 @@ impl Pixmap {
     ///
     /// Pixmap's width is limited by i32::MAX/4.
     pub fn new(width: u32, height: u32) -> Option<Self> {
+        Self::new_with_format(width, height, PixelFormat::Rgba8888)
+    }
+
+    /// Allocates a new pixmap using the specified pixel format.
+    ///
+    /// A pixmap is filled with zeros by default. Which is transparent black
+    /// for formats with alpha and opaque black otherwise.
+    ///
+    /// Zero size in an error.
+    pub fn new_with_format(width: u32, height: u32, format: PixelFormat) -> Option<Self> {
         let size = IntSize::from_wh(width, height)?;
-        let data_len = data_len_for_size(size)?;
+        let data_len = data_len_for_size(size, format)?;
 
         // We cannot check that allocation was successful yet.
         // We have to wait for https://github.com/rust-lang/rust/issues/48043
@@ -50,6 +67,8 @@ impl Pixmap {
         Some(Pixmap {
             data: vec![0; data_len],
             size,
+            format,
+            damage: None,
         })
     }
 
@@ -60,107 +79,67 @@ impl Pixmap {
     ///
     /// Pixmap's width is limited by i32::MAX/4.
     pub fn from_vec(data: Vec<u8>, size: IntSize) -> Option<Self> {
-        let data_len = data_len_for_size(size)?;
+        Self::from_vec_with_format(data, size, PixelFormat::Rgba8888)
+    }
+
+    /// Creates a new pixmap by taking ownership over an image buffer
+    /// (unpremultiplied RGBA pixels).
+    ///
+    /// Pixels will be premultiplied in place.
+    /// This is the opposite of [`take_demultiplied`](Self::take_demultiplied).
+    ///
+    /// The size needs to match the data provided.
+    pub fn from_rgba8_unpremultiplied(mut data: Vec<u8>, size: IntSize) -> Option<Self> {
+        if data.len() != data_len_for_size(size, PixelFormat::Rgba8888)? {
+            return None;
+        }
+
+        premultiply_rgba8(&mut data);
+        Self::from_vec(data, size)
+    }
+
+    /// Creates a new pixmap by taking ownership over an image buffer
+    /// in the specified pixel format.
+    ///
+    /// The size needs to match the data provided.
+    pub fn from_vec_with_format(data: Vec<u8>, size: IntSize, format: PixelFormat) -> Option<Self> {
+        let data_len = data_len_for_size(size, format)?;
         if data.len() != data_len {
             return None;
         }
 
-        Some(Pixmap { data, size })
+        Some(Pixmap {
+            data,
+            size,
+            format,
+            damage: None,
+        })
     }
 
     /// Decodes a PNG data into a `Pixmap`.
     ///
-    /// Only 8-bit images are supported.
-    /// Index PNGs are not supported.
+    /// All PNG color types and bit depths are supported.
+    /// 16-bit images will be converted into 8-bit.
+    /// Colors will be converted into sRGB using the `cICP`, `sRGB` and `gAMA` chunks.
+    ///
+    /// See [`Pixmap::decode_png_with`] for more options.
     #[cfg(feature = "png-format")]
     pub fn decode_png(data: &[u8]) -> Result<Self, png::DecodingError> {
-        fn make_custom_png_error(msg: &str) -> png::DecodingError {
-            std::io::Error::new(std::io::ErrorKind::Other, msg).into()
-        }
-
-        let mut decoder = png::Decoder::new(std::io::BufReader::new(std::io::Cursor::new(data)));
-        decoder.set_transformations(png::Transformations::normalize_to_color8());
-        let mut reader = decoder.read_info()?;
-        let output_buffer_size = reader
-            .output_buffer_size()
-            .ok_or(png::DecodingError::LimitsExceeded)?;
-        let mut img_data = vec![0; output_buffer_size];
-        let info = reader.next_frame(&mut img_data)?;
-
-        if info.bit_depth != png::BitDepth::Eight {
-            return Err(make_custom_png_error("unsupported bit depth"));
-        }
-
-        let size = IntSize::from_wh(info.width, info.height)
-            .ok_or_else(|| make_custom_png_error("invalid image size"))?;
-        let data_len =
-            data_len_for_size(size).ok_or_else(|| make_custom_png_error("image is too big"))?;
-
-        img_data = match info.color_type {
-            png::ColorType::Rgb => {
-                let mut rgba_data = Vec::with_capacity(data_len);
-                for rgb in img_data.chunks(3) {
-                    rgba_data.push(rgb[0]);
-                    rgba_data.push(rgb[1]);
-                    rgba_data.push(rgb[2]);
-                    rgba_data.push(ALPHA_U8_OPAQUE);
-                }
-
-                rgba_data
-            }
-            png::ColorType::Rgba => img_data,
-            png::ColorType::Grayscale => {
-                let mut rgba_data = Vec::with_capacity(data_len);
-                for gray in img_data {
-                    rgba_data.push(gray);
-                    rgba_data.push(gray);
-                    rgba_data.push(gray);
-                    rgba_data.push(ALPHA_U8_OPAQUE);
-                }
-
-                rgba_data
-            }
-            png::ColorType::GrayscaleAlpha => {
-                let mut rgba_data = Vec::with_capacity(data_len);
-                for slice in img_data.chunks(2) {
-                    let gray = slice[0];
-                    let alpha = slice[1];
-                    rgba_data.push(gray);
-                    rgba_data.push(gray);
-                    rgba_data.push(gray);
-                    rgba_data.push(alpha);
-                }
-
-                rgba_data
-            }
-            png::ColorType::Indexed => {
-                return Err(make_custom_png_error("indexed PNG is not supported"));
-            }
-        };
-
-        // Premultiply alpha.
-        //
-        // We cannon use RasterPipeline here, which is faster,
-        // because it produces slightly different results.
-        // Seems like Skia does the same.
-        //
-        // Also, in our tests unsafe version (no bound checking)
-        // had roughly the same performance. So we keep the safe one.
-        for pixel in img_data.as_mut_slice().chunks_mut(BYTES_PER_PIXEL) {
-            let a = pixel[3];
-            pixel[0] = premultiply_u8(pixel[0], a);
-            pixel[1] = premultiply_u8(pixel[1], a);
-            pixel[2] = premultiply_u8(pixel[2], a);
-        }
+        Self::decode_png_with(data, &PngDecodeOptions::default())
+    }
 
-        Pixmap::from_vec(img_data, size)
-            .ok_or_else(|| make_custom_png_error("failed to create a pixmap"))
+    /// Decodes a PNG data into a `Pixmap` using the specified options.
+    #[cfg(feature = "png-format")]
+    pub fn decode_png_with(
+        data: &[u8],
+        options: &PngDecodeOptions,
+    ) -> Result<Self, png::DecodingError> {
+        crate::png_format::decode(data, options)
     }
 
     /// Loads a PNG file into a `Pixmap`.
     ///
-    /// Only 8-bit images are supported.
-    /// Index PNGs are not supported.
+    /// Same as [`Pixmap::decode_png`].
     #[cfg(feature = "png-format")]
     pub fn load_png<P: AsRef<std::path::Path>>(path: P) -> Result<Self, png::DecodingError> {
         // `png::Decoder` is generic over input, which means that it will instance
@@ -176,6 +155,15 @@ impl Pixmap {
         self.as_ref().encode_png()
     }
 
+    /// Encodes pixmap into a PNG data using the specified options.
+    #[cfg(feature = "png-format")]
+    pub fn encode_png_with(
+        &self,
+        options: &PngEncodeOptions,
+    ) -> Result<Vec<u8>, png::EncodingError> {
+        self.as_ref().encode_png_with(options)
+    }
+
     /// Saves pixmap as a PNG file.
     #[cfg(feature = "png-format")]
     pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), png::EncodingError> {
@@ -187,17 +175,46 @@ impl Pixmap {
         PixmapRef {
             data: &self.data,
             size: self.size,
+            real_width: self.size.width() as usize,
+            format: self.format,
         }
     }
 
     /// Returns a container that references Pixmap's data.
+    ///
+    /// Shares the damage tracking state with the pixmap.
     pub fn as_mut(&mut self) -> PixmapMut<'_> {
         PixmapMut {
             data: &mut self.data,
             size: self.size,
+            real_width: self.size.width() as usize,
+            format: self.format,
+            damage: DamageState::Borrowed(&mut self.damage),
         }
     }
 
+    /// Enables damage tracking.
+    ///
+    /// See [`PixmapMut::enable_damage_tracking`] for details.
+    pub fn enable_damage_tracking(&mut self) {
+        self.as_mut().enable_damage_tracking();
+    }
+
+    /// Returns a region affected by drawing since damage tracking was enabled
+    /// or the last [`Pixmap::take_damage`] call.
+    ///
+    /// Returns `None` when damage tracking is disabled.
+    pub fn damage(&self) -> Option<&Region> {
+        self.damage.as_ref()
+    }
+
+    /// Returns the damage region and resets it.
+    ///
+    /// Damage tracking stays enabled.
+    pub fn take_damage(&mut self) -> Option<Region> {
+        self.as_mut().take_damage()
+    }
+
     /// Returns pixmap's width.
     #[inline]
     pub fn width(&self) -> u32 {
@@ -216,44 +233,59 @@ impl Pixmap {
         self.size
     }
 
+    /// Returns pixmap's pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
     /// Fills the entire pixmap with a specified color.
     pub fn fill(&mut self, color: Color) {
-        let c = color.premultiply().to_color_u8();
-        for p in self.as_mut().pixels_mut() {
-            *p = c;
-        }
+        self.as_mut().fill(color);
     }
 
     /// Returns the internal data.
     ///
-    /// Byteorder: RGBA
+    /// Byteorder: RGBA, unless a different pixel format was set.
     pub fn data(&self) -> &[u8] {
         self.data.as_slice()
     }
 
     /// Returns the mutable internal data.
     ///
-    /// Byteorder: RGBA
+    /// Byteorder: RGBA, unless a different pixel format was set.
     pub fn data_mut(&mut self) -> &mut [u8] {
         self.data.as_mut_slice()
     }
 
     /// Returns a pixel color.
     ///
+    /// Pixels in other formats will be converted into RGBA.
+    ///
     /// Returns `None` when position is out of bounds.
     pub fn pixel(&self, x: u32, y: u32) -> Option<PremultipliedColorU8> {
-        let idx = self.width().checked_mul(y)?.checked_add(x)?;
-        self.pixels().get(idx as usize).cloned()
+        self.as_ref().pixel(x, y)
     }
 
     /// Returns a mutable slice of pixels.
+    ///
+    /// Panics when the pixel format is not `Rgba8888`.
+    /// Use [`data_mut`](Self::data_mut) to access pixels in other formats.
     pub fn pixels_mut(&mut self) -> &mut [PremultipliedColorU8] {
+        assert_eq!(
+            self.format,
+            PixelFormat::Rgba8888,
+            "only Rgba8888 pixmaps can be accessed as pixels"
+        );
+
         bytemuck::cast_slice_mut(self.data_mut())
     }
 
     /// Returns a slice of pixels.
+    ///
+    /// Panics when the pixel format is not `Rgba8888`.
+    /// Use [`data`](Self::data) to access pixels in other formats.
     pub fn pixels(&self) -> &[PremultipliedColorU8] {
-        bytemuck::cast_slice(self.data())
+        self.as_ref().pixels()
     }
 
     /// Consumes the internal data.
@@ -265,18 +297,27 @@ impl Pixmap {
 
     /// Consumes the pixmap and returns the internal data as demultiplied RGBA bytes.
     ///
+    /// Pixmaps in other formats will be converted into RGBA first.
+    ///
     /// Byteorder: RGBA
-    pub fn take_demultiplied(mut self) -> Vec<u8> {
+    pub fn take_demultiplied(self) -> Vec<u8> {
+        let mut pixmap = self.into_rgba8888();
+
         // Demultiply alpha.
         //
         // RasterPipeline is 15% faster here, but produces slightly different results
         // due to rounding. So we stick with this method for now.
-        for pixel in self.pixels_mut() {
-            let c = pixel.demultiply();
-            *pixel =
-                PremultipliedColorU8::from_rgba_unchecked(c.red(), c.green(), c.blue(), c.alpha());
+        demultiply_rgba8(&mut pixmap.data);
+        pixmap.data
+    }
+
+    /// Converts the pixmap into `PixelFormat::Rgba8888`, when needed.
+    fn into_rgba8888(self) -> Pixmap {
+        if self.format == PixelFormat::Rgba8888 {
+            self
+        } else {
+            self.as_ref().to_rgba8888()
         }
-        self.data
     }
 
     /// Returns a copy of the pixmap that intersects the `rect`.
@@ -285,6 +326,22 @@ impl Pixmap {
     pub fn clone_rect(&self, rect: IntRect) -> Option<Pixmap> {
         self.as_ref().clone_rect(rect)
     }
+
+    /// Compares two pixmaps.
+    ///
+    /// Pixmaps can have different pixel formats.
+    ///
+    /// Returns `None` when pixmaps have different sizes.
+    pub fn compare(&self, other: &Pixmap) -> Option<DiffReport> {
+        self.as_ref().compare(other.as_ref())
+    }
+}
+
+impl PartialEq for Pixmap {
+    fn eq(&self, other: &Self) -> bool {
+        // Damage is not a part of the content.
+        self.data == other.data && self.size == other.size && self.format == other.format
+    }
 }
 
 impl core::fmt::Debug for Pixmap {
@@ -293,6 +350,7 @@ impl core::fmt::Debug for Pixmap {
             .field("data", &"...")
             .field("width", &self.size.width())
             .field("height", &self.size.height())
+            .field("format", &self.format)
             .finish()
     }
 }
@@ -301,11 +359,14 @@ impl core::fmt::Debug for Pixmap {
 ///
 /// Can be created from `Pixmap` or from a user provided data.
 ///
-/// The data is not aligned, therefore width == stride.
+/// Unlike `Pixmap`, rows can be padded. See [`PixmapRef::from_bytes_with_stride`].
 #[derive(Clone, Copy, PartialEq)]
 pub struct PixmapRef<'a> {
     data: &'a [u8],
     size: IntSize,
+    // Row length in pixels, including padding.
+    real_width: usize,
+    format: PixelFormat,
 }
 
 impl<'a> PixmapRef<'a> {
@@ -316,23 +377,130 @@ impl<'a> PixmapRef<'a> {
     ///
     /// The `data` is assumed to have premultiplied RGBA pixels (byteorder: RGBA).
     pub fn from_bytes(data: &'a [u8], width: u32, height: u32) -> Option<Self> {
+        Self::from_bytes_with_format(data, width, height, PixelFormat::Rgba8888)
+    }
+
+    /// Creates a new `PixmapRef` from bytes in the specified pixel format.
+    ///
+    /// The size must be at least `size.width() * size.height() * format.bytes_per_pixel()`.
+    /// Zero size in an error.
+    pub fn from_bytes_with_format(
+        data: &'a [u8],
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+    ) -> Option<Self> {
+        let row_bytes = width as usize * format.bytes_per_pixel();
+        Self::from_bytes_with_stride_and_format(data, width, height, row_bytes, format)
+    }
+
+    /// Creates a new `PixmapRef` from bytes with padded rows.
+    ///
+    /// `row_bytes` is the distance between the starts of two rows in bytes.
+    /// It must be at least `width * BYTES_PER_PIXEL` and a multiple of `BYTES_PER_PIXEL`.
+    ///
+    /// The size must be at least `row_bytes * (height - 1) + width * BYTES_PER_PIXEL`.
+    /// Zero size in an error.
+    ///
+    /// The `data` is assumed to have premultiplied RGBA pixels (byteorder: RGBA).
+    pub fn from_bytes_with_stride(
+        data: &'a [u8],
+        width: u32,
+        height: u32,
+        row_bytes: usize,
+    ) -> Option<Self> {
+        Self::from_bytes_with_stride_and_format(
+            data,
+            width,
+            height,
+            row_bytes,
+            PixelFormat::Rgba8888,
+        )
+    }
+
+    /// Creates a new `PixmapRef` from bytes with padded rows in the specified pixel format.
+    ///
+    /// `row_bytes` must be at least `width * format.bytes_per_pixel()`
+    /// and a multiple of `format.bytes_per_pixel()`.
+    ///
+    /// The size must be at least `row_bytes * (height - 1) + width * format.bytes_per_pixel()`.
+    /// Zero size in an error.
+    pub fn from_bytes_with_stride_and_format(
+        data: &'a [u8],
+        width: u32,
+        height: u32,
+        row_bytes: usize,
+        format: PixelFormat,
+    ) -> Option<Self> {
         let size = IntSize::from_wh(width, height)?;
-        let data_len = data_len_for_size(size)?;
+        let real_width = real_width_for_row_bytes(size, row_bytes, format)?;
+        let data_len = compute_data_len(size, row_bytes, format)?;
         if data.len() < data_len {
             return None;
         }
 
-        Some(PixmapRef { data, size })
+        Some(PixmapRef {
+            data,
+            size,
+            real_width,
+            format,
+        })
     }
 
     /// Creates a new `Pixmap` from the current data.
     ///
-    /// Clones the underlying data.
+    /// Clones the underlying data. Row padding will be removed.
     pub fn to_owned(&self) -> Pixmap {
+        let data_len = data_len_for_size(self.size, self.format).unwrap_or(0);
+        let data = if self.real_width == self.width() as usize {
+            self.data[..data_len].to_vec()
+        } else {
+            let mut data = Vec::with_capacity(data_len);
+            for row in self.rows() {
+                data.extend_from_slice(row);
+            }
+            data
+        };
+
         Pixmap {
-            data: self.data.to_vec(),
+            data,
             size: self.size,
+            format: self.format,
+            damage: None,
+        }
+    }
+
+    /// Returns pixmap's rows without padding.
+    fn rows(&self) -> impl Iterator<Item = &'a [u8]> {
+        let data = self.data;
+        let row_bytes = self.row_bytes();
+        let width_bytes = self.width() as usize * self.format.bytes_per_pixel();
+        (0..self.height() as usize).map(move |y| &data[y * row_bytes..][..width_bytes])
+    }
+
+    /// Returns a copy of the pixmap converted into `PixelFormat::Rgba8888`.
+    ///
+    /// Row padding will be removed.
+    pub(crate) fn to_rgba8888(&self) -> Pixmap {
+        if self.format == PixelFormat::Rgba8888 {
+            return self.to_owned();
+        }
+
+        let bpp = self.format.bytes_per_pixel();
+        let mut pixmap = Pixmap::new(self.width(), self.height()).unwrap();
+        let dst_rows = pixmap.pixels_mut().chunks_exact_mut(self.width() as usize);
+        for (dst_row, src_row) in dst_rows.zip(self.rows()) {
+            for (p, data) in dst_row.iter_mut().zip(src_row.chunks_exact(bpp)) {
+                *p = self.format.read_pixel(data);
+            }
         }
+
+        pixmap
+    }
+
+    /// Checks that the pixmap is in the `Rgba8888` format and has no row padding.
+    pub(crate) fn is_packed_rgba8888(&self) -> bool {
+        self.format == PixelFormat::Rgba8888 && self.real_width == self.width() as usize
     }
 
     /// Returns pixmap's width.
@@ -357,23 +525,59 @@ impl<'a> PixmapRef<'a> {
         self.size.to_screen_int_rect(0, 0)
     }
 
+    /// Returns pixmap's pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Returns the distance between the starts of two rows in bytes.
+    pub fn row_bytes(&self) -> usize {
+        self.real_width * self.format.bytes_per_pixel()
+    }
+
+    /// Returns the row length in pixels, including padding.
+    pub(crate) fn real_width(&self) -> usize {
+        self.real_width
+    }
+
     /// Returns the internal data.
     ///
-    /// Byteorder: RGBA
+    /// Byteorder: RGBA, unless a different pixel format was set.
+    ///
+    /// Includes row padding, if any.
     pub fn data(&self) -> &'a [u8] {
         self.data
     }
 
     /// Returns a pixel color.
     ///
+    /// Pixels in other formats will be converted into RGBA.
+    ///
     /// Returns `None` when position is out of bounds.
     pub fn pixel(&self, x: u32, y: u32) -> Option<PremultipliedColorU8> {
-        let idx = self.width().checked_mul(y)?.checked_add(x)?;
-        self.pixels().get(idx as usize).cloned()
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        let bpp = self.format.bytes_per_pixel();
+        let idx = (y as usize * self.real_width + x as usize) * bpp;
+        let data = self.data.get(idx..idx + bpp)?;
+        Some(self.format.read_pixel(data))
     }
 
     /// Returns a slice of pixels.
+    ///
+    /// Includes row padding, if any. Use [`row_bytes`](Self::row_bytes) to find a row start.
+    ///
+    /// Panics when the pixel format is not `Rgba8888`.
+    /// Use [`data`](Self::data) to access pixels in other formats.
     pub fn pixels(&self) -> &'a [PremultipliedColorU8] {
+        assert_eq!(
+            self.format,
+            PixelFormat::Rgba8888,
+            "only Rgba8888 pixmaps can be accessed as pixels"
+        );
+
         bytemuck::cast_slice(self.data())
     }
 
@@ -386,45 +590,45 @@ impl<'a> PixmapRef<'a> {
         // TODO: to ScreenIntRect?
 
         let rect = self.rect().to_int_rect().intersect(&rect)?;
-        let mut new = Pixmap::new(rect.width(), rect.height())?;
+        let mut new = Pixmap::new_with_format(rect.width(), rect.height(), self.format)?;
         {
-            let old_pixels = self.pixels();
-            let mut new_mut = new.as_mut();
-            let new_pixels = new_mut.pixels_mut();
-
-            // TODO: optimize
-            for y in 0..rect.height() {
-                for x in 0..rect.width() {
-                    let old_idx = (y + rect.y() as u32) * self.width() + (x + rect.x() as u32);
-                    let new_idx = y * rect.width() + x;
-                    new_pixels[new_idx as usize] = old_pixels[old_idx as usize];
-                }
+            let bpp = self.format.bytes_per_pixel();
+            let old_row_bytes = self.row_bytes();
+            let new_row_bytes = rect.width() as usize * bpp;
+            let new_data = new.data_mut();
+            for y in 0..rect.height() as usize {
+                let old_idx = (y + rect.y() as usize) * old_row_bytes + rect.x() as usize * bpp;
+                let new_idx = y * new_row_bytes;
+                new_data[new_idx..new_idx + new_row_bytes]
+                    .copy_from_slice(&self.data[old_idx..old_idx + new_row_bytes]);
             }
         }
 
         Some(new)
     }
 
+    /// Compares two pixmaps.
+    ///
+    /// Pixmaps can have different pixel formats.
+    ///
+    /// Returns `None` when pixmaps have different sizes.
+    pub fn compare(&self, other: PixmapRef) -> Option<DiffReport> {
+        crate::compare::compare(*self, other)
+    }
+
     /// Encodes pixmap into a PNG data.
     #[cfg(feature = "png-format")]
     pub fn encode_png(&self) -> Result<Vec<u8>, png::EncodingError> {
-        // Skia uses skcms here, which is somewhat similar to RasterPipeline.
-
-        // Sadly, we have to copy the pixmap here, because of demultiplication.
-        // Not sure how to avoid this.
-        // TODO: remove allocation
-        let demultiplied_data = self.to_owned().take_demultiplied();
-
-        let mut data = Vec::new();
-        {
-            let mut encoder = png::Encoder::new(&mut data, self.width(), self.height());
-            encoder.set_color(png::ColorType::Rgba);
-            encoder.set_depth(png::BitDepth::Eight);
-            let mut writer = encoder.write_header()?;
-            writer.write_image_data(&demultiplied_data)?;
-        }
+        self.encode_png_with(&PngEncodeOptions::default())
+    }
 
-        Ok(data)
+    /// Encodes pixmap into a PNG data using the specified options.
+    #[cfg(feature = "png-format")]
+    pub fn encode_png_with(
+        &self,
+        options: &PngEncodeOptions,
+    ) -> Result<Vec<u8>, png::EncodingError> {
+        crate::png_format::encode(*self, options)
     }
 
     /// Saves pixmap as a PNG file.
@@ -442,6 +646,7 @@ impl core::fmt::Debug for PixmapRef<'_> {
             .field("data", &"...")
             .field("width", &self.size.width())
             .field("height", &self.size.height())
+            .field("format", &self.format)
             .finish()
     }
 }
@@ -450,11 +655,39 @@ impl core::fmt::Debug for PixmapRef<'_> {
 ///
 /// Can be created from `Pixmap` or from a user provided data.
 ///
-/// The data is not aligned, therefore width == stride.
-#[derive(PartialEq)]
+/// Unlike `Pixmap`, rows can be padded. See [`PixmapMut::from_bytes_with_stride`].
 pub struct PixmapMut<'a> {
     data: &'a mut [u8],
     size: IntSize,
+    // Row length in pixels, including padding.
+    real_width: usize,
+    format: PixelFormat,
+    damage: DamageState<'a>,
+}
+
+/// A damage tracking state of a `PixmapMut`.
+///
+/// A `PixmapMut` created via `Pixmap::as_mut` borrows the state of the `Pixmap`,
+/// so drawing onto a `Pixmap` doesn't lose damage.
+enum DamageState<'a> {
+    Owned(Option<Region>),
+    Borrowed(&'a mut Option<Region>),
+}
+
+impl DamageState<'_> {
+    fn get(&self) -> Option<&Region> {
+        match self {
+            DamageState::Owned(damage) => damage.as_ref(),
+            DamageState::Borrowed(damage) => damage.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self) -> &mut Option<Region> {
+        match self {
+            DamageState::Owned(damage) => damage,
+            DamageState::Borrowed(damage) => damage,
+        }
+    }
 }
 
 impl<'a> PixmapMut<'a> {
@@ -465,23 +698,84 @@ impl<'a> PixmapMut<'a> {
     ///
     /// The `data` is assumed to have premultiplied RGBA pixels (byteorder: RGBA).
     pub fn from_bytes(data: &'a mut [u8], width: u32, height: u32) -> Option<Self> {
+        Self::from_bytes_with_format(data, width, height, PixelFormat::Rgba8888)
+    }
+
+    /// Creates a new `PixmapMut` from bytes in the specified pixel format.
+    ///
+    /// This allows rendering directly into a framebuffer.
+    ///
+    /// The size must be at least `size.width() * size.height() * format.bytes_per_pixel()`.
+    /// Zero size in an error.
+    pub fn from_bytes_with_format(
+        data: &'a mut [u8],
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+    ) -> Option<Self> {
+        let row_bytes = width as usize * format.bytes_per_pixel();
+        Self::from_bytes_with_stride_and_format(data, width, height, row_bytes, format)
+    }
+
+    /// Creates a new `PixmapMut` from bytes with padded rows.
+    ///
+    /// `row_bytes` is the distance between the starts of two rows in bytes.
+    /// It must be at least `width * BYTES_PER_PIXEL` and a multiple of `BYTES_PER_PIXEL`.
+    ///
+    /// The size must be at least `row_bytes * (height - 1) + width * BYTES_PER_PIXEL`.
+    /// Zero size in an error.
+    ///
+    /// The `data` is assumed to have premultiplied RGBA pixels (byteorder: RGBA).
+    pub fn from_bytes_with_stride(
+        data: &'a mut [u8],
+        width: u32,
+        height: u32,
+        row_bytes: usize,
+    ) -> Option<Self> {
+        Self::from_bytes_with_stride_and_format(
+            data,
+            width,
+            height,
+            row_bytes,
+            PixelFormat::Rgba8888,
+        )
+    }
+
+    /// Creates a new `PixmapMut` from bytes with padded rows in the specified pixel format.
+    ///
+    /// `row_bytes` must be at least `width * format.bytes_per_pixel()`
+    /// and a multiple of `format.bytes_per_pixel()`.
+    ///
+    /// The size must be at least `row_bytes * (height - 1) + width * format.bytes_per_pixel()`.
+    /// Zero size in an error.
+    pub fn from_bytes_with_stride_and_format(
+        data: &'a mut [u8],
+        width: u32,
+        height: u32,
+        row_bytes: usize,
+        format: PixelFormat,
+    ) -> Option<Self> {
         let size = IntSize::from_wh(width, height)?;
-        let data_len = data_len_for_size(size)?;
+        let real_width = real_width_for_row_bytes(size, row_bytes, format)?;
+        let data_len = compute_data_len(size, row_bytes, format)?;
         if data.len() < data_len {
             return None;
         }
 
-        Some(PixmapMut { data, size })
+        Some(PixmapMut {
+            data,
+            size,
+            real_width,
+            format,
+            damage: DamageState::Owned(None),
+        })
     }
 
     /// Creates a new `Pixmap` from the current data.
     ///
-    /// Clones the underlying data.
+    /// Clones the underlying data. Row padding will be removed.
     pub fn to_owned(&self) -> Pixmap {
-        Pixmap {
-            data: self.data.to_vec(),
-            size: self.size,
-        }
+        self.as_ref().to_owned()
     }
 
     /// Returns a container that references Pixmap's data.
@@ -489,6 +783,8 @@ impl<'a> PixmapMut<'a> {
         PixmapRef {
             data: self.data,
             size: self.size,
+            real_width: self.real_width,
+            format: self.format,
         }
     }
 
@@ -509,23 +805,94 @@ impl<'a> PixmapMut<'a> {
         self.size
     }
 
+    /// Returns pixmap's pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Returns the distance between the starts of two rows in bytes.
+    pub fn row_bytes(&self) -> usize {
+        self.real_width * self.format.bytes_per_pixel()
+    }
+
+    /// Enables damage tracking.
+    ///
+    /// Afterwards, device-space bounds of every drawing operation, after clipping,
+    /// will be added to the [`PixmapMut::damage`] region.
+    /// Bounds are conservative and can include pixels that were not changed.
+    ///
+    /// Useful for incremental redraw.
+    pub fn enable_damage_tracking(&mut self) {
+        let damage = self.damage.get_mut();
+        if damage.is_none() {
+            *damage = Some(Region::new());
+        }
+    }
+
+    /// Returns a region affected by drawing since damage tracking was enabled
+    /// or the last [`PixmapMut::take_damage`] call.
+    ///
+    /// Returns `None` when damage tracking is disabled.
+    pub fn damage(&self) -> Option<&Region> {
+        self.damage.get()
+    }
+
+    /// Returns the damage region and resets it.
+    ///
+    /// Damage tracking stays enabled.
+    pub fn take_damage(&mut self) -> Option<Region> {
+        self.damage.get_mut().as_mut().map(core::mem::take)
+    }
+
+    /// Adds a rect to the damage region, when damage tracking is enabled.
+    pub(crate) fn add_damage(&mut self, rect: IntRect) {
+        if let Some(damage) = self.damage.get_mut() {
+            damage.add_rect(rect);
+        }
+    }
+
     /// Fills the entire pixmap with a specified color.
+    ///
+    /// Row padding is left untouched.
     pub fn fill(&mut self, color: Color) {
-        let c = color.premultiply().to_color_u8();
-        for p in self.pixels_mut() {
-            *p = c;
+        self.add_damage(self.size.to_int_rect(0, 0));
+
+        let bpp = self.format.bytes_per_pixel();
+        let mut pixel = [0; 8];
+        self.format.write_pixel(color.premultiply(), &mut pixel);
+
+        let row_bytes = self.row_bytes();
+        let width_bytes = self.width() as usize * bpp;
+        for y in 0..self.height() as usize {
+            let row = &mut self.data[y * row_bytes..][..width_bytes];
+            for p in row.chunks_exact_mut(bpp) {
+                p.copy_from_slice(&pixel[..bpp]);
+            }
         }
     }
 
     /// Returns the mutable internal data.
     ///
-    /// Byteorder: RGBA
+    /// Byteorder: RGBA, unless a different pixel format was set.
+    ///
+    /// Includes row padding, if any.
     pub fn data_mut(&mut self) -> &mut [u8] {
         self.data
     }
 
     /// Returns a mutable slice of pixels.
+    ///
+    /// Includes row padding, if any. Use [`row_bytes`](Self::row_bytes) to find a row start.
+    ///
+    /// Panics when the pixel format is not `Rgba8888`.
+    /// Use [`data_mut`](Self::data_mut) to access pixels in other formats.
     pub fn pixels_mut(&mut self) -> &mut [PremultipliedColorU8] {
+        assert_eq!(
+            self.format,
+            PixelFormat::Rgba8888,
+            "only Rgba8888 pixmaps can be accessed as pixels"
+        );
+
         bytemuck::cast_slice_mut(self.data_mut())
     }
 
@@ -533,33 +900,59 @@ impl<'a> PixmapMut<'a> {
     pub(crate) fn as_subpixmap(&mut self) -> SubPixmapMut<'_> {
         SubPixmapMut {
             size: self.size(),
-            real_width: self.width() as usize,
+            real_width: self.real_width,
+            format: self.format,
             data: self.data,
         }
     }
 
+    /// Returns a `PixmapMut` for the pixmap region that intersects the `rect`.
+    pub(crate) fn region_mut(&mut self, rect: IntRect) -> Option<PixmapMut<'_>> {
+        let sub = self.subpixmap(rect)?;
+        Some(PixmapMut {
+            data: sub.data,
+            size: sub.size,
+            real_width: sub.real_width,
+            format: sub.format,
+            damage: DamageState::Owned(None),
+        })
+    }
+
     /// Returns a mutable reference to the pixmap region that intersects the `rect`.
     ///
     /// Returns `None` when `Pixmap`'s rect doesn't contain `rect`.
     pub(crate) fn subpixmap(&mut self, rect: IntRect) -> Option<SubPixmapMut<'_>> {
         let rect = self.size.to_int_rect(0, 0).intersect(&rect)?;
-        let row_bytes = self.width() as usize * BYTES_PER_PIXEL;
-        let offset = rect.top() as usize * row_bytes + rect.left() as usize * BYTES_PER_PIXEL;
+        let bpp = self.format.bytes_per_pixel();
+        let row_bytes = self.row_bytes();
+        let offset = rect.top() as usize * row_bytes + rect.left() as usize * bpp;
 
         Some(SubPixmapMut {
             size: rect.size(),
-            real_width: self.width() as usize,
+            real_width: self.real_width,
+            format: self.format,
             data: &mut self.data[offset..],
         })
     }
 }
 
+impl PartialEq for PixmapMut<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        // Damage is not a part of the content.
+        self.data == other.data
+            && self.size == other.size
+            && self.real_width == other.real_width
+            && self.format == other.format
+    }
+}
+
 impl core::fmt::Debug for PixmapMut<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("PixmapMut")
             .field("data", &"...")
             .field("width", &self.size.width())
             .field("height", &self.size.height())
+            .field("format", &self.format)
             .finish()
     }
 }
@@ -578,11 +971,15 @@ pub struct SubPixmapMut<'a> {
     pub data: &'a mut [u8],
     pub size: IntSize,
     pub real_width: usize,
+    pub format: PixelFormat,
 }
 
 impl SubPixmapMut<'_> {
     /// Returns a mutable slice of pixels.
+    ///
+    /// Must be used only with 4 bytes per pixel formats.
     pub fn pixels_mut(&mut self) -> &mut [PremultipliedColorU8] {
+        debug_assert_eq!(self.format.bytes_per_pixel(), BYTES_PER_PIXEL);
         bytemuck::cast_slice_mut(self.data)
     }
 }
@@ -590,23 +987,38 @@ impl SubPixmapMut<'_> {
 /// Returns minimum bytes per row as usize.
 ///
 /// Pixmap's maximum value for row bytes must fit in 31 bits.
-fn min_row_bytes(size: IntSize) -> Option<NonZeroUsize> {
+fn min_row_bytes(size: IntSize, format: PixelFormat) -> Option<NonZeroUsize> {
     let w = i32::try_from(size.width()).ok()?;
-    let w = w.checked_mul(BYTES_PER_PIXEL as i32)?;
+    let w = w.checked_mul(format.bytes_per_pixel() as i32)?;
     NonZeroUsize::new(w as usize)
 }
 
 /// Returns storage size required by pixel array.
-fn compute_data_len(size: IntSize, row_bytes: usize) -> Option<usize> {
+fn compute_data_len(size: IntSize, row_bytes: usize, format: PixelFormat) -> Option<usize> {
     let h = size.height().checked_sub(1)?;
     let h = (h as usize).checked_mul(row_bytes)?;
 
-    let w = (size.width() as usize).checked_mul(BYTES_PER_PIXEL)?;
+    let w = (size.width() as usize).checked_mul(format.bytes_per_pixel())?;
 
     h.checked_add(w)
 }
 
-fn data_len_for_size(size: IntSize) -> Option<usize> {
-    let row_bytes = min_row_bytes(size)?;
-    compute_data_len(size, row_bytes.get())
+/// Returns row length in pixels for the user provided row bytes.
+///
+/// Row bytes must be a multiple of bytes per pixel and must fit in 31 bits.
+fn real_width_for_row_bytes(size: IntSize, row_bytes: usize, format: PixelFormat) -> Option<usize> {
+    let bpp = format.bytes_per_pixel();
+    if row_bytes < min_row_bytes(size, format)?.get()
+        || !row_bytes.is_multiple_of(bpp)
+        || i32::try_from(row_bytes).is_err()
+    {
+        return None;
+    }
+
+    Some(row_bytes / bpp)
+}
+
+fn data_len_for_size(size: IntSize, format: PixelFormat) -> Option<usize> {
+    let row_bytes = min_row_bytes(size, format)?;
+    compute_data_len(size, row_bytes.get(), format)
 }
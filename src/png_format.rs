@@ -0,0 +1,383 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+use crate::convert::{expand_rgb8_to_rgba8, premultiply_rgba8};
+use crate::{PixelFormat, Pixmap, PixmapRef};
+
+/// PNG decoding options.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct PngDecodeOptions {
+    /// Decodes 16-bit images into a `PixelFormat::Rgba16161616` pixmap.
+    ///
+    /// Otherwise, all images will be converted into 8-bit `PixelFormat::Rgba8888`.
+    ///
+    /// Default: false
+    pub keep_16_bit: bool,
+
+    /// Converts colors into sRGB using the `cICP`, `sRGB` and `gAMA` chunks.
+    ///
+    /// Only the transfer function is taken into account.
+    /// Color primaries, chromaticities and ICC profiles are ignored.
+    ///
+    /// Default: false
+    pub color_management: bool,
+}
+
+/// PNG compression level.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum PngCompression {
+    /// No compression. Fastest, but results in large files.
+    None,
+    /// Extremely fast, but light compression.
+    Fastest,
+    /// Fast compression with a decent compression ratio.
+    Fast,
+    /// Balances encoding speed and compression ratio.
+    #[default]
+    Balanced,
+    /// Slower, but produces smaller files.
+    High,
+}
+
+/// PNG rows filter.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PngFilter {
+    /// No filtering.
+    None,
+    /// Difference with the left pixel.
+    Sub,
+    /// Difference with the pixel above.
+    Up,
+    /// Difference with the average of the left and above pixels.
+    Average,
+    /// Paeth predictor.
+    Paeth,
+    /// Selects the best filter for each row.
+    Adaptive,
+}
+
+/// PNG encoding options.
+///
+/// Pixels are written as is. Besides the optional `sRGB` chunk,
+/// no color space information, like `gAMA` or `iCCP`, is written.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct PngEncodeOptions {
+    /// Compression level.
+    ///
+    /// Default: `PngCompression::Balanced`
+    pub compression: PngCompression,
+
+    /// Rows filter.
+    ///
+    /// When not set, will be selected based on the compression level.
+    ///
+    /// Default: None
+    pub filter: Option<PngFilter>,
+
+    /// Image resolution in dots per inch. Stored in the `pHYs` chunk.
+    ///
+    /// Default: None
+    pub dpi: Option<f32>,
+
+    /// Writes the `sRGB` chunk, marking the image as sRGB.
+    ///
+    /// Default: false
+    pub srgb: bool,
+
+    /// Text metadata as keyword-value pairs.
+    ///
+    /// Latin-1 text is stored in `tEXt` chunks and everything else in `iTXt` chunks.
+    /// Keywords must be 1-79 Latin-1 characters.
+    ///
+    /// Default: empty
+    pub text: Vec<(String, String)>,
+}
+
+/// A transfer function of the decoded image.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum TransferFunction {
+    Linear,
+    /// An exponent that converts values into linear.
+    Gamma(f32),
+}
+
+impl TransferFunction {
+    pub(crate) fn from_info(info: &png::Info) -> Option<Self> {
+        // cICP has the highest priority, followed by sRGB and gAMA.
+        if let Some(cicp) = info.coding_independent_code_points {
+            return match cicp.transfer_function {
+                13 => None,
+                8 => Some(TransferFunction::Linear),
+                4 => Some(TransferFunction::Gamma(2.2)),
+                5 => Some(TransferFunction::Gamma(2.8)),
+                n => {
+                    log::warn!("unsupported cICP transfer function: {}", n);
+                    None
+                }
+            };
+        }
+
+        if info.srgb.is_some() {
+            return None;
+        }
+
+        let gamma = info.gama_chunk?.into_value();
+        if !gamma.is_finite() || gamma <= 0.0 {
+            return None;
+        }
+
+        if gamma == 1.0 {
+            Some(TransferFunction::Linear)
+        } else {
+            Some(TransferFunction::Gamma(1.0 / gamma))
+        }
+    }
+
+    fn to_srgb(&self, v: f32) -> f32 {
+        let v = match self {
+            TransferFunction::Linear => v,
+            TransferFunction::Gamma(g) => v.powf(*g),
+        };
+
+        let v = if v <= 0.0031308 {
+            v * 12.92
+        } else {
+            v.powf(1.0 / 2.4) * 1.055 - 0.055
+        };
+
+        v.clamp(0.0, 1.0)
+    }
+
+    /// Builds a lookup table for values in the `0..=max` range.
+    fn lut(&self, max: u16) -> Vec<u16> {
+        let max_f = f32::from(max);
+        (0..=max)
+            .map(|v| (self.to_srgb(f32::from(v) / max_f) * max_f + 0.5) as u16)
+            .collect()
+    }
+}
+
+pub(crate) fn decode(
+    data: &[u8],
+    options: &PngDecodeOptions,
+) -> Result<Pixmap, png::DecodingError> {
+    let mut decoder = png::Decoder::new(std::io::BufReader::new(std::io::Cursor::new(data)));
+    // Expands palette, tRNS and low bit depth images, but keeps 16-bit ones.
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info()?;
+    let output_buffer_size = reader
+        .output_buffer_size()
+        .ok_or(png::DecodingError::LimitsExceeded)?;
+    let mut img_data = vec![0; output_buffer_size];
+    let info = reader.next_frame(&mut img_data)?;
+    img_data.truncate(info.buffer_size());
+
+    let transfer = if options.color_management {
+        TransferFunction::from_info(reader.info())
+    } else {
+        None
+    };
+
+    frame_to_pixmap(img_data, &info, transfer, options.keep_16_bit)
+}
+
+/// Converts decoded frame data into a premultiplied pixmap.
+pub(crate) fn frame_to_pixmap(
+    img_data: Vec<u8>,
+    info: &png::OutputInfo,
+    transfer: Option<TransferFunction>,
+    keep_16_bit: bool,
+) -> Result<Pixmap, png::DecodingError> {
+    let size = IntSize::from_wh(info.width, info.height)
+        .ok_or_else(|| make_custom_png_error("invalid image size"))?;
+
+    let pixmap = match info.bit_depth {
+        png::BitDepth::Eight => {
+            let mut rgba = if info.color_type == png::ColorType::Rgb {
+                expand_rgb8_to_rgba8(&img_data)
+            } else {
+                expand_to_rgba(img_data, info.color_type, 255u8)?
+            };
+            if let Some(transfer) = transfer {
+                let lut = transfer.lut(255);
+                for pixel in rgba.chunks_exact_mut(4) {
+                    for c in &mut pixel[..3] {
+                        *c = lut[usize::from(*c)] as u8;
+                    }
+                }
+            }
+
+            premultiply_rgba8(&mut rgba);
+            Pixmap::from_vec(rgba, size)
+        }
+        png::BitDepth::Sixteen => {
+            let samples: Vec<u16> = img_data
+                .chunks_exact(2)
+                .map(|v| u16::from_be_bytes([v[0], v[1]]))
+                .collect();
+            let mut rgba = expand_to_rgba(samples, info.color_type, u16::MAX)?;
+            if let Some(transfer) = transfer {
+                let lut = transfer.lut(u16::MAX);
+                for pixel in rgba.chunks_exact_mut(4) {
+                    for c in &mut pixel[..3] {
+                        *c = lut[usize::from(*c)];
+                    }
+                }
+            }
+
+            if keep_16_bit {
+                let mut data = Vec::with_capacity(rgba.len() * 2);
+                for pixel in rgba.chunks_exact(4) {
+                    let a = u32::from(pixel[3]);
+                    for c in &pixel[..3] {
+                        let c = (u32::from(*c) * a + 32767) / 65535;
+                        data.extend_from_slice(&(c as u16).to_ne_bytes());
+                    }
+                    data.extend_from_slice(&pixel[3].to_ne_bytes());
+                }
+
+                Pixmap::from_vec_with_format(data, size, PixelFormat::Rgba16161616)
+            } else {
+                let mut data: Vec<u8> = rgba
+                    .iter()
+                    .map(|v| ((u32::from(*v) * 255 + 32767) / 65535) as u8)
+                    .collect();
+                premultiply_rgba8(&mut data);
+                Pixmap::from_vec(data, size)
+            }
+        }
+        _ => return Err(make_custom_png_error("unsupported bit depth")),
+    };
+
+    pixmap.ok_or_else(|| make_custom_png_error("failed to create a pixmap"))
+}
+
+/// Converts unpremultiplied samples of any color type into RGBA.
+fn expand_to_rgba<T: Copy>(
+    samples: Vec<T>,
+    color_type: png::ColorType,
+    opaque: T,
+) -> Result<Vec<T>, png::DecodingError> {
+    let rgba = match color_type {
+        png::ColorType::Rgb => {
+            let mut rgba = Vec::with_capacity(samples.len() / 3 * 4);
+            for rgb in samples.chunks_exact(3) {
+                rgba.extend_from_slice(&[rgb[0], rgb[1], rgb[2], opaque]);
+            }
+            rgba
+        }
+        png::ColorType::Rgba => samples,
+        png::ColorType::Grayscale => {
+            let mut rgba = Vec::with_capacity(samples.len() * 4);
+            for gray in samples {
+                rgba.extend_from_slice(&[gray, gray, gray, opaque]);
+            }
+            rgba
+        }
+        png::ColorType::GrayscaleAlpha => {
+            let mut rgba = Vec::with_capacity(samples.len() * 2);
+            for slice in samples.chunks_exact(2) {
+                rgba.extend_from_slice(&[slice[0], slice[0], slice[0], slice[1]]);
+            }
+            rgba
+        }
+        png::ColorType::Indexed => {
+            // Must be expanded by the decoder.
+            return Err(make_custom_png_error("indexed PNG is not supported"));
+        }
+    };
+
+    Ok(rgba)
+}
+
+pub(crate) fn encode(
+    pixmap: PixmapRef,
+    options: &PngEncodeOptions,
+) -> Result<Vec<u8>, png::EncodingError> {
+    // Skia uses skcms here, which is somewhat similar to RasterPipeline.
+
+    // Sadly, we have to copy the pixmap here, because of demultiplication.
+    // Not sure how to avoid this.
+    // TODO: remove allocation
+    let demultiplied_data = pixmap.to_owned().take_demultiplied();
+
+    let mut data = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut data, pixmap.width(), pixmap.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        setup_encoder(&mut encoder, options)?;
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&demultiplied_data)?;
+    }
+
+    Ok(data)
+}
+
+pub(crate) fn setup_encoder<W: std::io::Write>(
+    encoder: &mut png::Encoder<W>,
+    options: &PngEncodeOptions,
+) -> Result<(), png::EncodingError> {
+    encoder.set_compression(match options.compression {
+        PngCompression::None => png::Compression::NoCompression,
+        PngCompression::Fastest => png::Compression::Fastest,
+        PngCompression::Fast => png::Compression::Fast,
+        PngCompression::Balanced => png::Compression::Balanced,
+        PngCompression::High => png::Compression::High,
+    });
+
+    if let Some(filter) = options.filter {
+        encoder.set_filter(match filter {
+            PngFilter::None => png::Filter::NoFilter,
+            PngFilter::Sub => png::Filter::Sub,
+            PngFilter::Up => png::Filter::Up,
+            PngFilter::Average => png::Filter::Avg,
+            PngFilter::Paeth => png::Filter::Paeth,
+            PngFilter::Adaptive => png::Filter::Adaptive,
+        });
+    }
+
+    if let Some(dpi) = options.dpi {
+        if !(dpi.is_finite() && dpi > 0.0) {
+            return Err(make_custom_png_encoding_error("invalid DPI"));
+        }
+
+        // pHYs stores pixels per meter.
+        let ppm = (dpi / 0.0254).round() as u32;
+        encoder.set_pixel_dims(Some(png::PixelDimensions {
+            xppu: ppm,
+            yppu: ppm,
+            unit: png::Unit::Meter,
+        }));
+    }
+
+    if options.srgb {
+        encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+    }
+
+    for (keyword, text) in &options.text {
+        if text.chars().all(|c| (c as u32) < 256) {
+            encoder.add_text_chunk(keyword.clone(), text.clone())?;
+        } else {
+            encoder.add_itxt_chunk(keyword.clone(), text.clone())?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn make_custom_png_error(msg: &str) -> png::DecodingError {
+    std::io::Error::other(msg).into()
+}
+
+pub(crate) fn make_custom_png_encoding_error(msg: &str) -> png::EncodingError {
+    std::io::Error::other(msg).into()
+}
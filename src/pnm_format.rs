@@ -0,0 +1,338 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Binary [Netpbm](https://netpbm.sourceforge.net/doc/) formats: PGM, PPM and PAM.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+use crate::codec::{Channels, RawImage};
+use crate::{Mask, Pixmap, PixmapRef};
+
+impl Pixmap {
+    /// Decodes a binary PGM (P5), PPM (P6) or PAM (P7) data into a `Pixmap`.
+    ///
+    /// 16-bit images are rounded to 8 bits.
+    ///
+    /// Returns `None` when data is malformed.
+    pub fn decode_pnm(data: &[u8]) -> Option<Self> {
+        decode(data)?.into_pixmap()
+    }
+
+    /// Loads a binary PGM, PPM or PAM file into a `Pixmap`.
+    #[cfg(feature = "std")]
+    pub fn load_pnm<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::decode_pnm(&data).ok_or_else(|| crate::codec::invalid_data_error("invalid PNM"))
+    }
+
+    /// Encodes pixmap into a binary PPM data.
+    ///
+    /// PPM doesn't support transparency, therefore alpha will be ignored.
+    /// Use [`Pixmap::encode_pam`] to preserve it.
+    pub fn encode_ppm(&self) -> Vec<u8> {
+        self.as_ref().encode_ppm()
+    }
+
+    /// Saves pixmap as a binary PPM file.
+    ///
+    /// Alpha will be ignored.
+    #[cfg(feature = "std")]
+    pub fn save_ppm<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.as_ref().save_ppm(path)
+    }
+
+    /// Encodes pixmap into a PAM data.
+    pub fn encode_pam(&self) -> Vec<u8> {
+        self.as_ref().encode_pam()
+    }
+
+    /// Saves pixmap as a PAM file.
+    #[cfg(feature = "std")]
+    pub fn save_pam<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.as_ref().save_pam(path)
+    }
+}
+
+impl PixmapRef<'_> {
+    /// Encodes pixmap into a binary PPM data.
+    ///
+    /// PPM doesn't support transparency, therefore alpha will be ignored.
+    /// Use [`PixmapRef::encode_pam`] to preserve it.
+    pub fn encode_ppm(&self) -> Vec<u8> {
+        let image = RawImage::from_pixmap(*self);
+        let mut data = header(b"P6", image.size);
+        data.reserve(image.data.len() / 4 * 3);
+        for p in image.data.chunks_exact(4) {
+            data.extend_from_slice(&p[0..3]);
+        }
+
+        data
+    }
+
+    /// Saves pixmap as a binary PPM file.
+    ///
+    /// Alpha will be ignored.
+    #[cfg(feature = "std")]
+    pub fn save_ppm<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.encode_ppm())
+    }
+
+    /// Encodes pixmap into a PAM data.
+    pub fn encode_pam(&self) -> Vec<u8> {
+        let image = RawImage::from_pixmap(*self);
+        let mut data = format!(
+            "P7\nWIDTH {}\nHEIGHT {}\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n",
+            image.size.width(),
+            image.size.height()
+        )
+        .into_bytes();
+        data.extend_from_slice(&image.data);
+        data
+    }
+
+    /// Saves pixmap as a PAM file.
+    #[cfg(feature = "std")]
+    pub fn save_pam<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.encode_pam())
+    }
+}
+
+impl Mask {
+    /// Decodes a binary PGM (P5), PPM (P6) or PAM (P7) data into a `Mask`.
+    ///
+    /// Only opaque grayscale images are supported.
+    pub fn decode_pnm(data: &[u8]) -> Option<Self> {
+        decode(data)?.into_mask()
+    }
+
+    /// Loads a binary PGM, PPM or PAM file into a `Mask`.
+    ///
+    /// Only opaque grayscale images are supported.
+    #[cfg(feature = "std")]
+    pub fn load_pnm<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::decode_pnm(&data).ok_or_else(|| crate::codec::invalid_data_error("invalid PNM"))
+    }
+
+    /// Encodes mask into a binary PGM data.
+    pub fn encode_pgm(&self) -> Vec<u8> {
+        let mut data = header(b"P5", self.size());
+        data.extend_from_slice(self.data());
+        data
+    }
+
+    /// Saves mask as a binary PGM file.
+    #[cfg(feature = "std")]
+    pub fn save_pgm<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.encode_pgm())
+    }
+}
+
+fn header(magic: &[u8; 2], size: IntSize) -> Vec<u8> {
+    let mut data = magic.to_vec();
+    data.extend_from_slice(format!("\n{} {}\n255\n", size.width(), size.height()).as_bytes());
+    data
+}
+
+fn decode(data: &[u8]) -> Option<RawImage> {
+    let mut s = Stream { data, pos: 0 };
+    let (width, height, depth, max_value) = match data.get(0..2) {
+        Some(b"P5") | Some(b"P6") => {
+            s.pos = 2;
+            let width = s.parse_number()?;
+            let height = s.parse_number()?;
+            let max_value = s.parse_number()?;
+            // Exactly one whitespace character before the raster.
+            if !s.next()?.is_ascii_whitespace() {
+                log::warn!("invalid PNM header");
+                return None;
+            }
+
+            let depth = if &data[0..2] == b"P5" { 1 } else { 3 };
+            (width, height, depth, max_value)
+        }
+        Some(b"P7") => {
+            s.pos = 2;
+            parse_pam_header(&mut s)?
+        }
+        _ => {
+            log::warn!("not a binary PNM image");
+            return None;
+        }
+    };
+
+    let channels = match depth {
+        1 => Channels::Gray,
+        2 => Channels::GrayAlpha,
+        3 => Channels::Rgb,
+        4 => Channels::Rgba,
+        _ => {
+            log::warn!("unsupported PNM depth: {}", depth);
+            return None;
+        }
+    };
+
+    if max_value == 0 || max_value > 65535 {
+        log::warn!("invalid PNM max value: {}", max_value);
+        return None;
+    }
+
+    let size = IntSize::from_wh(width, height)?;
+    let bytes_per_sample = if max_value > 255 { 2 } else { 1 };
+    let raster = &data[s.pos..];
+    check_data_len(raster, size, depth as usize * bytes_per_sample)?;
+
+    let samples_count = size.width() as usize * size.height() as usize * depth as usize;
+    let data = if bytes_per_sample == 2 {
+        raster
+            .chunks_exact(2)
+            .take(samples_count)
+            .map(|v| scale(u32::from(u16::from_be_bytes([v[0], v[1]])), max_value))
+            .collect()
+    } else if max_value == 255 {
+        raster[..samples_count].to_vec()
+    } else {
+        raster[..samples_count]
+            .iter()
+            .map(|v| scale(u32::from(*v), max_value))
+            .collect()
+    };
+
+    Some(RawImage {
+        size,
+        channels,
+        data,
+    })
+}
+
+/// Returns width, height, depth and max value.
+fn parse_pam_header(s: &mut Stream) -> Option<(u32, u32, u32, u32)> {
+    let mut width = None;
+    let mut height = None;
+    let mut depth = None;
+    let mut max_value = None;
+    loop {
+        match s.parse_token()? {
+            b"WIDTH" => width = Some(s.parse_number()?),
+            b"HEIGHT" => height = Some(s.parse_number()?),
+            b"DEPTH" => depth = Some(s.parse_number()?),
+            b"MAXVAL" => max_value = Some(s.parse_number()?),
+            b"TUPLTYPE" => {
+                // The depth is enough to tell the channels layout.
+                s.skip_line();
+            }
+            b"ENDHDR" => {
+                if s.next()? != b'\n' {
+                    log::warn!("invalid PAM header");
+                    return None;
+                }
+
+                break;
+            }
+            _ => {
+                log::warn!("invalid PAM header");
+                return None;
+            }
+        }
+    }
+
+    match (width, height, depth, max_value) {
+        (Some(w), Some(h), Some(d), Some(m)) => Some((w, h, d, m)),
+        _ => {
+            log::warn!("incomplete PAM header");
+            None
+        }
+    }
+}
+
+/// Checks that `data` is long enough to hold an image of the specified size.
+///
+/// Prevents huge allocations on malformed headers.
+fn check_data_len(data: &[u8], size: IntSize, bytes_per_pixel: usize) -> Option<()> {
+    let len = (size.width() as usize)
+        .checked_mul(size.height() as usize)?
+        .checked_mul(bytes_per_pixel)?;
+    if data.len() < len {
+        log::warn!("image data is too short");
+        return None;
+    }
+
+    Some(())
+}
+
+#[inline]
+fn scale(v: u32, max_value: u32) -> u8 {
+    ((v.min(max_value) * 255 + max_value / 2) / max_value) as u8
+}
+
+struct Stream<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Stream<'a> {
+    fn next(&mut self) -> Option<u8> {
+        let c = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.data.get(self.pos) {
+            if *c == b'\n' {
+                break;
+            }
+
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespaces and comments.
+    fn skip_spaces(&mut self) {
+        while let Some(c) = self.data.get(self.pos) {
+            if *c == b'#' {
+                self.skip_line();
+            } else if c.is_ascii_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn parse_token(&mut self) -> Option<&'a [u8]> {
+        self.skip_spaces();
+        let start = self.pos;
+        while let Some(c) = self.data.get(self.pos) {
+            if c.is_ascii_whitespace() {
+                break;
+            }
+
+            self.pos += 1;
+        }
+
+        if start == self.pos {
+            log::warn!("unexpected end of PNM header");
+            return None;
+        }
+
+        Some(&self.data[start..self.pos])
+    }
+
+    fn parse_number(&mut self) -> Option<u32> {
+        let token = self.parse_token()?;
+        let n = core::str::from_utf8(token)
+            .ok()
+            .and_then(|s| s.parse().ok());
+        if n.is_none() {
+            log::warn!("invalid number in PNM header");
+        }
+
+        n
+    }
+}
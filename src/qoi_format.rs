@@ -0,0 +1,274 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! [QOI](https://qoiformat.org/) encoder and decoder.
+
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+use crate::codec::{Channels, RawImage};
+use crate::{Mask, Pixmap, PixmapRef};
+
+const MAGIC: &[u8; 4] = b"qoif";
+const HEADER_SIZE: usize = 14;
+const END_MARKER: &[u8; 8] = &[0, 0, 0, 0, 0, 0, 0, 1];
+
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RUN: u8 = 0xc0;
+const OP_RGB: u8 = 0xfe;
+const OP_RGBA: u8 = 0xff;
+const OP_MASK: u8 = 0xc0;
+
+const MAX_RUN: u8 = 62;
+
+impl Pixmap {
+    /// Decodes a QOI data into a `Pixmap`.
+    ///
+    /// Returns `None` when data is malformed.
+    pub fn decode_qoi(data: &[u8]) -> Option<Self> {
+        decode(data)?.into_pixmap()
+    }
+
+    /// Loads a QOI file into a `Pixmap`.
+    #[cfg(feature = "std")]
+    pub fn load_qoi<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::decode_qoi(&data).ok_or_else(|| crate::codec::invalid_data_error("invalid QOI"))
+    }
+
+    /// Encodes pixmap into a QOI data.
+    pub fn encode_qoi(&self) -> Vec<u8> {
+        self.as_ref().encode_qoi()
+    }
+
+    /// Saves pixmap as a QOI file.
+    #[cfg(feature = "std")]
+    pub fn save_qoi<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.as_ref().save_qoi(path)
+    }
+}
+
+impl PixmapRef<'_> {
+    /// Encodes pixmap into a QOI data.
+    pub fn encode_qoi(&self) -> Vec<u8> {
+        encode(RawImage::from_pixmap(*self))
+    }
+
+    /// Saves pixmap as a QOI file.
+    #[cfg(feature = "std")]
+    pub fn save_qoi<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.encode_qoi())
+    }
+}
+
+impl Mask {
+    /// Decodes a QOI data into a `Mask`.
+    ///
+    /// Only opaque grayscale images are supported.
+    pub fn decode_qoi(data: &[u8]) -> Option<Self> {
+        decode(data)?.into_mask()
+    }
+
+    /// Loads a QOI file into a `Mask`.
+    ///
+    /// Only opaque grayscale images are supported.
+    #[cfg(feature = "std")]
+    pub fn load_qoi<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::decode_qoi(&data).ok_or_else(|| crate::codec::invalid_data_error("invalid QOI"))
+    }
+
+    /// Encodes mask into a QOI data.
+    ///
+    /// The mask is stored as an opaque grayscale RGB image.
+    pub fn encode_qoi(&self) -> Vec<u8> {
+        encode(RawImage::from_mask(self))
+    }
+
+    /// Saves mask as a QOI file.
+    #[cfg(feature = "std")]
+    pub fn save_qoi<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.encode_qoi())
+    }
+}
+
+#[inline]
+fn hash(p: [u8; 4]) -> usize {
+    let [r, g, b, a] = p.map(usize::from);
+    (r * 3 + g * 5 + b * 7 + a * 11) % 64
+}
+
+fn encode(image: RawImage) -> Vec<u8> {
+    let size = image.size;
+    let has_alpha = matches!(image.channels, Channels::Rgba | Channels::GrayAlpha);
+    let rgba = image.into_rgba();
+
+    let mut out = Vec::with_capacity(HEADER_SIZE + rgba.len() / 2 + END_MARKER.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&size.width().to_be_bytes());
+    out.extend_from_slice(&size.height().to_be_bytes());
+    out.push(if has_alpha { 4 } else { 3 });
+    out.push(0); // sRGB with linear alpha
+
+    let mut index = [[0u8; 4]; 64];
+    let mut prev = [0, 0, 0, 255];
+    let mut run = 0;
+    let pixels_count = rgba.len() / 4;
+    for (i, p) in rgba.chunks_exact(4).enumerate() {
+        let px = [p[0], p[1], p[2], p[3]];
+
+        if px == prev {
+            run += 1;
+            if run == MAX_RUN || i + 1 == pixels_count {
+                out.push(OP_RUN | (run - 1));
+                run = 0;
+            }
+
+            continue;
+        }
+
+        if run > 0 {
+            out.push(OP_RUN | (run - 1));
+            run = 0;
+        }
+
+        let idx = hash(px);
+        if index[idx] == px {
+            out.push(OP_INDEX | idx as u8);
+        } else {
+            index[idx] = px;
+
+            if px[3] == prev[3] {
+                let vr = px[0].wrapping_sub(prev[0]) as i8;
+                let vg = px[1].wrapping_sub(prev[1]) as i8;
+                let vb = px[2].wrapping_sub(prev[2]) as i8;
+                let vg_r = vr.wrapping_sub(vg);
+                let vg_b = vb.wrapping_sub(vg);
+
+                if (-2..=1).contains(&vr) && (-2..=1).contains(&vg) && (-2..=1).contains(&vb) {
+                    out.push(
+                        OP_DIFF | ((vr + 2) as u8) << 4 | ((vg + 2) as u8) << 2 | (vb + 2) as u8,
+                    );
+                } else if (-8..=7).contains(&vg_r)
+                    && (-32..=31).contains(&vg)
+                    && (-8..=7).contains(&vg_b)
+                {
+                    out.push(OP_LUMA | (vg + 32) as u8);
+                    out.push(((vg_r + 8) as u8) << 4 | (vg_b + 8) as u8);
+                } else {
+                    out.extend_from_slice(&[OP_RGB, px[0], px[1], px[2]]);
+                }
+            } else {
+                out.extend_from_slice(&[OP_RGBA, px[0], px[1], px[2], px[3]]);
+            }
+        }
+
+        prev = px;
+    }
+
+    out.extend_from_slice(END_MARKER);
+    out
+}
+
+fn decode(data: &[u8]) -> Option<RawImage> {
+    if data.len() < HEADER_SIZE + END_MARKER.len() || &data[0..4] != MAGIC {
+        log::warn!("not a QOI image");
+        return None;
+    }
+
+    let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+    let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+    let channels = data[12];
+    if channels != 3 && channels != 4 {
+        log::warn!("invalid QOI channels number");
+        return None;
+    }
+
+    let size = IntSize::from_wh(width, height)?;
+
+    // A single byte can encode up to 62 pixels.
+    // Prevents huge allocations on malformed headers.
+    let chunks = &data[HEADER_SIZE..data.len() - END_MARKER.len()];
+    let pixels_count = (width as usize).checked_mul(height as usize);
+    let data_len = pixels_count.and_then(|n| n.checked_mul(4));
+    let (pixels_count, data_len) = match (pixels_count, data_len) {
+        (Some(pixels_count), Some(data_len)) => (pixels_count, data_len),
+        _ => {
+            log::warn!("QOI image is too big");
+            return None;
+        }
+    };
+
+    if pixels_count / usize::from(MAX_RUN) > chunks.len() {
+        log::warn!("QOI data is too short");
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(data_len);
+    let mut index = [[0u8; 4]; 64];
+    let mut px = [0, 0, 0, 255];
+    let mut pos = 0;
+    let mut run = 0;
+    let mut read = || -> Option<u8> {
+        let b = *chunks.get(pos)?;
+        pos += 1;
+        Some(b)
+    };
+
+    for _ in 0..pixels_count {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let b = match read() {
+                Some(v) => v,
+                None => {
+                    log::warn!("QOI data is too short");
+                    return None;
+                }
+            };
+
+            if b == OP_RGB {
+                px[0] = read()?;
+                px[1] = read()?;
+                px[2] = read()?;
+            } else if b == OP_RGBA {
+                px[0] = read()?;
+                px[1] = read()?;
+                px[2] = read()?;
+                px[3] = read()?;
+            } else {
+                match b & OP_MASK {
+                    OP_INDEX => px = index[usize::from(b)],
+                    OP_DIFF => {
+                        px[0] = px[0].wrapping_add((b >> 4) & 3).wrapping_sub(2);
+                        px[1] = px[1].wrapping_add((b >> 2) & 3).wrapping_sub(2);
+                        px[2] = px[2].wrapping_add(b & 3).wrapping_sub(2);
+                    }
+                    OP_LUMA => {
+                        let b2 = read()?;
+                        let vg = (b & 0x3f).wrapping_sub(32);
+                        px[0] = px[0].wrapping_add(vg.wrapping_sub(8).wrapping_add(b2 >> 4));
+                        px[1] = px[1].wrapping_add(vg);
+                        px[2] = px[2].wrapping_add(vg.wrapping_sub(8).wrapping_add(b2 & 0x0f));
+                    }
+                    _ => run = b & 0x3f,
+                }
+            }
+
+            index[hash(px)] = px;
+        }
+
+        out.extend_from_slice(&px);
+    }
+
+    Some(RawImage {
+        size,
+        channels: Channels::Rgba,
+        data: out,
+    })
+}
@@ -0,0 +1,156 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntRect;
+
+/// The maximum number of rects stored by a region.
+///
+/// Afterwards, the closest rects will be merged.
+const MAX_RECTS: usize = 16;
+
+/// A set of rectangles.
+///
+/// Used to accumulate pixmap regions affected by drawing.
+/// See [`PixmapMut::enable_damage_tracking`](crate::PixmapMut::enable_damage_tracking).
+///
+/// Rects that can be represented by a single rect are merged.
+/// The region is conservative, i.e. it can be larger than the union of added rects,
+/// since the number of stored rects is limited.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Region {
+    rects: Vec<IntRect>,
+}
+
+impl Region {
+    /// Creates a new, empty region.
+    pub fn new() -> Self {
+        Region::default()
+    }
+
+    /// Checks that the region is empty.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Returns region's rects.
+    ///
+    /// Rects are not overlapping only when they cannot be merged.
+    /// The order is unspecified.
+    pub fn rects(&self) -> &[IntRect] {
+        &self.rects
+    }
+
+    /// Returns a rect that contains the whole region.
+    ///
+    /// Returns `None` when the region is empty or the rect is too big.
+    pub fn bounds(&self) -> Option<IntRect> {
+        let mut iter = self.rects.iter();
+        let first = *iter.next()?;
+        iter.try_fold(first, |a, b| join(a, *b))
+    }
+
+    /// Checks that the region intersects a rect.
+    pub fn intersects(&self, rect: &IntRect) -> bool {
+        self.rects.iter().any(|r| r.intersect(rect).is_some())
+    }
+
+    /// Adds a rect to the region.
+    pub fn add_rect(&mut self, mut rect: IntRect) {
+        let mut i = 0;
+        while i < self.rects.len() {
+            let r = self.rects[i];
+            if r.contains(&rect) {
+                return;
+            }
+
+            if rect.contains(&r) || is_mergeable(r, rect) {
+                if let Some(joined) = join(r, rect) {
+                    rect = joined;
+                    self.rects.swap_remove(i);
+                    // A larger rect can be mergeable with already checked ones.
+                    i = 0;
+                    continue;
+                }
+            }
+
+            i += 1;
+        }
+
+        self.rects.push(rect);
+
+        if self.rects.len() > MAX_RECTS {
+            self.merge_closest();
+        }
+    }
+
+    /// Adds all rects from an another region.
+    pub fn add_region(&mut self, other: &Region) {
+        for rect in &other.rects {
+            self.add_rect(*rect);
+        }
+    }
+
+    /// Removes all rects.
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+
+    /// Merges two rects which union adds the smallest area.
+    fn merge_closest(&mut self) {
+        let mut best = None;
+        let mut best_waste = i64::MAX;
+        for i in 0..self.rects.len() {
+            for j in i + 1..self.rects.len() {
+                if let Some(waste) = waste(self.rects[i], self.rects[j]) {
+                    if waste < best_waste {
+                        best_waste = waste;
+                        best = Some((i, j));
+                    }
+                }
+            }
+        }
+
+        if let Some((i, j)) = best {
+            // Remove the last one first to keep the index valid.
+            let b = self.rects.swap_remove(j);
+            let a = self.rects.swap_remove(i);
+            // Cannot fail, since the waste was calculated.
+            if let Some(rect) = join(a, b) {
+                self.add_rect(rect);
+            }
+        }
+    }
+}
+
+fn area(rect: IntRect) -> i64 {
+    i64::from(rect.width()) * i64::from(rect.height())
+}
+
+/// Returns a rect that contains both rects.
+fn join(a: IntRect, b: IntRect) -> Option<IntRect> {
+    IntRect::from_ltrb(
+        a.left().min(b.left()),
+        a.top().min(b.top()),
+        a.right().max(b.right()),
+        a.bottom().max(b.bottom()),
+    )
+}
+
+/// Returns an area that would be added by joining two rects.
+fn waste(a: IntRect, b: IntRect) -> Option<i64> {
+    let overlap = a.intersect(&b).map(area).unwrap_or(0);
+    Some(area(join(a, b)?) - area(a) - area(b) + overlap)
+}
+
+/// Checks that the union of two rects is a rect.
+fn is_mergeable(a: IntRect, b: IntRect) -> bool {
+    let same_columns = a.left() == b.left() && a.right() == b.right();
+    let same_rows = a.top() == b.top() && a.bottom() == b.bottom();
+    let touch_vertically = a.top() <= b.bottom() && b.top() <= a.bottom();
+    let touch_horizontally = a.left() <= b.right() && b.left() <= a.right();
+    (same_columns && touch_vertically) || (same_rows && touch_horizontally)
+}
@@ -0,0 +1,247 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+use crate::{CubicResampler, Pixmap, PixmapRef, PremultipliedColorU8};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use tiny_skia_path::NoStdFloat;
+
+/// A filter used by [`Pixmap::resize`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ResizeFilter {
+    /// Box filter. Averages all covered pixels when downscaling
+    /// and acts like nearest-neighbor when upscaling.
+    Box,
+    /// Mitchell-Netravali cubic filter. `B = 1/3, C = 1/3`.
+    Mitchell,
+    /// Catmull-Rom cubic filter. `B = 0, C = 1/2`.
+    CatmullRom,
+    /// Lanczos filter with 3 lobes. The sharpest one, but slow.
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    fn support(&self) -> f32 {
+        match self {
+            ResizeFilter::Box => 0.5,
+            ResizeFilter::Mitchell | ResizeFilter::CatmullRom => 2.0,
+            ResizeFilter::Lanczos3 => 3.0,
+        }
+    }
+
+    fn weight(&self, x: f32) -> f32 {
+        match self {
+            ResizeFilter::Box => {
+                if (-0.5..0.5).contains(&x) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            ResizeFilter::Mitchell => cubic(CubicResampler::MITCHELL, x),
+            ResizeFilter::CatmullRom => cubic(CubicResampler::CATMULL_ROM, x),
+            ResizeFilter::Lanczos3 => {
+                if x.abs() < 3.0 {
+                    sinc(x) * sinc(x / 3.0)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+fn cubic(r: CubicResampler, x: f32) -> f32 {
+    let (b, c) = (r.b, r.c);
+    let x = x.abs();
+    let v = if x < 1.0 {
+        (12.0 - 9.0 * b - 6.0 * c) * x * x * x
+            + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+            + (6.0 - 2.0 * b)
+    } else if x < 2.0 {
+        (-b - 6.0 * c) * x * x * x
+            + (6.0 * b + 30.0 * c) * x * x
+            + (-12.0 * b - 48.0 * c) * x
+            + (8.0 * b + 24.0 * c)
+    } else {
+        0.0
+    };
+
+    v / 6.0
+}
+
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = x * core::f32::consts::PI;
+        x.sin() / x
+    }
+}
+
+/// Filter weights for a single destination pixel.
+struct Contribution {
+    start: usize,
+    weights: Vec<f32>,
+}
+
+fn contributions(src_len: u32, dst_len: u32, filter: ResizeFilter) -> Vec<Contribution> {
+    let scale = dst_len as f32 / src_len as f32;
+    // When downscaling, the filter has to be stretched to cover all source pixels.
+    let filter_scale = (1.0 / scale).max(1.0);
+    let support = filter.support() * filter_scale;
+
+    let mut list = Vec::with_capacity(dst_len as usize);
+    for i in 0..dst_len {
+        let center = (i as f32 + 0.5) / scale;
+        let start = ((center - support).floor().max(0.0) as u32).min(src_len - 1);
+        let end = ((center + support).ceil() as u32).clamp(start + 1, src_len);
+
+        let mut weights: Vec<f32> = (start..end)
+            .map(|j| filter.weight((j as f32 + 0.5 - center) / filter_scale))
+            .collect();
+
+        let sum: f32 = weights.iter().sum();
+        if sum != 0.0 {
+            weights.iter_mut().for_each(|w| *w /= sum);
+        } else {
+            // Can happen only with a box filter. Fallback to the nearest pixel.
+            let nearest = (center as u32).clamp(start, end - 1);
+            weights.iter_mut().for_each(|w| *w = 0.0);
+            weights[(nearest - start) as usize] = 1.0;
+        }
+
+        list.push(Contribution {
+            start: start as usize,
+            weights,
+        });
+    }
+
+    list
+}
+
+impl PixmapRef<'_> {
+    /// Returns a resized copy of the pixmap.
+    ///
+    /// Uses a separable filter applied to premultiplied pixels,
+    /// so transparent pixels do not bleed into opaque ones.
+    ///
+    /// The result is always in the `Rgba8888` pixel format.
+    ///
+    /// Returns `None` when the new size is too big.
+    pub fn resize(&self, size: IntSize, filter: ResizeFilter) -> Option<Pixmap> {
+        if !self.is_packed_rgba8888() {
+            return self.to_rgba8888().resize(size, filter);
+        }
+
+        let mut dst = Pixmap::new(size.width(), size.height())?;
+        if size == self.size() {
+            dst.data_mut().copy_from_slice(self.data());
+            return Some(dst);
+        }
+
+        let src_w = self.width() as usize;
+        let src_h = self.height() as usize;
+        let dst_w = size.width() as usize;
+
+        // Horizontal pass. Produces a `dst_w x src_h` image.
+        let columns = contributions(self.width(), size.width(), filter);
+        let src_pixels = self.pixels();
+        let mut tmp = vec![[0.0f32; 4]; dst_w * src_h];
+        for y in 0..src_h {
+            let row = &src_pixels[y * src_w..(y + 1) * src_w];
+            for (x, contrib) in columns.iter().enumerate() {
+                let mut acc = [0.0; 4];
+                for (i, w) in contrib.weights.iter().enumerate() {
+                    let c = row[contrib.start + i];
+                    acc[0] += c.red() as f32 * w;
+                    acc[1] += c.green() as f32 * w;
+                    acc[2] += c.blue() as f32 * w;
+                    acc[3] += c.alpha() as f32 * w;
+                }
+                tmp[y * dst_w + x] = acc;
+            }
+        }
+
+        // Vertical pass.
+        let rows = contributions(self.height(), size.height(), filter);
+        let dst_pixels = dst.pixels_mut();
+        for (y, contrib) in rows.iter().enumerate() {
+            for x in 0..dst_w {
+                let mut acc = [0.0; 4];
+                for (i, w) in contrib.weights.iter().enumerate() {
+                    let c = &tmp[(contrib.start + i) * dst_w + x];
+                    acc[0] += c[0] * w;
+                    acc[1] += c[1] * w;
+                    acc[2] += c[2] * w;
+                    acc[3] += c[3] * w;
+                }
+
+                // Filters with negative lobes can produce out of range values,
+                // which must be clamped to keep the color premultiplied.
+                let a = (acc[3] + 0.5).clamp(0.0, 255.0) as u8;
+                let to_u8 = |v: f32| ((v + 0.5).clamp(0.0, 255.0) as u8).min(a);
+                dst_pixels[y * dst_w + x] = PremultipliedColorU8::from_rgba_unchecked(
+                    to_u8(acc[0]),
+                    to_u8(acc[1]),
+                    to_u8(acc[2]),
+                    a,
+                );
+            }
+        }
+
+        Some(dst)
+    }
+}
+
+impl Pixmap {
+    /// Returns a resized copy of the pixmap.
+    ///
+    /// Uses a separable filter applied to premultiplied pixels,
+    /// so transparent pixels do not bleed into opaque ones.
+    ///
+    /// The result is always in the `Rgba8888` pixel format.
+    ///
+    /// Returns `None` when the new size is too big.
+    pub fn resize(&self, size: IntSize, filter: ResizeFilter) -> Option<Pixmap> {
+        self.as_ref().resize(size, filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_are_normalized() {
+        for filter in [
+            ResizeFilter::Box,
+            ResizeFilter::Mitchell,
+            ResizeFilter::CatmullRom,
+            ResizeFilter::Lanczos3,
+        ] {
+            for (src, dst) in [(10, 3), (3, 10), (7, 7), (1, 5), (5, 1)] {
+                for c in contributions(src, dst, filter) {
+                    let sum: f32 = c.weights.iter().sum();
+                    assert!((sum - 1.0).abs() < 0.0001);
+                    assert!(c.start + c.weights.len() <= src as usize);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cubic_kernels() {
+        assert_eq!(cubic(CubicResampler::CATMULL_ROM, 0.0), 1.0);
+        assert_eq!(cubic(CubicResampler::CATMULL_ROM, 1.0), 0.0);
+        assert!((cubic(CubicResampler::MITCHELL, 0.0) - 8.0 / 9.0).abs() < 0.0001);
+        assert_eq!(cubic(CubicResampler::MITCHELL, 2.0), 0.0);
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use tiny_skia_path::NormalizedF32;
+
+use crate::{Color, ColorSpace, Shader, Transform};
+
+use crate::pipeline::{self, RasterPipelineBuilder};
+
+/// A user-defined shader implementation.
+///
+/// Colors are computed for batches of 8 pixels, which matches the width
+/// of the high precision raster pipeline.
+///
+/// Used via [`CustomShader`].
+pub trait ShaderImpl: Send + Sync {
+    /// Computes colors for a batch of pixels.
+    ///
+    /// `x` and `y` are pixel centers in the shader's coordinate space.
+    /// That is, device coordinates mapped by the inverse of the shader and paint transforms.
+    ///
+    /// At the end of a row, a batch may extend past the drawing area.
+    /// Colors of such pixels are ignored.
+    fn shade(&self, x: &[f32; 8], y: &[f32; 8], colors: &mut [Color; 8]);
+
+    /// Checks if the shader is guaranteed to produce only opaque colors.
+    fn is_opaque(&self) -> bool {
+        false
+    }
+}
+
+impl PartialEq for dyn ShaderImpl + '_ {
+    /// Shaders are compared by address.
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(
+            self as *const Self as *const u8,
+            other as *const Self as *const u8,
+        )
+    }
+}
+
+impl core::fmt::Debug for dyn ShaderImpl + '_ {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ShaderImpl").finish_non_exhaustive()
+    }
+}
+
+/// A user-defined shader.
+///
+/// Wraps a [`ShaderImpl`] together with a transform and an opacity,
+/// just like other shaders.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CustomShader<'a> {
+    pub(crate) shader: &'a dyn ShaderImpl,
+    pub(crate) opacity: NormalizedF32,
+    pub(crate) transform: Transform,
+}
+
+impl<'a> CustomShader<'a> {
+    /// Creates a new user-defined shader.
+    ///
+    /// `opacity` will be clamped to the 0..=1 range.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(shader: &'a dyn ShaderImpl, opacity: f32, transform: Transform) -> Shader<'a> {
+        Shader::Custom(CustomShader {
+            shader,
+            opacity: NormalizedF32::new_clamped(opacity),
+            transform,
+        })
+    }
+
+    pub(crate) fn is_opaque(&self) -> bool {
+        self.opacity == NormalizedF32::ONE && self.shader.is_opaque()
+    }
+
+    pub(crate) fn push_stages(&self, cs: ColorSpace, p: &mut RasterPipelineBuilder) -> bool {
+        let ts = match self.transform.invert() {
+            Some(v) => v,
+            None => {
+                log::warn!("failed to invert a custom shader transform. Nothing will be rendered");
+                return false;
+            }
+        };
+
+        p.ctx.custom_shader_opacity = self.opacity.get();
+
+        p.push(pipeline::Stage::SeedShader);
+        p.push_transform(ts);
+        p.push(pipeline::Stage::CustomShader);
+
+        if let Some(stage) = cs.expand_stage() {
+            p.push(stage);
+        }
+
+        p.push(pipeline::Stage::Premultiply);
+        true
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2013 Google Inc.
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+use crate::{Pixmap, PixmapRef, PremultipliedColorU8, Transform};
+
+/// A lazily generated mipmap chain.
+///
+/// Used by patterns with [`FilterQuality::Medium`](crate::FilterQuality::Medium)
+/// to reduce aliasing when an image is drawn at a smaller size.
+///
+/// Levels are generated on first use and then cached, so a `Mipmaps`
+/// can be kept and reused across multiple draw calls/frames via
+/// [`Pattern::new_mipmapped`](crate::Pattern::new_mipmapped).
+/// Without the `std` feature, all levels are generated on creation instead.
+///
+/// Unlike Skia, we're using a simple 2x2 (or 3x3 for odd sizes) box filter
+/// over premultiplied pixels.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Mipmaps<'a> {
+    base: PixmapRef<'a>,
+    // Doesn't include the base level.
+    levels: Vec<LevelCell>,
+}
+
+// `OnceLock` keeps `Mipmaps`, and therefore `Pattern` and `Paint`, `Sync`.
+#[cfg(feature = "std")]
+type LevelCell = std::sync::OnceLock<Pixmap>;
+
+// There is no `Sync` once-cell in `core`, so levels are generated eagerly.
+#[cfg(not(feature = "std"))]
+#[derive(Clone, PartialEq, Debug)]
+struct LevelCell(Pixmap);
+
+#[cfg(not(feature = "std"))]
+impl LevelCell {
+    fn get(&self) -> Option<&Pixmap> {
+        Some(&self.0)
+    }
+
+    fn get_or_init(&self, _: impl FnOnce() -> Pixmap) -> &Pixmap {
+        &self.0
+    }
+}
+
+impl<'a> Mipmaps<'a> {
+    /// Creates a new mipmap chain for the provided pixmap.
+    ///
+    /// Doesn't allocate any level pixels yet, unless the `std` feature is disabled.
+    pub fn new(pixmap: PixmapRef<'a>) -> Self {
+        let count = levels_count(pixmap.size());
+        let mut levels = Vec::with_capacity(count);
+
+        #[cfg(feature = "std")]
+        levels.resize_with(count, LevelCell::new);
+
+        #[cfg(not(feature = "std"))]
+        for _ in 0..count {
+            let level = match levels.last() {
+                Some(LevelCell(parent)) => downsample(parent.as_ref()),
+                None => downsample(pixmap),
+            };
+            levels.push(LevelCell(level));
+        }
+
+        Mipmaps {
+            base: pixmap,
+            levels,
+        }
+    }
+
+    /// Returns the base pixmap.
+    pub fn pixmap(&self) -> PixmapRef<'a> {
+        self.base
+    }
+
+    /// Returns the number of levels, including the base one.
+    pub fn len(&self) -> usize {
+        self.levels.len() + 1
+    }
+
+    /// Checks that there are no levels except the base one.
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Returns a mipmap level, generating it when needed.
+    ///
+    /// Level 0 is the base pixmap. Each next level is two times smaller.
+    ///
+    /// Returns `None` when `index` is out of bounds.
+    pub fn level(&self, index: usize) -> Option<PixmapRef<'_>> {
+        if index == 0 {
+            return Some(self.base);
+        }
+
+        let parent = self.level(index - 1)?;
+        let level = self
+            .levels
+            .get(index - 1)?
+            .get_or_init(|| downsample(parent));
+        Some(level.as_ref())
+    }
+
+    /// Checks if a level was already generated.
+    pub fn is_level_ready(&self, index: usize) -> bool {
+        match index {
+            0 => true,
+            _ => self
+                .levels
+                .get(index - 1)
+                .is_some_and(|level| level.get().is_some()),
+        }
+    }
+}
+
+/// Returns the number of levels after the base one.
+///
+/// The last level is always 1x1.
+fn levels_count(size: IntSize) -> usize {
+    let mut count = 0;
+    let mut w = size.width();
+    let mut h = size.height();
+    while w > 1 || h > 1 {
+        w = (w >> 1).max(1);
+        h = (h >> 1).max(1);
+        count += 1;
+    }
+
+    count
+}
+
+/// Returns the size of a mipmap level.
+pub(crate) fn level_size(size: IntSize, level: usize) -> IntSize {
+    let mut w = size.width();
+    let mut h = size.height();
+    for _ in 0..level {
+        w = (w >> 1).max(1);
+        h = (h >> 1).max(1);
+    }
+
+    IntSize::from_wh(w, h).unwrap()
+}
+
+/// Selects a mipmap level for the inverted pattern transform.
+///
+/// This is `SkMipmap::ComputeLevel` with the nearest level rounding.
+/// Returns 0 when no downscaling is needed.
+pub(crate) fn compute_level(size: IntSize, inv_ts: &Transform) -> usize {
+    // How many source pixels are covered by a single destination one.
+    // Like Skia, we're using the largest one, to prevent aliasing.
+    let (sx, sy) = inv_ts.get_scale();
+    let mut inv_scale = sx.max(sy);
+    if !inv_scale.is_finite() {
+        return 0;
+    }
+
+    // Skia uses `max(log2(1 / scale) - 0.5, 0)` rounded to the nearest integer,
+    // which is the same as `floor(log2(1 / scale))`.
+    let max_level = levels_count(size);
+    let mut level = 0;
+    while inv_scale >= 2.0 && level < max_level {
+        inv_scale *= 0.5;
+        level += 1;
+    }
+
+    level
+}
+
+/// Builds the next mipmap level.
+///
+/// The level is always in the `Rgba8888` format.
+pub(crate) fn downsample(src: PixmapRef) -> Pixmap {
+    let converted;
+    let src = if !src.is_packed_rgba8888() {
+        converted = src.to_rgba8888();
+        converted.as_ref()
+    } else {
+        src
+    };
+
+    let src_w = src.width() as usize;
+    let src_h = src.height() as usize;
+    let size = level_size(src.size(), 1);
+    let dst_w = size.width() as usize;
+    let dst_h = size.height() as usize;
+
+    // Odd dimensions are filtered using a 1-2-1 kernel, so no pixels are skipped.
+    let x_taps: &[u32] = match src_w {
+        1 => &[1],
+        w if w % 2 == 0 => &[1, 1],
+        _ => &[1, 2, 1],
+    };
+    let y_taps: &[u32] = match src_h {
+        1 => &[1],
+        h if h % 2 == 0 => &[1, 1],
+        _ => &[1, 2, 1],
+    };
+    let x_sum: u32 = x_taps.iter().sum();
+    let y_sum: u32 = y_taps.iter().sum();
+    let total = x_sum * y_sum;
+
+    let src_pixels = src.pixels();
+    let mut dst = Pixmap::new(size.width(), size.height()).unwrap();
+    let dst_pixels = dst.pixels_mut();
+    for y in 0..dst_h {
+        for x in 0..dst_w {
+            let mut acc = [0u32; 4];
+            for (j, wy) in y_taps.iter().enumerate() {
+                let sy = (y * 2 + j).min(src_h - 1);
+                for (i, wx) in x_taps.iter().enumerate() {
+                    let sx = (x * 2 + i).min(src_w - 1);
+                    let c = src_pixels[sy * src_w + sx];
+                    let w = wx * wy;
+                    acc[0] += u32::from(c.red()) * w;
+                    acc[1] += u32::from(c.green()) * w;
+                    acc[2] += u32::from(c.blue()) * w;
+                    acc[3] += u32::from(c.alpha()) * w;
+                }
+            }
+
+            // Averaging premultiplied colors cannot produce color > alpha,
+            // but we still have to round all channels the same way.
+            let avg = |v: u32| ((v + total / 2) / total) as u8;
+            dst_pixels[y * dst_w + x] = PremultipliedColorU8::from_rgba_unchecked(
+                avg(acc[0]),
+                avg(acc[1]),
+                avg(acc[2]),
+                avg(acc[3]),
+            );
+        }
+    }
+
+    dst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count() {
+        assert_eq!(levels_count(IntSize::from_wh(1, 1).unwrap()), 0);
+        assert_eq!(levels_count(IntSize::from_wh(2, 1).unwrap()), 1);
+        assert_eq!(levels_count(IntSize::from_wh(100, 10).unwrap()), 6);
+        assert_eq!(levels_count(IntSize::from_wh(256, 256).unwrap()), 8);
+    }
+
+    #[test]
+    fn level_selection() {
+        let size = IntSize::from_wh(256, 256).unwrap();
+        assert_eq!(compute_level(size, &Transform::identity()), 0);
+        assert_eq!(compute_level(size, &Transform::from_scale(0.5, 0.5)), 0);
+        assert_eq!(compute_level(size, &Transform::from_scale(1.9, 1.9)), 0);
+        assert_eq!(compute_level(size, &Transform::from_scale(2.0, 2.0)), 1);
+        assert_eq!(compute_level(size, &Transform::from_scale(4.0, 1.0)), 2);
+        assert_eq!(
+            compute_level(size, &Transform::from_scale(5000.0, 5000.0)),
+            8
+        );
+    }
+
+    #[test]
+    fn odd_size() {
+        let mut pixmap = Pixmap::new(3, 1).unwrap();
+        pixmap.pixels_mut()[1] = PremultipliedColorU8::from_rgba(200, 100, 0, 200).unwrap();
+
+        let level = downsample(pixmap.as_ref());
+        assert_eq!(level.width(), 1);
+        assert_eq!(level.height(), 1);
+        assert_eq!(
+            level.pixel(0, 0).unwrap(),
+            PremultipliedColorU8::from_rgba(100, 50, 0, 100).unwrap()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lazy() {
+        let pixmap = Pixmap::new(8, 4).unwrap();
+        let mipmaps = Mipmaps::new(pixmap.as_ref());
+        assert_eq!(mipmaps.len(), 4);
+        assert!(!mipmaps.is_level_ready(2));
+        assert_eq!(
+            mipmaps.level(2).unwrap().size(),
+            IntSize::from_wh(2, 1).unwrap()
+        );
+        assert!(mipmaps.is_level_ready(1));
+        assert!(mipmaps.is_level_ready(2));
+        assert!(!mipmaps.is_level_ready(3));
+        assert!(mipmaps.level(4).is_none());
+    }
+}
@@ -4,17 +4,23 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
+mod custom;
 mod gradient;
 mod linear_gradient;
+mod mipmap;
 mod pattern;
+mod perlin_noise;
 mod radial_gradient;
 mod sweep_gradient;
 
 use tiny_skia_path::{NormalizedF32, Scalar};
 
+pub use custom::{CustomShader, ShaderImpl};
 pub use gradient::GradientStop;
 pub use linear_gradient::LinearGradient;
-pub use pattern::{FilterQuality, Pattern, PixmapPaint};
+pub use mipmap::Mipmaps;
+pub use pattern::{CubicResampler, FilterQuality, Pattern, PixmapPaint};
+pub use perlin_noise::PerlinNoise;
 pub use radial_gradient::RadialGradient;
 pub use sweep_gradient::SweepGradient;
 
@@ -58,6 +64,12 @@ pub enum Shader<'a> {
     SweepGradient(SweepGradient),
     /// A pattern shader.
     Pattern(Pattern<'a>),
+    /// A fractal noise shader.
+    FractalNoise(PerlinNoise),
+    /// A turbulence shader.
+    Turbulence(PerlinNoise),
+    /// A user-defined shader.
+    Custom(CustomShader<'a>),
 }
 
 impl Shader<'_> {
@@ -71,6 +83,8 @@ impl Shader<'_> {
             Shader::RadialGradient(_) => false,
             Shader::SweepGradient(g) => g.is_opaque(),
             Shader::Pattern(_) => false,
+            Shader::FractalNoise(_) | Shader::Turbulence(_) => false,
+            Shader::Custom(s) => s.is_opaque(),
         }
     }
 
@@ -89,6 +103,9 @@ impl Shader<'_> {
             Shader::RadialGradient(g) => g.push_stages(cs, p),
             Shader::SweepGradient(g) => g.push_stages(cs, p),
             Shader::Pattern(patt) => patt.push_stages(cs, p),
+            Shader::FractalNoise(n) => n.push_stages(true, cs, p),
+            Shader::Turbulence(n) => n.push_stages(false, cs, p),
+            Shader::Custom(c) => c.push_stages(cs, p),
         }
     }
 
@@ -108,6 +125,12 @@ impl Shader<'_> {
             Shader::Pattern(p) => {
                 p.transform = p.transform.post_concat(ts);
             }
+            Shader::FractalNoise(n) | Shader::Turbulence(n) => {
+                n.transform = n.transform.post_concat(ts);
+            }
+            Shader::Custom(c) => {
+                c.transform = c.transform.post_concat(ts);
+            }
         }
     }
 
@@ -124,6 +147,8 @@ impl Shader<'_> {
     /// - For `SolidColor` this function will multiply `color.alpha` by `opacity`.
     /// - For gradients this function will multiply all colors by `opacity`.
     /// - For `Pattern` this function will multiply `Patter::opacity` by `opacity`.
+    /// - For `FractalNoise` and `Turbulence` this function will multiply the alpha channel by `opacity`.
+    /// - For `Custom` this function will multiply the alpha channel by `opacity`.
     pub fn apply_opacity(&mut self, opacity: f32) {
         match self {
             Shader::SolidColor(ref mut c) => {
@@ -141,6 +166,12 @@ impl Shader<'_> {
             Shader::Pattern(ref mut p) => {
                 p.opacity = NormalizedF32::new(p.opacity.get() * opacity.bound(0.0, 1.0)).unwrap();
             }
+            Shader::FractalNoise(ref mut n) | Shader::Turbulence(ref mut n) => {
+                n.opacity = NormalizedF32::new(n.opacity.get() * opacity.bound(0.0, 1.0)).unwrap();
+            }
+            Shader::Custom(ref mut c) => {
+                c.opacity = NormalizedF32::new(c.opacity.get() * opacity.bound(0.0, 1.0)).unwrap();
+            }
         }
     }
 }
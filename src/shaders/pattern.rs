@@ -6,10 +6,11 @@
 
 use tiny_skia_path::NormalizedF32;
 
-use crate::{BlendMode, ColorSpace, PixmapRef, Shader, SpreadMode, Transform};
+use crate::{BlendMode, ColorSpace, PixelFormat, Pixmap, PixmapRef, Shader, SpreadMode, Transform};
 
 use crate::pipeline;
 use crate::pipeline::RasterPipelineBuilder;
+use crate::shaders::mipmap::{self, Mipmaps};
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
 use tiny_skia_path::NoStdFloat;
@@ -21,8 +22,69 @@ pub enum FilterQuality {
     Nearest,
     /// Bilinear.
     Bilinear,
+    /// Bilinear filtering of a mipmap level.
+    ///
+    /// Improves quality when an image is scaled down by more than 2x.
+    /// Behaves like `Bilinear` otherwise.
+    Medium,
     /// Bicubic. High quality, but slow.
+    ///
+    /// Same as `Cubic(CubicResampler::MITCHELL)`.
     Bicubic,
+    /// Bicubic with custom filter parameters.
+    Cubic(CubicResampler),
+}
+
+/// Parameters of a Mitchell-Netravali cubic filter.
+///
+/// Matches Skia's `SkCubicResampler`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CubicResampler {
+    /// The "blurriness" parameter.
+    ///
+    /// Usually in 0..=1 range.
+    pub b: f32,
+    /// The "ringing" parameter.
+    ///
+    /// Usually in 0..=1 range.
+    pub c: f32,
+}
+
+impl CubicResampler {
+    /// The Mitchell-Netravali filter. `B = 1/3, C = 1/3`.
+    ///
+    /// A good balance between blurring and ringing.
+    pub const MITCHELL: Self = CubicResampler {
+        b: 1.0 / 3.0,
+        c: 1.0 / 3.0,
+    };
+
+    /// The Catmull-Rom filter. `B = 0, C = 1/2`.
+    ///
+    /// Sharper than Mitchell, but produces more ringing.
+    pub const CATMULL_ROM: Self = CubicResampler { b: 0.0, c: 0.5 };
+
+    /// Returns polynomial coefficients for the pixels at 0.5 and 1.5 offsets
+    /// from the sample point.
+    ///
+    /// The near one is (1, t, t^2, t^3) and the far one is (t^2, t^3),
+    /// since the rest is always zero.
+    ///
+    /// This is `SkImageShader::CubicResamplerMatrix`, but we need only half of it,
+    /// because the filter is symmetric.
+    pub(crate) fn coefficients(&self) -> ([f32; 4], [f32; 2]) {
+        // Everything is computed in 1/18 units, so Mitchell's coefficients are exact.
+        let b = self.b * 18.0;
+        let c = self.c * 18.0;
+        let near = [
+            b / 6.0 / 18.0,
+            (b / 2.0 + c) / 18.0,
+            (54.0 - b * 2.5 - 2.0 * c) / 18.0,
+            (-36.0 + b * 1.5 + c) / 18.0,
+        ];
+        let far = [-c / 18.0, (b / 6.0 + c) / 18.0];
+        (near, far)
+    }
 }
 
 /// Controls how a pixmap should be blended.
@@ -62,11 +124,12 @@ impl Default for PixmapPaint {
 ///
 /// Essentially a `SkImageShader`.
 ///
-/// Unlike Skia, we do not support FilterQuality::Medium, because it involves
-/// mipmap generation, which adds too much complexity.
+/// Unlike Skia, `FilterQuality::Medium` always uses the nearest mipmap level
+/// and doesn't blend between levels.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Pattern<'a> {
     pub(crate) pixmap: PixmapRef<'a>,
+    mipmaps: Option<&'a Mipmaps<'a>>,
     quality: FilterQuality,
     spread_mode: SpreadMode,
     pub(crate) opacity: NormalizedF32,
@@ -87,6 +150,7 @@ impl<'a> Pattern<'a> {
     ) -> Shader<'a> {
         Shader::Pattern(Pattern {
             pixmap,
+            mipmaps: None,
             spread_mode,
             quality,
             opacity: NormalizedF32::new_clamped(opacity),
@@ -94,6 +158,73 @@ impl<'a> Pattern<'a> {
         })
     }
 
+    /// Creates a new pattern shader using a mipmap chain.
+    ///
+    /// Unlike `Pattern::new` with `FilterQuality::Medium`, which would generate
+    /// a required mipmap level on each draw, generated levels are cached by `mipmaps`
+    /// and can be reused between draw calls.
+    ///
+    /// `opacity` will be clamped to the 0..=1 range.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new_mipmapped(
+        mipmaps: &'a Mipmaps<'a>,
+        spread_mode: SpreadMode,
+        quality: FilterQuality,
+        opacity: f32,
+        transform: Transform,
+    ) -> Shader<'a> {
+        Shader::Pattern(Pattern {
+            pixmap: mipmaps.pixmap(),
+            mipmaps: Some(mipmaps),
+            spread_mode,
+            quality,
+            opacity: NormalizedF32::new_clamped(opacity),
+            transform,
+        })
+    }
+
+    /// Returns a mipmap level index that should be used for rendering.
+    fn mip_level(&self) -> usize {
+        if self.quality != FilterQuality::Medium {
+            return 0;
+        }
+
+        match self.transform.invert() {
+            Some(ts) => mipmap::compute_level(self.pixmap.size(), &ts),
+            None => 0,
+        }
+    }
+
+    /// Returns a pixmap that should be sampled.
+    ///
+    /// When a mipmap level is required, but there is no mipmap cache,
+    /// the level will be generated and returned as an owned `Pixmap`.
+    /// Same for pixmaps that are not in the `Rgba8888` format,
+    /// since we can sample only from RGBA.
+    pub(crate) fn source_pixmap(&self) -> (PixmapRef<'a>, Option<Pixmap>) {
+        let level = self.mip_level();
+        if level == 0 {
+            if self.pixmap.format() != PixelFormat::Rgba8888 {
+                return (self.pixmap, Some(self.pixmap.to_rgba8888()));
+            }
+
+            return (self.pixmap, None);
+        }
+
+        if let Some(mipmaps) = self.mipmaps {
+            if let Some(pixmap) = mipmaps.level(level) {
+                return (pixmap, None);
+            }
+        }
+
+        let mut pixmap = mipmap::downsample(self.pixmap);
+        for _ in 1..level {
+            pixmap = mipmap::downsample(pixmap.as_ref());
+        }
+
+        (self.pixmap, Some(pixmap))
+    }
+
     pub(crate) fn push_stages(&self, cs: ColorSpace, p: &mut RasterPipelineBuilder) -> bool {
         let ts = match self.transform.invert() {
             Some(v) => v,
@@ -105,10 +236,22 @@ impl<'a> Pattern<'a> {
 
         p.push(pipeline::Stage::SeedShader);
 
-        p.push_transform(ts);
-
         let mut quality = self.quality;
 
+        // Use the same level size as `source_pixmap`.
+        let level = self.mip_level();
+        let size = mipmap::level_size(self.pixmap.size(), level);
+        let ts = if level != 0 {
+            ts.post_scale(
+                size.width() as f32 / self.pixmap.width() as f32,
+                size.height() as f32 / self.pixmap.height() as f32,
+            )
+        } else {
+            ts
+        };
+
+        p.push_transform(ts);
+
         if ts.is_identity() || ts.is_translate() {
             quality = FilterQuality::Nearest;
         }
@@ -122,18 +265,16 @@ impl<'a> Pattern<'a> {
             }
         }
 
-        // TODO: minimizing scale via mipmap
-
         match quality {
             FilterQuality::Nearest => {
                 p.ctx.limit_x = pipeline::TileCtx {
-                    scale: self.pixmap.width() as f32,
-                    inv_scale: 1.0 / self.pixmap.width() as f32,
+                    scale: size.width() as f32,
+                    inv_scale: 1.0 / size.width() as f32,
                 };
 
                 p.ctx.limit_y = pipeline::TileCtx {
-                    scale: self.pixmap.height() as f32,
-                    inv_scale: 1.0 / self.pixmap.height() as f32,
+                    scale: size.height() as f32,
+                    inv_scale: 1.0 / size.height() as f32,
                 };
 
                 match self.spread_mode {
@@ -144,19 +285,27 @@ impl<'a> Pattern<'a> {
 
                 p.push(pipeline::Stage::Gather);
             }
-            FilterQuality::Bilinear => {
+            FilterQuality::Bilinear | FilterQuality::Medium => {
                 p.ctx.sampler = pipeline::SamplerCtx {
                     spread_mode: self.spread_mode,
-                    inv_width: 1.0 / self.pixmap.width() as f32,
-                    inv_height: 1.0 / self.pixmap.height() as f32,
+                    inv_width: 1.0 / size.width() as f32,
+                    inv_height: 1.0 / size.height() as f32,
+                    ..pipeline::SamplerCtx::default()
                 };
                 p.push(pipeline::Stage::Bilinear);
             }
-            FilterQuality::Bicubic => {
+            FilterQuality::Bicubic | FilterQuality::Cubic(_) => {
+                let cubic = match quality {
+                    FilterQuality::Cubic(cubic) => cubic,
+                    _ => CubicResampler::MITCHELL,
+                };
+                let (cubic_near, cubic_far) = cubic.coefficients();
                 p.ctx.sampler = pipeline::SamplerCtx {
                     spread_mode: self.spread_mode,
-                    inv_width: 1.0 / self.pixmap.width() as f32,
-                    inv_height: 1.0 / self.pixmap.height() as f32,
+                    inv_width: 1.0 / size.width() as f32,
+                    inv_height: 1.0 / size.height() as f32,
+                    cubic_near,
+                    cubic_far,
                 };
                 p.push(pipeline::Stage::Bicubic);
 
@@ -0,0 +1,302 @@
+// Copyright 2013 Google Inc.
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec;
+
+use tiny_skia_path::{NormalizedF32, Scalar};
+
+use crate::{Color, ColorSpace, IntSize, Point, Shader, Transform};
+
+use crate::pipeline::{self, PerlinNoiseCtx, RasterPipelineBuilder, StitchData};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use tiny_skia_path::NoStdFloat;
+
+const BLOCK_SIZE: usize = 256;
+const MAX_OCTAVES: u32 = 255;
+const RAND_MAXIMUM: i32 = i32::MAX;
+
+/// A Perlin noise shader.
+///
+/// Used by [`Shader::FractalNoise`] and [`Shader::Turbulence`].
+///
+/// Matches Skia's `SkPerlinNoiseShader` and SVG's `feTurbulence`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PerlinNoise {
+    base_frequency_x: f32,
+    base_frequency_y: f32,
+    octaves: u32,
+    seed: f32,
+    stitch_tile: Option<IntSize>,
+    pub(crate) opacity: NormalizedF32,
+    pub(crate) transform: Transform,
+}
+
+impl PerlinNoise {
+    /// Creates a new fractal noise shader.
+    ///
+    /// `base_frequency_x` and `base_frequency_y` are the frequencies in the 0..1 range.
+    /// `octaves` is the number of noise functions to combine.
+    /// When `stitch_tile` is set, frequencies are adjusted to make a seamless tile of this size.
+    ///
+    /// Returns `Shader::SolidColor` when `octaves` is zero.
+    ///
+    /// Returns `None` when:
+    ///
+    /// - frequencies are negative or not finite
+    /// - `octaves` is bigger than 255
+    /// - `seed` is not finite
+    /// - `transform` is not invertible
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new_fractal_noise(
+        base_frequency_x: f32,
+        base_frequency_y: f32,
+        octaves: u32,
+        seed: f32,
+        stitch_tile: Option<IntSize>,
+        transform: Transform,
+    ) -> Option<Shader<'static>> {
+        if octaves == 0 && Self::is_valid(base_frequency_x, base_frequency_y, seed, transform) {
+            // Without octaves, the shader collapses to 0 * noise + 0.5.
+            return Some(Shader::SolidColor(
+                Color::from_rgba(0.5, 0.5, 0.5, 0.5).unwrap(),
+            ));
+        }
+
+        Self::new(
+            base_frequency_x,
+            base_frequency_y,
+            octaves,
+            seed,
+            stitch_tile,
+            transform,
+        )
+        .map(Shader::FractalNoise)
+    }
+
+    /// Creates a new turbulence shader.
+    ///
+    /// Unlike fractal noise, uses the absolute noise values,
+    /// which produces sharper, "billowing" patterns.
+    ///
+    /// Returns `Shader::SolidColor` when `octaves` is zero.
+    ///
+    /// Returns `None` in the same cases as [`PerlinNoise::new_fractal_noise`].
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new_turbulence(
+        base_frequency_x: f32,
+        base_frequency_y: f32,
+        octaves: u32,
+        seed: f32,
+        stitch_tile: Option<IntSize>,
+        transform: Transform,
+    ) -> Option<Shader<'static>> {
+        if octaves == 0 && Self::is_valid(base_frequency_x, base_frequency_y, seed, transform) {
+            return Some(Shader::SolidColor(Color::TRANSPARENT));
+        }
+
+        Self::new(
+            base_frequency_x,
+            base_frequency_y,
+            octaves,
+            seed,
+            stitch_tile,
+            transform,
+        )
+        .map(Shader::Turbulence)
+    }
+
+    fn new(
+        base_frequency_x: f32,
+        base_frequency_y: f32,
+        octaves: u32,
+        seed: f32,
+        stitch_tile: Option<IntSize>,
+        transform: Transform,
+    ) -> Option<Self> {
+        if octaves > MAX_OCTAVES
+            || !Self::is_valid(base_frequency_x, base_frequency_y, seed, transform)
+        {
+            return None;
+        }
+
+        Some(PerlinNoise {
+            base_frequency_x,
+            base_frequency_y,
+            octaves,
+            seed,
+            stitch_tile,
+            opacity: NormalizedF32::ONE,
+            transform,
+        })
+    }
+
+    fn is_valid(base_frequency_x: f32, base_frequency_y: f32, seed: f32, ts: Transform) -> bool {
+        base_frequency_x >= 0.0
+            && base_frequency_x.is_finite()
+            && base_frequency_y >= 0.0
+            && base_frequency_y.is_finite()
+            && seed.is_finite()
+            && ts.invert().is_some()
+    }
+
+    pub(crate) fn push_stages(
+        &self,
+        fractal_noise: bool,
+        cs: ColorSpace,
+        p: &mut RasterPipelineBuilder,
+    ) -> bool {
+        let ts = self.transform;
+
+        // Like Skia, only the scale part of the transform affects the noise frequency,
+        // while the translate part shifts it.
+        let scale_x = (ts.sx * ts.sx + ts.ky * ts.ky).sqrt();
+        let scale_y = (ts.kx * ts.kx + ts.sy * ts.sy).sqrt();
+        let (scale_x, scale_y) = if scale_x.is_finite()
+            && scale_y.is_finite()
+            && !scale_x.is_nearly_zero()
+            && !scale_y.is_nearly_zero()
+        {
+            (scale_x, scale_y)
+        } else {
+            (
+                tiny_skia_path::SCALAR_NEARLY_ZERO,
+                tiny_skia_path::SCALAR_NEARLY_ZERO,
+            )
+        };
+
+        let mut ctx = PerlinNoiseCtx {
+            fractal_noise,
+            octaves: self.octaves,
+            base_frequency_x: self.base_frequency_x / scale_x,
+            base_frequency_y: self.base_frequency_y / scale_y,
+            stitch: None,
+            opacity: self.opacity.get(),
+            lattice_selector: vec![0; BLOCK_SIZE],
+            gradients: vec![Point::zero(); BLOCK_SIZE * 4],
+        };
+        init_painting_data(self.seed, &mut ctx);
+
+        if let Some(tile) = self.stitch_tile {
+            // The tile size in device space.
+            let (w, h) = (tile.width() as f32, tile.height() as f32);
+            let tile_width = round_to_i32(ts.sx * w + ts.kx * h);
+            let tile_height = round_to_i32(ts.ky * w + ts.sy * h);
+            if tile_width > 0 && tile_height > 0 {
+                stitch(tile_width as f32, tile_height as f32, &mut ctx);
+            }
+        }
+
+        p.ctx.perlin_noise = ctx;
+
+        p.push(pipeline::Stage::SeedShader);
+        // Skia samples the noise at the top-left pixel corner, offset by 1,
+        // which originates from WebKit's 1-based coordinates.
+        p.push_transform(Transform::from_translate(0.5 - ts.tx, 0.5 - ts.ty));
+        p.push(pipeline::Stage::PerlinNoise);
+
+        if let Some(stage) = cs.expand_stage() {
+            p.push(stage);
+        }
+
+        p.push(pipeline::Stage::Premultiply);
+        true
+    }
+}
+
+struct Random(i32);
+
+impl Random {
+    fn new(seed: f32) -> Self {
+        // According to the SVG spec, the seed must be truncated, not rounded.
+        let mut seed = seed as i32;
+        if seed <= 0 {
+            seed = -(seed % (RAND_MAXIMUM - 1)) + 1;
+        }
+
+        Random(seed.min(RAND_MAXIMUM - 1))
+    }
+
+    // See https://www.w3.org/TR/SVG11/filters.html#feTurbulenceElement
+    fn next(&mut self) -> i32 {
+        const RAND_AMPLITUDE: i32 = 16807; // 7**5; primitive root of m
+        const RAND_Q: i32 = 127773; // m / a
+        const RAND_R: i32 = 2836; // m % a
+
+        let mut result = RAND_AMPLITUDE * (self.0 % RAND_Q) - RAND_R * (self.0 / RAND_Q);
+        if result <= 0 {
+            result += RAND_MAXIMUM;
+        }
+
+        self.0 = result;
+        result
+    }
+}
+
+fn init_painting_data(seed: f32, ctx: &mut PerlinNoiseCtx) {
+    let mut random = Random::new(seed);
+
+    let mut noise = [[[0i32; 2]; BLOCK_SIZE]; 4];
+    for channel in noise.iter_mut() {
+        for (i, values) in channel.iter_mut().enumerate() {
+            ctx.lattice_selector[i] = i as u8;
+            values[0] = random.next() % (2 * BLOCK_SIZE as i32);
+            values[1] = random.next() % (2 * BLOCK_SIZE as i32);
+        }
+    }
+
+    for i in (1..BLOCK_SIZE).rev() {
+        let k = ctx.lattice_selector[i];
+        let j = (random.next() % BLOCK_SIZE as i32) as usize;
+        ctx.lattice_selector[i] = ctx.lattice_selector[j];
+        ctx.lattice_selector[j] = k;
+    }
+
+    for (channel, channel_noise) in noise.iter().enumerate() {
+        for i in 0..BLOCK_SIZE {
+            let values = channel_noise[ctx.lattice_selector[i] as usize];
+            let mut gradient = Point::from_xy(
+                (values[0] - BLOCK_SIZE as i32) as f32 / BLOCK_SIZE as f32,
+                (values[1] - BLOCK_SIZE as i32) as f32 / BLOCK_SIZE as f32,
+            );
+            if !gradient.normalize() {
+                gradient = Point::zero();
+            }
+
+            ctx.gradients[channel * BLOCK_SIZE + i] = gradient;
+        }
+    }
+}
+
+fn stitch(tile_width: f32, tile_height: f32, ctx: &mut PerlinNoiseCtx) {
+    // When stitching tiled turbulence, the frequencies must be adjusted
+    // so that the tile borders will be continuous.
+    fn adjust_frequency(frequency: f32, tile_size: f32) -> f32 {
+        if frequency == 0.0 {
+            return frequency;
+        }
+
+        let low_frequency = (tile_size * frequency).floor() / tile_size;
+        let high_frequency = (tile_size * frequency).ceil() / tile_size;
+        // `low_frequency` can be zero when `frequency` is very small.
+        if frequency / low_frequency < high_frequency / frequency {
+            low_frequency
+        } else {
+            high_frequency
+        }
+    }
+
+    ctx.base_frequency_x = adjust_frequency(ctx.base_frequency_x, tile_width);
+    ctx.base_frequency_y = adjust_frequency(ctx.base_frequency_y, tile_height);
+    ctx.stitch = Some(StitchData::new(
+        round_to_i32(tile_width * ctx.base_frequency_x),
+        round_to_i32(tile_height * ctx.base_frequency_y),
+    ));
+}
+
+fn round_to_i32(v: f32) -> i32 {
+    (v + 0.5).floor() as i32
+}
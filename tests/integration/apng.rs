@@ -0,0 +1,130 @@
+use tiny_skia::*;
+
+fn frame(color: Color, rect: Rect) -> Pixmap {
+    let mut pixmap = Pixmap::new(50, 40).unwrap();
+    pixmap.fill(Color::WHITE);
+
+    let mut paint = Paint::default();
+    paint.set_color(color);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
+    pixmap
+}
+
+fn frame_sizes(data: &[u8]) -> Vec<(u32, u32, u32, u32)> {
+    let decoder = png::Decoder::new(std::io::Cursor::new(data));
+    let mut reader = decoder.read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+    let mut sizes = Vec::new();
+    while reader.next_frame(&mut buf).is_ok() {
+        let fctl = reader.info().frame_control.unwrap();
+        sizes.push((fctl.x_offset, fctl.y_offset, fctl.width, fctl.height));
+    }
+    sizes
+}
+
+#[test]
+fn round_trip() {
+    let mut apng = Apng::default();
+    apng.plays = 3;
+    apng.frames.push(ApngFrame::new(frame(Color::BLACK, Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap()), 1, 10));
+    apng.frames.push(ApngFrame::new(frame(Color::BLACK, Rect::from_xywh(5.0, 5.0, 10.0, 10.0).unwrap()), 2, 10));
+    apng.frames.push(ApngFrame::new(frame(Color::from_rgba8(0, 0, 255, 128), Rect::from_xywh(20.0, 10.0, 5.0, 20.0).unwrap()), 1, 0));
+    // Identical frames are allowed.
+    apng.frames.push(ApngFrame::new(apng.frames[2].pixmap.clone(), 1, 10));
+
+    let data = apng.encode().unwrap();
+    let decoded = Apng::decode(&data).unwrap();
+    assert_eq!(decoded, apng);
+}
+
+#[test]
+fn round_trip_other_formats() {
+    let frame = |format: PixelFormat, color: Color, rect: Rect| {
+        let mut pixmap = Pixmap::new_with_format(50, 40, format).unwrap();
+        pixmap.fill(Color::WHITE);
+
+        let mut paint = Paint::default();
+        paint.set_color(color);
+        pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
+        pixmap
+    };
+
+    let mut apng = Apng::default();
+    apng.frames.push(ApngFrame::new(frame(PixelFormat::Bgra8888, Color::BLACK, Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap()), 1, 10));
+    apng.frames.push(ApngFrame::new(frame(PixelFormat::Bgra8888, Color::from_rgba8(255, 0, 0, 255), Rect::from_xywh(5.0, 5.0, 10.0, 10.0).unwrap()), 1, 10));
+    apng.frames.push(ApngFrame::new(frame(PixelFormat::Gray8, Color::BLACK, Rect::from_xywh(20.0, 10.0, 5.0, 20.0).unwrap()), 1, 10));
+
+    let data = apng.encode().unwrap();
+    assert_eq!(frame_sizes(&data), &[(0, 0, 50, 40), (0, 0, 15, 15), (5, 5, 20, 25)]);
+
+    let decoded = Apng::decode(&data).unwrap();
+    assert_eq!(decoded.frames.len(), 3);
+    for (a, b) in decoded.frames.iter().zip(&apng.frames) {
+        assert!(a.pixmap.compare(&b.pixmap).unwrap().is_identical());
+    }
+}
+
+#[test]
+fn dirty_rects() {
+    let mut apng = Apng::default();
+    apng.frames.push(ApngFrame::new(frame(Color::BLACK, Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap()), 1, 10));
+    apng.frames.push(ApngFrame::new(frame(Color::BLACK, Rect::from_xywh(5.0, 5.0, 10.0, 10.0).unwrap()), 1, 10));
+    apng.frames.push(ApngFrame::new(apng.frames[1].pixmap.clone(), 1, 10));
+
+    let data = apng.encode().unwrap();
+    assert_eq!(frame_sizes(&data), &[(0, 0, 50, 40), (0, 0, 15, 15), (0, 0, 1, 1)]);
+}
+
+#[test]
+fn dispose_and_blend() {
+    let mut apng = Apng::default();
+
+    let mut first = ApngFrame::new(frame(Color::BLACK, Rect::from_xywh(10.0, 10.0, 10.0, 10.0).unwrap()), 1, 10);
+    first.dispose_op = ApngDisposeOp::Previous;
+    apng.frames.push(first);
+
+    let mut second = ApngFrame::new(frame(Color::BLACK, Rect::from_xywh(12.0, 12.0, 4.0, 4.0).unwrap()), 1, 10);
+    second.dispose_op = ApngDisposeOp::Background;
+    second.blend_op = ApngBlendOp::Over;
+    apng.frames.push(second);
+
+    let mut translucent = frame(Color::BLACK, Rect::from_xywh(0.0, 0.0, 1.0, 1.0).unwrap());
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(255, 0, 0, 100);
+    paint.blend_mode = BlendMode::Source;
+    translucent.fill_rect(Rect::from_xywh(30.0, 5.0, 4.0, 4.0).unwrap(), &paint, Transform::identity(), Clip::None);
+    let mut third = ApngFrame::new(translucent, 1, 10);
+    third.blend_op = ApngBlendOp::Over;
+    third.dispose_op = ApngDisposeOp::Previous;
+    apng.frames.push(third);
+
+    apng.frames.push(ApngFrame::new(frame(Color::BLACK, Rect::from_xywh(0.0, 0.0, 1.0, 1.0).unwrap()), 1, 10));
+
+    let data = apng.encode().unwrap();
+    let decoded = Apng::decode(&data).unwrap();
+    assert_eq!(decoded.frames.len(), 4);
+    for (a, b) in decoded.frames.iter().zip(&apng.frames) {
+        assert_eq!(a.pixmap, b.pixmap);
+        assert_eq!(a.dispose_op, b.dispose_op);
+    }
+
+    // Translucent pixels cannot be blended exactly.
+    assert_eq!(decoded.frames[1].blend_op, ApngBlendOp::Over);
+    assert_eq!(decoded.frames[2].blend_op, ApngBlendOp::Source);
+}
+
+#[test]
+fn decode_static_png() {
+    let apng = Apng::load("tests/images/pngs/rgba.png").unwrap();
+    assert_eq!(apng.frames.len(), 1);
+    assert_eq!(apng.frames[0].pixmap, Pixmap::load_png("tests/images/pngs/rgba.png").unwrap());
+}
+
+#[test]
+fn encode_different_sizes() {
+    let mut apng = Apng::default();
+    apng.frames.push(ApngFrame::new(Pixmap::new(10, 10).unwrap(), 1, 10));
+    apng.frames.push(ApngFrame::new(Pixmap::new(10, 20).unwrap(), 1, 10));
+    assert!(apng.encode().is_err());
+    assert!(Apng::default().encode().is_err());
+}
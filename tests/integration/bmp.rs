@@ -0,0 +1,76 @@
+use tiny_skia::*;
+
+/// Creates a 40 bytes header BMP.
+fn bmp(width: i32, height: i32, bits_per_pixel: u16, pixels: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&(54 + pixels.len() as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&54u32.to_le_bytes());
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&bits_per_pixel.to_le_bytes());
+    data.extend_from_slice(&[0; 24]);
+    data.extend_from_slice(pixels);
+    data
+}
+
+#[test]
+fn decode_24_bit() {
+    // Bottom-up with padded rows.
+    let pixels = [
+        0, 0, 255, 0, 255, 0, 0, 0,
+        255, 0, 0, 255, 255, 255, 0, 0,
+    ];
+    let pixmap = Pixmap::decode_bmp(&bmp(2, 2, 24, &pixels)).unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(0, 0, 255, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(255, 255, 255, 255).premultiply());
+    assert_eq!(pixmap.pixel(0, 1).unwrap(), ColorU8::from_rgba(255, 0, 0, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 1).unwrap(), ColorU8::from_rgba(0, 255, 0, 255).premultiply());
+}
+
+#[test]
+fn decode_32_bit_top_down() {
+    let pixels = [0, 0, 255, 0, 255, 0, 0, 0];
+    let pixmap = Pixmap::decode_bmp(&bmp(1, -2, 32, &pixels)).unwrap();
+    // Zero alpha everywhere means that alpha is unused.
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(255, 0, 0, 255).premultiply());
+    assert_eq!(pixmap.pixel(0, 1).unwrap(), ColorU8::from_rgba(0, 0, 255, 255).premultiply());
+
+    let pixels = [0, 0, 255, 128, 255, 0, 0, 0];
+    let pixmap = Pixmap::decode_bmp(&bmp(1, -2, 32, &pixels)).unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(255, 0, 0, 128).premultiply());
+    assert_eq!(pixmap.pixel(0, 1).unwrap(), ColorU8::from_rgba(0, 0, 255, 0).premultiply());
+}
+
+#[test]
+fn decode_invalid() {
+    assert!(Pixmap::decode_bmp(&[]).is_none());
+    assert!(Pixmap::decode_bmp(&bmp(2, 2, 24, &[0; 12])).is_none());
+    assert!(Pixmap::decode_bmp(&bmp(1, 1, 8, &[0; 4])).is_none());
+    assert!(Pixmap::decode_bmp(&bmp(0, 1, 32, &[0; 4])).is_none());
+    assert!(Pixmap::decode_bmp(&bmp(100000, 100000, 32, &[0; 4])).is_none());
+    assert!(Pixmap::decode_bmp(&bmp(i32::MAX, i32::MAX, 32, &[0; 4])).is_none());
+}
+
+#[test]
+fn round_trip() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgba.png").unwrap();
+    let data = pixmap.encode_bmp().unwrap();
+    assert_eq!(data.len(), 14 + 108 + pixmap.data().len());
+    assert_eq!(Pixmap::decode_bmp(&data).unwrap(), pixmap);
+}
+
+#[test]
+fn mask_round_trip() {
+    let mut mask = Mask::new(20, 20).unwrap();
+    mask.fill_path(&PathBuilder::from_circle(10.0, 10.0, 8.0).unwrap(), FillRule::Winding, true, Transform::identity());
+
+    let data = mask.encode_bmp().unwrap();
+    assert_eq!(Mask::decode_bmp(&data).unwrap(), mask);
+
+    let pixmap = Pixmap::decode_bmp(&data).unwrap();
+    assert_eq!(pixmap.pixel(10, 10).unwrap(), ColorU8::from_rgba(255, 255, 255, 255).premultiply());
+}
@@ -0,0 +1,150 @@
+use tiny_skia::*;
+
+fn paint() -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = false;
+    paint
+}
+
+#[test]
+fn rect() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
+    let clip = Clip::Rect(IntRect::from_xywh(10, 10, 80, 80).unwrap());
+    pixmap.fill_rect(rect, &paint(), Transform::identity(), clip);
+
+    let expected = Pixmap::load_png("tests/images/mask/rect.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn rect_outside() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
+    let clip = Clip::Rect(IntRect::from_xywh(100, 10, 80, 80).unwrap());
+    pixmap.fill_rect(rect, &paint(), Transform::identity(), clip);
+
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0));
+}
+
+#[test]
+fn aa_rect() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
+    let clip = Clip::AntiAliasedRect(Rect::from_xywh(10.5, 10.0, 80.0, 80.5).unwrap());
+    pixmap.fill_rect(rect, &paint(), Transform::identity(), clip);
+
+    let expected = Pixmap::load_png("tests/images/mask/rect-aa.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn pixel_aligned_aa_rect() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
+    let clip = Clip::AntiAliasedRect(Rect::from_xywh(10.0, 10.0, 80.0, 80.0).unwrap());
+    pixmap.fill_rect(rect, &paint(), Transform::identity(), clip);
+
+    let expected = Pixmap::load_png("tests/images/mask/rect.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn rect_same_as_mask() {
+    let clip_rect = IntRect::from_xywh(20, 30, 50, 40).unwrap();
+    let mut mask = Mask::new(100, 100).unwrap();
+    let clip_path = PathBuilder::from_rect(clip_rect.to_rect());
+    mask.fill_path(&clip_path, FillRule::Winding, false, Transform::default());
+    // Paths are clipped by the mask bounds before rasterization, which affects anti-aliasing
+    // near the clip edges. Drawing bounds are conservative, so make them exact.
+    mask.update_bounds();
+    assert_eq!(mask.bounds(), Some(clip_rect));
+
+    let mut paint = paint();
+    paint.anti_alias = true;
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    let stroke = Stroke {
+        width: 6.0,
+        ..Stroke::default()
+    };
+    let hairline = Stroke::default();
+
+    let draw = |clip: Clip| {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), clip);
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::from_scale(0.5, 0.5), clip);
+        pixmap.stroke_path(&path, &paint, &hairline, Transform::identity(), clip);
+        pixmap
+    };
+
+    assert_eq!(draw(Clip::Rect(clip_rect)), draw(Clip::Mask(&mask)));
+}
+
+#[test]
+fn aa_rect_same_as_mask() {
+    let clip_rect = Rect::from_xywh(20.3, 30.5, 50.4, 40.2).unwrap();
+    let mut mask = Mask::new(100, 100).unwrap();
+    let clip_path = PathBuilder::from_rect(clip_rect);
+    mask.fill_path(&clip_path, FillRule::Winding, true, Transform::default());
+
+    let mut paint = paint();
+    paint.anti_alias = true;
+    paint.shader = LinearGradient::new(
+        Point::from_xy(10.0, 10.0),
+        Point::from_xy(90.0, 90.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .unwrap();
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    let hairline = Stroke::default();
+
+    let draw = |clip: Clip| {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let rect = Rect::from_xywh(5.0, 5.0, 30.0, 90.0).unwrap();
+        pixmap.fill_rect(rect, &paint, Transform::identity(), clip);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), clip);
+        pixmap.stroke_path(&path, &paint, &hairline, Transform::identity(), clip);
+        pixmap
+    };
+
+    assert_eq!(draw(Clip::AntiAliasedRect(clip_rect)), draw(Clip::Mask(&mask)));
+}
+
+#[test]
+fn draw_pixmap() {
+    let mut image = Pixmap::new(50, 50).unwrap();
+    image.fill(Color::from_rgba8(50, 127, 150, 200));
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let clip = Clip::Rect(IntRect::from_xywh(30, 30, 40, 40).unwrap());
+    pixmap.draw_pixmap(10, 10, image.as_ref(), &PixmapPaint::default(), Transform::identity(), clip);
+
+    for y in 0..100 {
+        for x in 0..100 {
+            let inside = (30..60).contains(&x) && (30..60).contains(&y);
+            assert_eq!(pixmap.pixel(x, y).unwrap().alpha() != 0, inside);
+        }
+    }
+}
+
+#[test]
+fn tiled() {
+    // Large enough to be rendered in tiles.
+    let mut pixmap = Pixmap::new(10000, 10).unwrap();
+    let path = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 10000.0, 10.0).unwrap());
+    let clip = Clip::Rect(IntRect::from_xywh(8000, 2, 400, 6).unwrap());
+    pixmap.fill_path(&path, &paint(), FillRule::Winding, Transform::identity(), clip);
+
+    for y in 0..10 {
+        for x in (0..10000).step_by(7) {
+            let inside = (8000..8400).contains(&x) && (2..8).contains(&y);
+            assert_eq!(pixmap.pixel(x, y).unwrap().alpha() != 0, inside);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use tiny_skia::*;
+
+fn render(anti_alias: bool, force_hq_pipeline: bool) -> Pixmap {
+    let mut paint = Paint::default();
+    paint.anti_alias = anti_alias;
+    paint.force_hq_pipeline = force_hq_pipeline;
+    paint.shader = LinearGradient::new(
+        Point::from_xy(10.0, 10.0),
+        Point::from_xy(90.0, 90.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+    pixmap
+}
+
+#[test]
+fn identical() {
+    let pixmap = render(true, false);
+    let report = pixmap.compare(&pixmap.clone()).unwrap();
+    assert!(report.is_identical());
+    assert_eq!(report.max_channel_delta, 0);
+    assert_eq!(report.psnr, f32::INFINITY);
+    assert_eq!(report.ssim, 1.0);
+    assert_eq!(report.diff.pixel(0, 0).unwrap(), PremultipliedColorU8::from_rgba(230, 230, 230, 255).unwrap());
+}
+
+#[test]
+fn single_pixel() {
+    let pixmap1 = Pixmap::new(10, 10).unwrap();
+    let mut pixmap2 = pixmap1.clone();
+    pixmap2.pixels_mut()[55] = PremultipliedColorU8::from_rgba(0, 0, 10, 20).unwrap();
+
+    let report = pixmap1.compare(&pixmap2).unwrap();
+    assert!(!report.is_identical());
+    assert_eq!(report.max_channel_delta, 20);
+    assert_eq!(report.different_pixels, 1);
+    // MSE is (10^2 + 20^2) / 400.
+    assert!((report.psnr - 47.16).abs() < 0.01);
+    assert!(report.ssim < 1.0);
+    assert_eq!(report.diff.pixel(5, 5).unwrap().alpha(), 78);
+    assert_eq!(report.diff.pixel(0, 0).unwrap().alpha(), 255);
+}
+
+#[test]
+fn lowp_vs_highp() {
+    let report = render(true, false).compare(&render(true, true)).unwrap();
+    assert!(report.max_channel_delta <= 2);
+    assert!(report.psnr > 40.0);
+    assert!(report.ssim > 0.99);
+}
+
+#[test]
+fn structural_change() {
+    let report = render(true, false).compare(&render(false, false)).unwrap();
+    let report2 = render(true, false).compare(&Pixmap::new(100, 100).unwrap()).unwrap();
+    assert!(report.ssim > report2.ssim);
+    assert!(report.psnr > report2.psnr);
+}
+
+#[test]
+fn different_formats() {
+    let pixmap = render(true, false);
+    let mut bgra = Pixmap::new_with_format(100, 100, PixelFormat::Bgra8888).unwrap();
+    bgra.draw_pixmap(0, 0, pixmap.as_ref(), &PixmapPaint::default(), Transform::identity(), Clip::None);
+    assert!(pixmap.compare(&bgra).unwrap().is_identical());
+}
+
+#[test]
+fn different_sizes() {
+    let pixmap1 = Pixmap::new(10, 10).unwrap();
+    let pixmap2 = Pixmap::new(10, 11).unwrap();
+    assert!(pixmap1.compare(&pixmap2).is_none());
+}
@@ -0,0 +1,102 @@
+use tiny_skia::*;
+
+struct Canvas {
+    clip: IntRect,
+    data: Vec<u8>,
+    // How many times each pixel was reported.
+    hits: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(clip: IntRect) -> Self {
+        let len = (clip.width() * clip.height()) as usize;
+        Canvas { clip, data: vec![0; len], hits: vec![0; len] }
+    }
+}
+
+impl CoverageSink for Canvas {
+    fn span(&mut self, x: i32, y: i32, width: u32, coverage: u8) {
+        assert!(coverage > 0);
+        assert!(IntRect::from_xywh(x, y, width, 1).is_some_and(|r| self.clip.contains(&r)));
+
+        let row = (y - self.clip.y()) as usize * self.clip.width() as usize;
+        let start = row + (x - self.clip.x()) as usize;
+        for i in start..start + width as usize {
+            self.data[i] = coverage;
+            self.hits[i] += 1;
+        }
+    }
+}
+
+fn check(path: &Path, fill_rule: FillRule, anti_alias: bool) {
+    let clip = IntRect::from_xywh(0, 0, 100, 100).unwrap();
+    let mut canvas = Canvas::new(clip);
+    rasterize_path(path, fill_rule, Transform::identity(), clip, anti_alias, &mut canvas);
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(path, fill_rule, anti_alias, Transform::identity());
+
+    assert!(canvas.hits.iter().all(|n| *n <= 1));
+    assert!(canvas.data.iter().any(|v| *v != 0));
+    assert!(canvas.data.iter().zip(mask.data()).all(|(a, b)| a.abs_diff(*b) <= 1));
+}
+
+#[test]
+fn same_as_mask() {
+    let circle = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    check(&circle, FillRule::Winding, true);
+    check(&circle, FillRule::Winding, false);
+
+    let mut pb = PathBuilder::new();
+    pb.push_circle(50.0, 50.0, 40.0);
+    pb.push_circle(50.0, 50.0, 20.0);
+    let rings = pb.finish().unwrap();
+    check(&rings, FillRule::EvenOdd, true);
+    check(&rings, FillRule::EvenOdd, false);
+
+    let rect = PathBuilder::from_rect(Rect::from_xywh(10.0, 20.0, 30.0, 40.0).unwrap());
+    check(&rect, FillRule::Winding, false);
+}
+
+#[test]
+fn clip_with_offset() {
+    let circle = PathBuilder::from_circle(0.0, 0.0, 40.0).unwrap();
+    let clip = IntRect::from_xywh(-50, -20, 60, 40).unwrap();
+    let mut canvas = Canvas::new(clip);
+    rasterize_path(&circle, FillRule::Winding, Transform::identity(), clip, true, &mut canvas);
+
+    // The circle center.
+    assert_eq!(canvas.data[20 * 60 + 50], 255);
+    // Outside of the circle.
+    assert_eq!(canvas.data[0], 0);
+}
+
+#[test]
+fn transform() {
+    let rect = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap());
+    let clip = IntRect::from_xywh(0, 0, 100, 100).unwrap();
+    let mut canvas = Canvas::new(clip);
+    let ts = Transform::from_row(2.0, 0.0, 0.0, 3.0, 20.0, 30.0);
+    rasterize_path(&rect, FillRule::Winding, ts, clip, false, &mut canvas);
+
+    for y in 0..100 {
+        for x in 0..100 {
+            let inside = (20..40).contains(&x) && (30..60).contains(&y);
+            assert_eq!(canvas.data[y * 100 + x] != 0, inside);
+        }
+    }
+}
+
+#[test]
+fn tiled() {
+    // Large enough to be rendered in tiles.
+    let clip = IntRect::from_xywh(0, 0, 10000, 4).unwrap();
+    let rect = PathBuilder::from_rect(Rect::from_xywh(10.0, 1.0, 9980.0, 2.0).unwrap());
+    let mut canvas = Canvas::new(clip);
+    rasterize_path(&rect, FillRule::Winding, Transform::identity(), clip, true, &mut canvas);
+
+    assert!(canvas.hits.iter().all(|n| *n <= 1));
+    for x in (0..10000).step_by(7) {
+        assert_eq!(canvas.data[10000 + x] == 255, (10..9990).contains(&x));
+    }
+}
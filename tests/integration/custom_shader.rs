@@ -0,0 +1,237 @@
+use tiny_skia::*;
+
+struct Checkerboard {
+    size: f32,
+    color1: Color,
+    color2: Color,
+}
+
+impl ShaderImpl for Checkerboard {
+    fn shade(&self, x: &[f32; 8], y: &[f32; 8], colors: &mut [Color; 8]) {
+        for i in 0..8 {
+            let cell = (x[i] / self.size).floor() + (y[i] / self.size).floor();
+            colors[i] = if cell.rem_euclid(2.0) == 0.0 {
+                self.color1
+            } else {
+                self.color2
+            };
+        }
+    }
+
+    fn is_opaque(&self) -> bool {
+        self.color1.is_opaque() && self.color2.is_opaque()
+    }
+}
+
+struct Radial;
+
+impl ShaderImpl for Radial {
+    fn shade(&self, x: &[f32; 8], y: &[f32; 8], colors: &mut [Color; 8]) {
+        for i in 0..8 {
+            let d = ((x[i] - 100.0).powi(2) + (y[i] - 100.0).powi(2)).sqrt() / 100.0;
+            let d = d.min(1.0);
+            colors[i] = Color::from_rgba(d, 0.5, 1.0 - d, 1.0 - d * 0.5).unwrap();
+        }
+    }
+}
+
+#[test]
+fn checkerboard() {
+    let shader = Checkerboard {
+        size: 10.0,
+        color1: Color::from_rgba8(50, 127, 150, 255),
+        color2: Color::from_rgba8(220, 140, 75, 255),
+    };
+
+    let mut paint = Paint::default();
+    paint.shader = CustomShader::new(&shader, 1.0, Transform::identity());
+    assert!(paint.shader.is_opaque());
+
+    // The width is not a multiple of 8 to test the tail handling.
+    let mut pixmap = Pixmap::new(45, 25).unwrap();
+    pixmap.fill_rect(
+        Rect::from_xywh(0.0, 0.0, 45.0, 25.0).unwrap(),
+        &paint,
+        Transform::identity(),
+        Clip::None,
+    );
+
+    let c1 = ColorU8::from_rgba(50, 127, 150, 255).premultiply();
+    let c2 = ColorU8::from_rgba(220, 140, 75, 255).premultiply();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), c1);
+    assert_eq!(pixmap.pixel(9, 9).unwrap(), c1);
+    assert_eq!(pixmap.pixel(10, 0).unwrap(), c2);
+    assert_eq!(pixmap.pixel(0, 10).unwrap(), c2);
+    assert_eq!(pixmap.pixel(10, 10).unwrap(), c1);
+    assert_eq!(pixmap.pixel(44, 24).unwrap(), c1);
+    assert_eq!(pixmap.pixel(44, 0).unwrap(), c1);
+    assert_eq!(pixmap.pixel(39, 0).unwrap(), c2);
+}
+
+#[test]
+fn fill_path() {
+    let shader = Radial;
+
+    let mut paint = Paint::default();
+    paint.shader = CustomShader::new(&shader, 1.0, Transform::identity());
+    paint.anti_alias = true;
+    assert!(!paint.shader.is_opaque());
+
+    let path = PathBuilder::from_circle(100.0, 100.0, 80.0).unwrap();
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+
+    let expected = Pixmap::load_png("tests/images/custom-shader/fill-path.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn transform() {
+    let shader = Checkerboard {
+        size: 5.0,
+        color1: Color::BLACK,
+        color2: Color::WHITE,
+    };
+
+    let mut paint = Paint::default();
+    paint.shader = CustomShader::new(&shader, 1.0, Transform::from_translate(5.0, 0.0));
+
+    // The paint transform is applied to the shader as well.
+    let mut pixmap = Pixmap::new(40, 40).unwrap();
+    pixmap.fill_rect(
+        Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap(),
+        &paint,
+        Transform::from_scale(4.0, 4.0),
+        Clip::None,
+    );
+
+    let black = ColorU8::from_rgba(0, 0, 0, 255).premultiply();
+    let white = ColorU8::from_rgba(255, 255, 255, 255).premultiply();
+    assert_eq!(pixmap.pixel(5, 5).unwrap(), white);
+    assert_eq!(pixmap.pixel(15, 5).unwrap(), white);
+    assert_eq!(pixmap.pixel(25, 5).unwrap(), black);
+    assert_eq!(pixmap.pixel(5, 25).unwrap(), black);
+}
+
+#[test]
+fn opacity() {
+    let shader = Checkerboard {
+        size: 10.0,
+        color1: Color::BLACK,
+        color2: Color::BLACK,
+    };
+
+    let mut paint = Paint::default();
+    paint.shader = CustomShader::new(&shader, 0.5, Transform::identity());
+    assert!(!paint.shader.is_opaque());
+
+    let mut pixmap = Pixmap::new(20, 20).unwrap();
+    pixmap.fill_rect(
+        Rect::from_xywh(0.0, 0.0, 20.0, 10.0).unwrap(),
+        &paint,
+        Transform::identity(),
+        Clip::None,
+    );
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 128);
+
+    // Thin hairlines are faded using the shader opacity.
+    let mut path = PathBuilder::new();
+    path.move_to(0.0, 15.5);
+    path.line_to(20.0, 15.5);
+    let path = path.finish().unwrap();
+
+    paint.shader = CustomShader::new(&shader, 1.0, Transform::identity());
+    let stroke = Stroke {
+        width: 0.5,
+        ..Stroke::default()
+    };
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
+    assert_eq!(pixmap.pixel(5, 15).unwrap().alpha(), 127);
+}
+
+#[test]
+fn tiled() {
+    let shader = Checkerboard {
+        size: 100.0,
+        color1: Color::BLACK,
+        color2: Color::WHITE,
+    };
+
+    let mut paint = Paint::default();
+    paint.shader = CustomShader::new(&shader, 1.0, Transform::identity());
+
+    // Wide pixmaps are rendered in tiles, which must not affect the shader coordinates.
+    let mut pixmap = Pixmap::new(9000, 10).unwrap();
+    pixmap.fill_rect(
+        Rect::from_xywh(0.0, 0.0, 9000.0, 10.0).unwrap(),
+        &paint,
+        Transform::identity(),
+        Clip::None,
+    );
+
+    let black = ColorU8::from_rgba(0, 0, 0, 255).premultiply();
+    let white = ColorU8::from_rgba(255, 255, 255, 255).premultiply();
+    assert_eq!(pixmap.pixel(8150, 0).unwrap(), white);
+    assert_eq!(pixmap.pixel(8200, 0).unwrap(), black);
+    assert_eq!(pixmap.pixel(8999, 9).unwrap(), white);
+}
+
+#[test]
+fn anti_aliased_rect_clip() {
+    let shader = Radial;
+
+    let mut paint = Paint::default();
+    paint.shader = CustomShader::new(&shader, 1.0, Transform::identity());
+    paint.anti_alias = true;
+
+    // The clip is drawn using a temporary region-sized pixmap,
+    // which must not affect the shader coordinates.
+    let clip_rect = Rect::from_xywh(50.5, 50.25, 100.0, 100.5).unwrap();
+    let mut mask = Mask::new(200, 200).unwrap();
+    mask.fill_path(
+        &PathBuilder::from_rect(clip_rect),
+        FillRule::Winding,
+        true,
+        Transform::identity(),
+    );
+
+    let path = PathBuilder::from_circle(100.0, 100.0, 80.0).unwrap();
+    let draw = |clip: Clip| {
+        let mut pixmap = Pixmap::new(200, 200).unwrap();
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), clip);
+        pixmap
+    };
+
+    assert_eq!(draw(Clip::AntiAliasedRect(clip_rect)), draw(Clip::Mask(&mask)));
+}
+
+#[test]
+fn send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CustomShader>();
+}
+
+#[test]
+fn mask() {
+    let shader = Checkerboard {
+        size: 10.0,
+        color1: Color::BLACK,
+        color2: Color::TRANSPARENT,
+    };
+
+    let mut paint = Paint::default();
+    paint.shader = CustomShader::new(&shader, 1.0, Transform::identity());
+
+    let mut mask = Mask::new(20, 20).unwrap();
+    mask.fill_rect_with_paint(
+        Rect::from_xywh(0.0, 0.0, 20.0, 20.0).unwrap(),
+        &paint,
+        Transform::identity(),
+        Clip::None,
+    );
+
+    assert_eq!(mask.data()[0], 255);
+    assert_eq!(mask.data()[10], 0);
+    assert_eq!(mask.data()[10 * 20 + 10], 255);
+}
@@ -0,0 +1,167 @@
+use tiny_skia::*;
+
+fn rect(x: i32, y: i32, w: u32, h: u32) -> IntRect {
+    IntRect::from_xywh(x, y, w, h).unwrap()
+}
+
+/// Checks that all non-transparent pixels are inside the region.
+fn assert_covered(pixmap: &Pixmap, region: &Region) {
+    for y in 0..pixmap.height() {
+        for x in 0..pixmap.width() {
+            if pixmap.pixel(x, y).unwrap().alpha() != 0 {
+                assert!(region.intersects(&rect(x as i32, y as i32, 1, 1)));
+            }
+        }
+    }
+}
+
+#[test]
+fn region_merge() {
+    let mut region = Region::new();
+    assert!(region.is_empty());
+    assert_eq!(region.bounds(), None);
+
+    region.add_rect(rect(10, 10, 20, 20));
+    region.add_rect(rect(15, 15, 5, 5)); // contained
+    region.add_rect(rect(10, 30, 20, 10)); // adjacent
+    assert_eq!(region.rects(), &[rect(10, 10, 20, 30)]);
+
+    region.add_rect(rect(50, 50, 10, 10));
+    assert_eq!(region.rects().len(), 2);
+    assert_eq!(region.bounds(), Some(rect(10, 10, 50, 50)));
+    assert!(region.intersects(&rect(55, 55, 1, 1)));
+    assert!(!region.intersects(&rect(40, 10, 5, 5)));
+
+    // Covers both rects.
+    region.add_rect(rect(0, 0, 100, 100));
+    assert_eq!(region.rects(), &[rect(0, 0, 100, 100)]);
+
+    region.clear();
+    assert!(region.is_empty());
+}
+
+#[test]
+fn region_limit() {
+    let mut region = Region::new();
+    for i in 0..100 {
+        region.add_rect(rect(i * 20, i * 20, 10, 10));
+    }
+
+    assert!(region.rects().len() <= 16);
+    assert_eq!(region.bounds(), Some(rect(0, 0, 1990, 1990)));
+    for i in 0..100 {
+        assert!(region.intersects(&rect(i * 20 + 5, i * 20 + 5, 1, 1)));
+    }
+}
+
+#[test]
+fn disabled_by_default() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut pixmap = pixmap.as_mut();
+    pixmap.fill(Color::WHITE);
+    assert_eq!(pixmap.damage(), None);
+    assert_eq!(pixmap.take_damage(), None);
+}
+
+#[test]
+fn fill_and_stroke() {
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    let mut canvas = pixmap.as_mut();
+    canvas.enable_damage_tracking();
+
+    let paint = Paint::default();
+    let circle = PathBuilder::from_circle(50.0, 50.0, 30.0).unwrap();
+    canvas.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(120.0, 150.0);
+    pb.line_to(180.0, 190.0);
+    let line = pb.finish().unwrap();
+    canvas.stroke_path(&line, &paint, &Stroke::default(), Transform::identity(), Clip::None);
+
+    let stroke = Stroke { width: 10.0, ..Stroke::default() };
+    canvas.stroke_path(&line, &paint, &stroke, Transform::from_translate(-100.0, 0.0), Clip::None);
+
+    let damage = canvas.take_damage().unwrap();
+    assert_eq!(damage.rects().len(), 3);
+    assert!(!damage.intersects(&rect(100, 0, 100, 100)));
+    assert_covered(&pixmap, &damage);
+}
+
+#[test]
+fn clipped() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = pixmap.as_mut();
+    canvas.enable_damage_tracking();
+
+    let paint = Paint::default();
+    let circle = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+
+    let clip = Clip::Rect(rect(20, 30, 10, 10));
+    canvas.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), clip);
+    assert_eq!(canvas.take_damage().unwrap().rects(), &[rect(20, 30, 10, 10)]);
+
+    let clip = Clip::AntiAliasedRect(Rect::from_xywh(20.5, 30.5, 10.0, 10.0).unwrap());
+    canvas.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), clip);
+    assert_eq!(canvas.take_damage().unwrap().rects(), &[rect(20, 30, 11, 11)]);
+
+    // Fully clipped-out.
+    let clip = Clip::Rect(rect(200, 30, 10, 10));
+    canvas.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), clip);
+    assert!(canvas.damage().unwrap().is_empty());
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(
+        &PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 30.0, 30.0).unwrap()),
+        FillRule::Winding,
+        false,
+        Transform::identity(),
+    );
+    canvas.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), Clip::Mask(&mask));
+    let bounds = canvas.take_damage().unwrap().bounds().unwrap();
+    assert!(rect(0, 0, 31, 31).contains(&bounds));
+    assert!(bounds.contains(&rect(10, 10, 20, 20)));
+
+    canvas.apply_mask(&mask);
+    assert_eq!(canvas.take_damage().unwrap().rects(), &[rect(0, 0, 100, 100)]);
+}
+
+#[test]
+fn draw_pixmap() {
+    let mut image = Pixmap::new(20, 20).unwrap();
+    image.fill(Color::BLACK);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = pixmap.as_mut();
+    canvas.enable_damage_tracking();
+    canvas.draw_pixmap(10, 20, image.as_ref(), &PixmapPaint::default(), Transform::identity(), Clip::None);
+    canvas.draw_pixmap(90, 90, image.as_ref(), &PixmapPaint::default(), Transform::identity(), Clip::None);
+
+    let damage = canvas.take_damage().unwrap();
+    assert_eq!(damage.rects(), &[rect(10, 20, 20, 20), rect(90, 90, 10, 10)]);
+    assert_covered(&pixmap, &damage);
+}
+
+#[test]
+fn pixmap() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill(Color::WHITE);
+    assert_eq!(pixmap.damage(), None);
+
+    pixmap.enable_damage_tracking();
+    assert!(pixmap.damage().unwrap().is_empty());
+
+    let paint = Paint::default();
+    pixmap.fill_rect(Rect::from_xywh(10.0, 20.0, 30.0, 40.0).unwrap(), &paint, Transform::identity(), Clip::None);
+    assert_eq!(pixmap.take_damage().unwrap().rects(), &[rect(10, 20, 30, 40)]);
+
+    // `as_mut` shares the damage state.
+    pixmap.as_mut().fill(Color::BLACK);
+    assert_eq!(pixmap.take_damage().unwrap().rects(), &[rect(0, 0, 100, 100)]);
+
+    // Damage is not a part of the content.
+    let mut other = pixmap.clone();
+    other.take_damage();
+    other.fill(Color::BLACK);
+    assert_eq!(pixmap, other);
+}
@@ -16,7 +16,7 @@ fn line() {
     stroke.width = 2.0;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/dash/line.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -38,7 +38,7 @@ fn quad() {
     stroke.width = 2.0;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/dash/quad.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -60,7 +60,7 @@ fn cubic() {
     stroke.width = 2.0;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/dash/cubic.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -82,7 +82,7 @@ fn hairline() {
     stroke.width = 0.5;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/dash/hairline.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -110,7 +110,7 @@ fn complex() {
     stroke.width = 2.0;
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/dash/complex.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -139,7 +139,7 @@ fn multi_subpaths() {
     stroke.width = 2.0;
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/dash/multi_subpaths.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -163,8 +163,120 @@ fn closed() {
     stroke.width = 2.0;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/dash/closed.png").unwrap();
     assert_eq!(pixmap, expected);
 }
+
+#[test]
+fn fit_to_length() {
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_xywh(10.0, 10.0, 80.0, 50.0).unwrap());
+    pb.push_circle(150.0, 50.0, 37.0);
+    pb.move_to(10.0, 100.0);
+    pb.line_to(100.0, 100.0);
+    pb.line_to(100.0, 190.0);
+    pb.move_to(120.0, 190.0);
+    pb.quad_to(150.0, 80.0, 180.0, 190.0);
+    let path = pb.finish().unwrap();
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let mut dash = StrokeDash::new(vec![12.0, 8.0], 3.0).unwrap();
+    dash.set_fit_to_length(true);
+
+    let mut stroke = Stroke::default();
+    stroke.dash = Some(dash);
+    stroke.width = 4.0;
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
+
+    let expected = Pixmap::load_png("tests/images/dash/fit-to-length.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn zero_length_dashes() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 20.0);
+    pb.line_to(90.0, 20.0);
+    pb.move_to(10.0, 50.0);
+    pb.line_to(90.0, 50.0);
+    pb.push_rect(Rect::from_xywh(15.0, 65.0, 70.0, 25.0).unwrap());
+    let path = pb.finish().unwrap();
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let mut stroke = Stroke::default();
+    stroke.dash = StrokeDash::new(vec![0.0, 10.0], 0.0);
+    stroke.width = 6.0;
+    stroke.line_cap = LineCap::Round;
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
+
+    let expected = Pixmap::load_png("tests/images/dash/zero-length.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn zero_length_dashes_fit_to_length() {
+    let path = PathBuilder::from_rect(Rect::from_xywh(10.0, 10.0, 80.0, 47.0).unwrap());
+
+    let mut dash = StrokeDash::new(vec![0.0, 10.0], 0.0).unwrap();
+    dash.set_fit_to_length(true);
+
+    let dashed = path.dash(&dash, 1.0).unwrap();
+    let dots: Vec<_> = dashed
+        .segments()
+        .filter_map(|s| match s {
+            PathSegment::MoveTo(p) => Some(p),
+            _ => None,
+        })
+        .collect();
+
+    // A dot at each corner and no duplicates.
+    assert_eq!(dots.len(), 2 * 8 + 2 * 5);
+    for corner in [(10.0, 10.0), (90.0, 10.0), (90.0, 57.0), (10.0, 57.0)] {
+        let count = dots
+            .iter()
+            .filter(|p| (p.x - corner.0).abs() < 0.01 && (p.y - corner.1).abs() < 0.01)
+            .count();
+        assert_eq!(count, 1);
+    }
+}
+
+#[test]
+fn continuous_phase() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(15.0, 0.0);
+    pb.move_to(0.0, 10.0);
+    pb.line_to(15.0, 10.0);
+    let path = pb.finish().unwrap();
+
+    let mut dash = StrokeDash::new(vec![10.0, 10.0], 0.0).unwrap();
+    let dashed = path.dash(&dash, 1.0).unwrap();
+    assert_eq!(dashed.bounds(), Rect::from_ltrb(0.0, 0.0, 10.0, 10.0).unwrap());
+
+    // The second contour starts within a gap and ends with a 5 units long dash.
+    dash.set_phase_reset(false);
+    let dashed = path.dash(&dash, 1.0).unwrap();
+    assert_eq!(dashed.bounds(), Rect::from_ltrb(0.0, 0.0, 15.0, 10.0).unwrap());
+    let points: Vec<_> = dashed.points().to_vec();
+    assert_eq!(
+        points,
+        [
+            Point::from_xy(0.0, 0.0),
+            Point::from_xy(10.0, 0.0),
+            Point::from_xy(5.0, 10.0),
+            Point::from_xy(15.0, 10.0),
+        ]
+    );
+}
@@ -12,7 +12,7 @@ fn horizontal_line() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/empty.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -30,7 +30,7 @@ fn vertical_line() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/empty.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -48,7 +48,7 @@ fn single_line() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/empty.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -63,7 +63,7 @@ fn int_rect() {
     let rect = Rect::from_xywh(10.0, 15.0, 80.0, 70.0).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/int-rect.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -78,7 +78,7 @@ fn float_rect() {
     let rect = Rect::from_xywh(10.3, 15.4, 80.5, 70.6).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/float-rect.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -93,7 +93,7 @@ fn int_rect_aa() {
     let rect = Rect::from_xywh(10.0, 15.0, 80.0, 70.0).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/int-rect-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -108,7 +108,7 @@ fn float_rect_aa() {
     let rect = Rect::from_xywh(10.3, 15.4, 80.5, 70.6).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/float-rect-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -124,7 +124,7 @@ fn float_rect_aa_highp() {
     let rect = Rect::from_xywh(10.3, 15.4, 80.5, 70.6).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/float-rect-aa-highp.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -138,7 +138,7 @@ fn tiny_float_rect() {
 
     let rect = Rect::from_xywh(1.3, 1.4, 0.5, 0.6).unwrap();
     let mut pixmap = Pixmap::new(3, 3).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     assert_eq!(
         pixmap.pixels(),
@@ -167,7 +167,7 @@ fn tiny_float_rect_aa() {
     let rect = Rect::from_xywh(1.3, 1.4, 0.5, 0.6).unwrap();
 
     let mut pixmap = Pixmap::new(3, 3).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     assert_eq!(
         pixmap.pixels(),
@@ -194,7 +194,7 @@ fn tiny_rect_aa() {
     paint.anti_alias = true;
     let rect = Rect::from_xywh(0.7, 0.0, 1.0, 2.0).unwrap();
     let mut pixmap = Pixmap::new(10, 10).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 }
 
 #[test]
@@ -206,7 +206,7 @@ fn float_rect_clip_top_left_aa() {
     let rect = Rect::from_xywh(-10.3, -20.4, 100.5, 70.2).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/float-rect-clip-top-left-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -221,7 +221,7 @@ fn float_rect_clip_top_right_aa() {
     let rect = Rect::from_xywh(60.3, -20.4, 100.5, 70.2).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/float-rect-clip-top-right-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -236,7 +236,7 @@ fn float_rect_clip_bottom_right_aa() {
     let rect = Rect::from_xywh(60.3, 40.4, 100.5, 70.2).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/float-rect-clip-bottom-right-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -251,7 +251,7 @@ fn int_rect_with_ts_clip_right() {
     let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::from_row(1.0, 0.0, 0.0, 1.0, 0.5, 0.5), None);
+    pixmap.fill_rect(rect, &paint, Transform::from_row(1.0, 0.0, 0.0, 1.0, 0.5, 0.5), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/int-rect-with-ts-clip-right.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -272,7 +272,7 @@ fn open_polygon() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/polygon.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -295,7 +295,7 @@ fn closed_polygon() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/polygon.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -316,7 +316,7 @@ fn winding_star() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/winding-star.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -337,7 +337,7 @@ fn even_odd_star() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/even-odd-star.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -355,7 +355,7 @@ fn quad_curve() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/quad.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -373,7 +373,7 @@ fn cubic_curve() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/cubic.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -388,7 +388,7 @@ fn memset2d() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 90.0, 90.0).unwrap());
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/memset2d.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -404,7 +404,7 @@ fn memset2d_out_of_bounds() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(50.0, 50.0, 120.0, 120.0).unwrap());
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/memset2d-2.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -427,7 +427,7 @@ fn fill_aa() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::EvenOdd, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/star-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -446,7 +446,7 @@ fn overflow_in_walk_edges_1() {
 
     // Must not panic.
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 }
 
 #[test]
@@ -464,7 +464,7 @@ fn clip_line_1() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/clip-line-1.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -486,7 +486,7 @@ fn clip_line_2() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/clip-line-2.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -504,7 +504,7 @@ fn clip_quad() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/clip-quad.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -523,7 +523,7 @@ fn clip_cubic_1() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/clip-cubic-1.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -542,7 +542,7 @@ fn clip_cubic_2() {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/clip-cubic-2.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -561,7 +561,7 @@ fn aa_endless_loop() {
 
     // Must not loop.
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 }
 
 #[test]
@@ -578,7 +578,7 @@ fn clear_aa() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     let expected = Pixmap::load_png("tests/images/fill/clear-aa.png").unwrap();
@@ -598,7 +598,7 @@ fn line_curve() {
     };
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     // Must not panic.
 }
@@ -621,7 +621,7 @@ fn vertical_lines_merging_bug() {
 
     // Must not panic.
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::from_row(5.4, 0.0, 0.0, 5.4, -4050.0, -840.0), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::from_row(5.4, 0.0, 0.0, 5.4, -4050.0, -840.0), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/fill/vertical-lines-merging-bug.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -638,7 +638,7 @@ fn fill_rect() {
         Rect::from_xywh(20.3, 10.4, 50.5, 30.2).unwrap(),
         &paint,
         Transform::from_row(1.2, 0.3, -0.7, 0.8, 12.0, 15.3),
-        None,
+        Clip::None,
     );
 
     let expected = Pixmap::load_png("tests/images/canvas/fill-rect.png").unwrap();
@@ -55,15 +55,15 @@ fn gamma() {
 
         paint.colorspace = *color;
         paint.shader = solid.clone();
-        pixmap.stroke_path(&path, &paint, &stroke, xf, None);
+        pixmap.stroke_path(&path, &paint, &stroke, xf, Clip::None);
 
         let xf = Transform::from_translate(0.0, 20.0 + 10.0 * i as f32);
         paint.shader = grad2.clone();
-        pixmap.stroke_path(&path, &paint, &wide, xf, None);
+        pixmap.stroke_path(&path, &paint, &wide, xf, Clip::None);
 
         let xf = Transform::from_translate(0.0, 22.5 + 10.0 * i as f32);
         paint.shader = grad3.clone();
-        pixmap.stroke_path(&path, &paint, &wide, xf, None);
+        pixmap.stroke_path(&path, &paint, &wide, xf, Clip::None);
     }
 
     // pixmap.save_png("tests/images/gamma.png").unwrap();
@@ -0,0 +1,198 @@
+use tiny_skia::*;
+
+fn pt(x: f32, y: f32) -> Point {
+    Point::from_xy(x, y)
+}
+
+fn assert_near(a: Point, b: Point) {
+    assert!(a.distance(b) < 0.01, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn eval_and_split() {
+    let curve = Curve::Cubic(pt(0.0, 0.0), pt(0.0, 100.0), pt(100.0, 100.0), pt(100.0, 0.0));
+    assert_eq!(curve.eval(0.0), pt(0.0, 0.0));
+    assert_eq!(curve.eval(1.0), pt(100.0, 0.0));
+    assert_near(curve.eval(0.5), pt(50.0, 75.0));
+    // Out of range `t` is clamped.
+    assert_eq!(curve.eval(2.0), pt(100.0, 0.0));
+
+    let (left, right) = curve.split(0.25);
+    assert_eq!(left.start(), curve.start());
+    assert_eq!(right.end(), curve.end());
+    assert_near(left.end(), curve.eval(0.25));
+    assert_near(left.eval(0.5), curve.eval(0.125));
+    assert_near(right.eval(0.5), curve.eval(0.625));
+}
+
+#[test]
+fn bounds() {
+    let curve = Curve::Quad(pt(0.0, 0.0), pt(50.0, 100.0), pt(100.0, 0.0));
+    assert_eq!(curve.bounds().unwrap(), Rect::from_ltrb(0.0, 0.0, 100.0, 50.0).unwrap());
+
+    let curve = Curve::Cubic(pt(0.0, 0.0), pt(0.0, 100.0), pt(100.0, 100.0), pt(100.0, 0.0));
+    assert_eq!(curve.bounds().unwrap(), Rect::from_ltrb(0.0, 0.0, 100.0, 75.0).unwrap());
+
+    let curve = Curve::Line(pt(10.0, 20.0), pt(f32::NAN, 0.0));
+    assert!(curve.bounds().is_none());
+}
+
+#[test]
+fn nearest_point_line() {
+    let curve = Curve::Line(pt(0.0, 0.0), pt(100.0, 0.0));
+
+    let nearest = curve.nearest_point(pt(25.0, 10.0));
+    assert_eq!(nearest.t, 0.25);
+    assert_eq!(nearest.point, pt(25.0, 0.0));
+    assert_eq!(nearest.distance, 10.0);
+
+    let nearest = curve.nearest_point(pt(-10.0, 0.0));
+    assert_eq!(nearest.t, 0.0);
+    assert_eq!(nearest.distance, 10.0);
+}
+
+#[test]
+fn nearest_point_quad() {
+    let curve = Curve::Quad(pt(0.0, 0.0), pt(50.0, 100.0), pt(100.0, 0.0));
+
+    let nearest = curve.nearest_point(pt(50.0, 80.0));
+    assert!((nearest.t - 0.5).abs() < 1e-4);
+    assert_near(nearest.point, pt(50.0, 50.0));
+    assert!((nearest.distance - 30.0).abs() < 0.01);
+
+    let nearest = curve.nearest_point(pt(110.0, -10.0));
+    assert_eq!(nearest.t, 1.0);
+}
+
+#[test]
+fn nearest_point_cubic() {
+    let curve = Curve::Cubic(pt(0.0, 0.0), pt(0.0, 100.0), pt(100.0, 100.0), pt(100.0, 0.0));
+
+    let nearest = curve.nearest_point(pt(50.0, 100.0));
+    assert!((nearest.t - 0.5).abs() < 1e-3);
+    assert_near(nearest.point, pt(50.0, 75.0));
+    assert!((nearest.distance - 25.0).abs() < 0.01);
+
+    // The result must be the nearest point among dense samples.
+    let p = pt(30.0, 20.0);
+    let nearest = curve.nearest_point(p);
+    for i in 0..=1000 {
+        let sample = curve.eval(i as f32 / 1000.0);
+        assert!(nearest.distance <= sample.distance(p) + 1e-3);
+    }
+}
+
+#[test]
+fn line_line_intersections() {
+    let line1 = Curve::Line(pt(0.0, 0.0), pt(100.0, 100.0));
+    let line2 = Curve::Line(pt(0.0, 100.0), pt(100.0, 0.0));
+    let result = line1.intersections(&line2);
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].t1, 0.5);
+    assert_eq!(result[0].t2, 0.5);
+    assert_eq!(result[0].point, pt(50.0, 50.0));
+
+    let line2 = Curve::Line(pt(0.0, 10.0), pt(100.0, 110.0));
+    assert!(line1.intersections(&line2).is_empty());
+
+    let line2 = Curve::Line(pt(200.0, 0.0), pt(100.0, 100.0));
+    assert!(line1.intersections(&Curve::Line(pt(200.0, 0.0), pt(150.0, 50.0))).is_empty());
+    assert_eq!(line1.intersections(&line2).len(), 1);
+}
+
+#[test]
+fn collinear_lines_intersections() {
+    let line1 = Curve::Line(pt(0.0, 0.0), pt(100.0, 0.0));
+    let line2 = Curve::Line(pt(50.0, 0.0), pt(150.0, 0.0));
+    let result = line1.intersections(&line2);
+    assert_eq!(result.len(), 2);
+    assert_eq!((result[0].t1, result[0].t2), (0.5, 0.0));
+    assert_eq!((result[1].t1, result[1].t2), (1.0, 0.5));
+}
+
+#[test]
+fn line_curve_intersections() {
+    let quad = Curve::Quad(pt(0.0, 0.0), pt(50.0, 100.0), pt(100.0, 0.0));
+    let line = Curve::Line(pt(0.0, 25.0), pt(100.0, 25.0));
+
+    let result = line.intersections(&quad);
+    assert_eq!(result.len(), 2);
+    for r in &result {
+        assert_near(r.point, pt(r.t1 * 100.0, 25.0));
+        assert_near(r.point, quad.eval(r.t2));
+    }
+
+    // Parameters are swapped when the line is the second curve.
+    let swapped = quad.intersections(&line);
+    assert_eq!(swapped.len(), 2);
+    assert_near(quad.eval(swapped[0].t1), line.eval(swapped[0].t2));
+
+    let cubic = Curve::Cubic(pt(0.0, 0.0), pt(0.0, 100.0), pt(100.0, -100.0), pt(100.0, 0.0));
+    let line = Curve::Line(pt(0.0, 0.0), pt(100.0, 0.0));
+    let result = cubic.intersections(&line);
+    assert_eq!(result.len(), 3);
+    assert!((result[1].t1 - 0.5).abs() < 1e-4);
+    assert_near(result[1].point, pt(50.0, 0.0));
+}
+
+#[test]
+fn curve_curve_intersections() {
+    let quad1 = Curve::Quad(pt(0.0, 0.0), pt(50.0, 100.0), pt(100.0, 0.0));
+    let quad2 = Curve::Quad(pt(0.0, 50.0), pt(50.0, -50.0), pt(100.0, 50.0));
+    let result = quad1.intersections(&quad2);
+    assert_eq!(result.len(), 2);
+    for r in &result {
+        assert_near(quad1.eval(r.t1), quad2.eval(r.t2));
+        assert_near(r.point, quad1.eval(r.t1));
+    }
+    assert!(result[0].t1 < result[1].t1);
+
+    let cubic = Curve::Cubic(pt(0.0, 100.0), pt(30.0, -100.0), pt(70.0, 200.0), pt(100.0, 0.0));
+    let result = quad1.intersections(&cubic);
+    assert!(!result.is_empty());
+    for r in &result {
+        assert_near(quad1.eval(r.t1), cubic.eval(r.t2));
+    }
+
+    let far = Curve::Quad(pt(200.0, 0.0), pt(250.0, 100.0), pt(300.0, 0.0));
+    assert!(quad1.intersections(&far).is_empty());
+}
+
+#[test]
+fn path_curves() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(100.0, 0.0);
+    pb.quad_to(100.0, 100.0, 0.0, 100.0);
+    pb.close();
+    pb.move_to(200.0, 0.0);
+    pb.line_to(300.0, 0.0);
+    pb.line_to(200.0, 0.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    assert_eq!(
+        path.curves().collect::<Vec<_>>(),
+        &[
+            Curve::Line(pt(0.0, 0.0), pt(100.0, 0.0)),
+            Curve::Quad(pt(100.0, 0.0), pt(100.0, 100.0), pt(0.0, 100.0)),
+            Curve::Line(pt(0.0, 100.0), pt(0.0, 0.0)),
+            Curve::Line(pt(200.0, 0.0), pt(300.0, 0.0)),
+            Curve::Line(pt(300.0, 0.0), pt(200.0, 0.0)),
+        ]
+    );
+}
+
+#[test]
+fn path_nearest_point() {
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 90.0, 90.0).unwrap());
+
+    let (index, nearest) = path.nearest_point(pt(95.0, 50.0)).unwrap();
+    assert_eq!(index, 1);
+    assert_eq!(nearest.point, pt(90.0, 50.0));
+    assert_eq!(nearest.distance, 5.0);
+
+    let (index, nearest) = path.nearest_point(pt(20.0, 50.0)).unwrap();
+    assert_eq!(index, 3);
+    assert_eq!(nearest.point, pt(10.0, 50.0));
+}
@@ -18,7 +18,7 @@ fn two_stops_linear_pad_lq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/two-stops-linear-pad-lq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -42,7 +42,7 @@ fn two_stops_linear_repeat_lq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/two-stops-linear-repeat-lq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -66,7 +66,7 @@ fn two_stops_linear_reflect_lq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/two-stops-linear-reflect-lq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -92,7 +92,7 @@ fn three_stops_evenly_spaced_lq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/three-stops-evenly-spaced-lq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -117,7 +117,7 @@ fn two_stops_unevenly_spaced_lq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/two-stops-unevenly-spaced-lq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -142,7 +142,7 @@ fn two_stops_linear_pad_hq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/two-stops-linear-pad-hq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -167,7 +167,7 @@ fn two_stops_linear_repeat_hq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/two-stops-linear-repeat-hq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -192,7 +192,7 @@ fn two_stops_linear_reflect_hq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/two-stops-linear-reflect-hq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -219,7 +219,7 @@ fn three_stops_evenly_spaced_hq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/three-stops-evenly-spaced-hq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -245,7 +245,7 @@ fn two_stops_unevenly_spaced_hq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/two-stops-unevenly-spaced-hq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -274,7 +274,7 @@ fn well_behaved_radial() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/well-behaved-radial.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -300,7 +300,7 @@ fn focal_on_circle_radial() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/focal-on-circle-radial.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -326,7 +326,7 @@ fn conical_greater_radial() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/conical-greater-radial.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -352,7 +352,7 @@ fn simple_radial_lq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/simple-radial-lq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -379,7 +379,7 @@ fn simple_radial_hq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/simple-radial-hq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -406,7 +406,7 @@ fn simple_radial_with_ts_hq() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/simple-radial-with-ts-hq.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -435,7 +435,7 @@ fn global_opacity() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/global-opacity.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -462,7 +462,7 @@ fn strip_gradient() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/strip-gradient.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -489,7 +489,7 @@ fn concentric_radial() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/concentric-radial.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -520,7 +520,7 @@ fn conical_smaller_radial() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/gradients/conical-smaller-radial.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -551,7 +551,7 @@ fn sweep_gradient() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     let expected = Pixmap::load_png("tests/images/gradients/sweep-gradient.png").unwrap();
@@ -583,7 +583,7 @@ fn sweep_gradient_full() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     let expected = Pixmap::load_png("tests/images/gradients/sweep-gradient-full.png").unwrap();
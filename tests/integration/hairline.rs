@@ -15,7 +15,7 @@ fn draw_line(x0: f32, y0: f32, x1: f32, y1: f32, anti_alias: bool, width: f32, l
     let mut stroke = Stroke::default();
     stroke.width = width;
     stroke.line_cap = line_cap;
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     pixmap
 }
@@ -95,7 +95,7 @@ fn clip_line_00_v2() {
     builder.move_to(369.26462, 577.8069);
     builder.line_to(488.0846, 471.04388);
     let path = builder.finish().unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/hairline/clip-line-00-v2.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -140,7 +140,7 @@ fn draw_quad(anti_alias: bool, width: f32, line_cap: LineCap) -> Pixmap {
     let mut stroke = Stroke::default();
     stroke.width = width;
     stroke.line_cap = line_cap;
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     pixmap
 }
@@ -178,7 +178,7 @@ fn draw_cubic(points: &[f32; 8], anti_alias: bool, width: f32, line_cap: LineCap
     let mut stroke = Stroke::default();
     stroke.width = width;
     stroke.line_cap = line_cap;
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     pixmap
 }
@@ -250,7 +250,7 @@ fn clipped_circle_aa() {
     stroke.width = 0.5;
 
     let path = PathBuilder::from_circle(50.0, 50.0, 55.0).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/hairline/clipped-circle-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -1,12 +1,28 @@
+#[rustfmt::skip] mod apng;
+#[cfg(feature = "bmp-format")]
+#[rustfmt::skip] mod bmp;
 #[rustfmt::skip] mod mask;
+#[rustfmt::skip] mod clip;
+#[rustfmt::skip] mod compare;
+#[rustfmt::skip] mod coverage;
+#[rustfmt::skip] mod custom_shader;
+#[rustfmt::skip] mod damage;
 #[rustfmt::skip] mod dash;
 #[rustfmt::skip] mod fill;
 #[rustfmt::skip] mod gamma;
+#[rustfmt::skip] mod geometry;
 #[rustfmt::skip] mod gradients;
 #[rustfmt::skip] mod hairline;
 #[rustfmt::skip] mod path;
 #[rustfmt::skip] mod pattern;
+#[rustfmt::skip] mod perlin_noise;
+#[rustfmt::skip] mod pixel_format;
 #[rustfmt::skip] mod pixmap;
 #[rustfmt::skip] mod png;
+#[cfg(feature = "pnm-format")]
+#[rustfmt::skip] mod pnm;
+#[cfg(feature = "qoi-format")]
+#[rustfmt::skip] mod qoi;
 #[rustfmt::skip] mod skia_dash;
+#[rustfmt::skip] mod stride;
 #[rustfmt::skip] mod stroke;
@@ -12,7 +12,7 @@ fn rect() {
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
     let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), Some(&mask));
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::Mask(&mask));
 
     let expected = Pixmap::load_png("tests/images/mask/rect.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -30,7 +30,7 @@ fn rect_aa() {
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
     let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), Some(&mask));
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::Mask(&mask));
 
     let expected = Pixmap::load_png("tests/images/mask/rect-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -51,7 +51,7 @@ fn rect_ts() {
     paint.anti_alias = false;
 
     let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), Some(&mask));
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::Mask(&mask));
 
     let expected = Pixmap::load_png("tests/images/mask/rect-ts.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -70,7 +70,7 @@ fn circle_bottom_right_aa() {
     paint.anti_alias = false;
 
     let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), Some(&mask));
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::Mask(&mask));
 
     let expected = Pixmap::load_png("tests/images/mask/circle-bottom-right-aa.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -92,7 +92,7 @@ fn stroke() {
     stroke.width = 10.0;
 
     let path = PathBuilder::from_rect(Rect::from_xywh(10.0, 10.0, 80.0, 80.0).unwrap());
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Some(&mask));
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::Mask(&mask));
 
     let expected = Pixmap::load_png("tests/images/mask/stroke.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -112,7 +112,7 @@ fn skip_dest() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     let mut pixmap2 = Pixmap::new(200, 200).unwrap();
@@ -121,7 +121,7 @@ fn skip_dest() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     let clip_path = PathBuilder::from_rect(Rect::from_xywh(40.0, 40.0, 40.0, 40.0).unwrap());
@@ -129,7 +129,7 @@ fn skip_dest() {
     mask.fill_path(&clip_path, FillRule::Winding, true, Transform::default());
 
     pixmap.draw_pixmap(0, 0, pixmap2.as_ref(), &PixmapPaint::default(),
-                                Transform::identity(), Some(&mask));
+                                Transform::identity(), Clip::Mask(&mask));
 
     let expected = Pixmap::load_png("tests/images/mask/skip-dest.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -153,7 +153,7 @@ fn intersect_aa() {
         Rect::from_xywh(0.0, 0.0, 200.0, 200.0).unwrap(),
         &paint,
         Transform::identity(),
-        Some(&mask),
+        Clip::Mask(&mask),
     );
 
     let expected = Pixmap::load_png("tests/images/mask/intersect-aa.png").unwrap();
@@ -176,7 +176,7 @@ fn ignore_memset() {
         Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(),
         &paint,
         Transform::identity(),
-        Some(&mask),
+        Clip::Mask(&mask),
     );
 
     let expected = Pixmap::load_png("tests/images/mask/ignore-memset.png").unwrap();
@@ -201,7 +201,7 @@ fn ignore_source() {
         Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(),
         &paint,
         Transform::identity(),
-        Some(&mask),
+        Clip::Mask(&mask),
     );
 
     let expected = Pixmap::load_png("tests/images/mask/ignore-source.png").unwrap();
@@ -221,7 +221,7 @@ fn apply_mask() {
     paint.anti_alias = false;
 
     let rect = Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
-    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
     pixmap.apply_mask(&mask);
 
     let expected = Pixmap::load_png("tests/images/mask/apply-mask.png").unwrap();
@@ -237,7 +237,7 @@ fn mask_from_alpha() {
     paint.anti_alias = true;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), Clip::None);
 
     let mask = Mask::from_pixmap(pixmap.as_ref(), MaskType::Alpha);
 
@@ -254,10 +254,293 @@ fn mask_from_luma() {
     paint.anti_alias = true;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), Clip::None);
 
     let mask = Mask::from_pixmap(pixmap.as_ref(), MaskType::Luminance);
 
     let expected = Mask::load_png("tests/images/mask/mask-from-luma.png").unwrap();
     assert_eq!(mask, expected);
 }
+
+fn circle_mask(cx: f32, cy: f32, size: u32) -> Mask {
+    let circle = PathBuilder::from_circle(cx, cy, 50.0).unwrap();
+    let mut mask = Mask::new(size, size).unwrap();
+    mask.fill_path(&circle, FillRule::Winding, true, Transform::default());
+    mask
+}
+
+#[test]
+fn combine() {
+    let mask1 = circle_mask(75.0, 75.0, 200);
+    let mask2 = circle_mask(125.0, 125.0, 200);
+
+    fn expected(op: MaskOp, a: u32, b: u32) -> u32 {
+        let ab = (a * b + 127) / 255;
+        match op {
+            MaskOp::Intersect => ab,
+            MaskOp::Union => a + b - ab,
+            MaskOp::Difference => a - ab,
+            MaskOp::Xor => a + b - 2 * ab,
+            MaskOp::ReverseDifference => b - ab,
+        }
+    }
+
+    let ops = [MaskOp::Intersect, MaskOp::Union, MaskOp::Difference, MaskOp::Xor, MaskOp::ReverseDifference];
+    for op in ops {
+        let mut mask = mask1.clone();
+        mask.combine(&mask2, op, 0, 0);
+
+        for ((v, a), b) in mask.data().iter().zip(mask1.data()).zip(mask2.data()) {
+            assert_eq!(*v as u32, expected(op, *a as u32, *b as u32), "{:?}", op);
+        }
+    }
+}
+
+#[test]
+fn combine_with_offset() {
+    let mask1 = circle_mask(75.0, 75.0, 200);
+    let mask2 = circle_mask(50.0, 50.0, 101);
+
+    let mut expected = mask1.clone();
+    expected.combine(&circle_mask(80.0, 90.0, 200), MaskOp::Xor, 0, 0);
+    let mut mask = mask1.clone();
+    mask.combine(&mask2, MaskOp::Xor, 30, 40);
+    assert_eq!(mask, expected);
+
+    let mut expected = mask1.clone();
+    expected.combine(&circle_mask(20.0, 190.0, 200), MaskOp::Intersect, 0, 0);
+    let mut mask = mask1.clone();
+    mask.combine(&mask2, MaskOp::Intersect, -30, 140);
+    assert_eq!(mask, expected);
+
+    // No overlap.
+    let mut mask = mask1.clone();
+    mask.combine(&mask2, MaskOp::Union, 300, 0);
+    assert_eq!(mask, mask1);
+    mask.combine(&mask2, MaskOp::ReverseDifference, 300, 0);
+    assert!(mask.data().iter().all(|v| *v == 0));
+}
+
+#[test]
+fn union_and_subtract_path() {
+    let circle1 = PathBuilder::from_circle(75.0, 75.0, 50.0).unwrap();
+    let circle2 = PathBuilder::from_circle(125.0, 125.0, 50.0).unwrap();
+
+    let mut mask = circle_mask(75.0, 75.0, 200);
+    mask.union_path(&circle2, FillRule::Winding, true, Transform::default());
+    let mut expected = circle_mask(75.0, 75.0, 200);
+    expected.combine(&circle_mask(125.0, 125.0, 200), MaskOp::Union, 0, 0);
+    assert!(mask.data().iter().zip(expected.data()).all(|(a, b)| a.abs_diff(*b) <= 1));
+
+    let mut mask = circle_mask(75.0, 75.0, 200);
+    mask.subtract_path(&circle2, FillRule::Winding, true, Transform::default());
+    let mut expected = circle_mask(75.0, 75.0, 200);
+    expected.combine(&circle_mask(125.0, 125.0, 200), MaskOp::Difference, 0, 0);
+    assert!(mask.data().iter().zip(expected.data()).all(|(a, b)| a.abs_diff(*b) <= 1));
+
+    // Non-AA paths are filled using memset.
+    let mut mask = circle_mask(75.0, 75.0, 200);
+    mask.subtract_path(&circle1, FillRule::Winding, false, Transform::default());
+    assert_eq!(mask.data()[75 * 200 + 75], 0);
+}
+
+#[test]
+fn bounds() {
+    let mut mask = Mask::new(200, 200).unwrap();
+    assert_eq!(mask.bounds(), None);
+
+    let circle = PathBuilder::from_circle(75.0, 75.0, 50.0).unwrap();
+    mask.fill_path(&circle, FillRule::Winding, true, Transform::default());
+    let bounds = mask.bounds().unwrap();
+    assert!(bounds.contains(&IntRect::from_ltrb(25, 25, 125, 125).unwrap()));
+    assert!(IntRect::from_ltrb(23, 23, 127, 127).unwrap().contains(&bounds));
+
+    mask.update_bounds();
+    assert_eq!(mask.bounds(), IntRect::from_ltrb(25, 25, 125, 125));
+
+    let mut mask2 = mask.clone();
+    mask2.combine(&circle_mask(125.0, 125.0, 200), MaskOp::Intersect, 0, 0);
+    mask2.update_bounds();
+    assert_eq!(mask2.bounds(), IntRect::from_ltrb(75, 75, 125, 125));
+
+    mask2.combine(&circle_mask(125.0, 125.0, 200), MaskOp::Intersect, 100, 100);
+    assert_eq!(mask2.bounds(), None);
+    assert!(mask2.data().iter().all(|v| *v == 0));
+
+    mask.invert();
+    assert_eq!(mask.bounds(), IntRect::from_xywh(0, 0, 200, 200));
+
+    mask.clear();
+    assert_eq!(mask.bounds(), None);
+}
+
+#[test]
+fn intersect_path_bounds() {
+    let circle1 = PathBuilder::from_circle(75.0, 75.0, 50.0).unwrap();
+    let circle2 = PathBuilder::from_circle(125.0, 125.0, 50.0).unwrap();
+
+    let mut mask = Mask::new(200, 200).unwrap();
+    mask.fill_path(&circle1, FillRule::Winding, true, Transform::default());
+    mask.intersect_path(&circle2, FillRule::Winding, true, Transform::default());
+
+    let mut expected = circle_mask(75.0, 75.0, 200);
+    expected.combine(&circle_mask(125.0, 125.0, 200), MaskOp::Intersect, 0, 0);
+    assert_eq!(mask, expected);
+
+    // No overlap.
+    let circle3 = PathBuilder::from_circle(175.0, 25.0, 20.0).unwrap();
+    mask.intersect_path(&circle3, FillRule::Winding, true, Transform::default());
+    assert_eq!(mask.bounds(), None);
+    assert!(mask.data().iter().all(|v| *v == 0));
+}
+
+#[test]
+fn intersect_path_with_invalid_transform() {
+    let circle = PathBuilder::from_circle(75.0, 75.0, 50.0).unwrap();
+
+    let mut mask = Mask::new(200, 200).unwrap();
+    mask.fill_path(&circle, FillRule::Winding, true, Transform::default());
+
+    // An untransformable path is treated like an empty one.
+    let ts = Transform::from_scale(f32::MAX, f32::MAX);
+    mask.intersect_path(&circle, FillRule::Winding, true, ts);
+    assert!(mask.data().iter().all(|v| *v == 0));
+}
+
+#[test]
+fn draw_with_bounded_mask() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let rect = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+    let mask = circle_mask(75.0, 75.0, 200);
+
+    // The same mask, but with bounds covering the whole mask.
+    let mut full_mask = mask.clone();
+    full_mask.data_mut();
+    assert_eq!(full_mask.bounds(), IntRect::from_xywh(0, 0, 200, 200));
+
+    let mut pixmap1 = Pixmap::new(200, 200).unwrap();
+    pixmap1.fill_path(&rect, &paint, FillRule::Winding, Transform::default(), Clip::Mask(&mask));
+    let mut pixmap2 = Pixmap::new(200, 200).unwrap();
+    pixmap2.fill_path(&rect, &paint, FillRule::Winding, Transform::default(), Clip::Mask(&full_mask));
+    assert_eq!(pixmap1, pixmap2);
+
+    let stroke = Stroke::default();
+    let mut pixmap1 = Pixmap::new(200, 200).unwrap();
+    pixmap1.stroke_path(&rect, &paint, &stroke, Transform::default(), Clip::Mask(&mask));
+    let mut pixmap2 = Pixmap::new(200, 200).unwrap();
+    pixmap2.stroke_path(&rect, &paint, &stroke, Transform::default(), Clip::Mask(&full_mask));
+    assert_eq!(pixmap1, pixmap2);
+
+    // Nothing to draw.
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    let empty_mask = Mask::new(200, 200).unwrap();
+    pixmap.fill_path(&rect, &paint, FillRule::Winding, Transform::default(), Clip::Mask(&empty_mask));
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0));
+}
+
+fn assert_alpha_eq(mask: &Mask, pixmap: &Pixmap) {
+    let iter = mask.data().iter().zip(pixmap.pixels());
+    assert!(iter.clone().any(|(a, _)| *a != 0));
+    assert!(iter.clone().all(|(a, p)| a.abs_diff(p.alpha()) <= 1));
+
+    // Bounds must contain all non-zero values.
+    let mut tight = mask.clone();
+    tight.update_bounds();
+    assert!(mask.bounds().unwrap().contains(&tight.bounds().unwrap()));
+}
+
+#[test]
+fn stroke_path() {
+    let path = PathBuilder::from_circle(50.0, 50.0, 30.0).unwrap();
+    let ts = Transform::from_row(1.2, 0.3, -0.2, 0.8, 5.0, 10.0);
+    let stroke = Stroke {
+        width: 8.0,
+        line_join: LineJoin::Round,
+        ..Stroke::default()
+    };
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.stroke_path(&path, &stroke, true, ts);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.stroke_path(&path, &Paint::default(), &stroke, ts, Clip::None);
+
+    assert_alpha_eq(&mask, &pixmap);
+}
+
+#[test]
+fn stroke_hairline() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 10.0);
+    pb.line_to(90.0, 70.0);
+    let path = pb.finish().unwrap();
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.stroke_path(&path, &Stroke::default(), true, Transform::identity());
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.stroke_path(&path, &Paint::default(), &Stroke::default(), Transform::identity(), Clip::None);
+
+    assert_alpha_eq(&mask, &pixmap);
+}
+
+#[test]
+fn fill_path_with_gradient() {
+    let mut paint = Paint::default();
+    paint.shader = LinearGradient::new(
+        Point::from_xy(10.0, 0.0),
+        Point::from_xy(90.0, 0.0),
+        vec![
+            GradientStop::new(0.0, Color::WHITE),
+            GradientStop::new(1.0, Color::TRANSPARENT),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .unwrap();
+
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path_with_paint(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+
+    assert_alpha_eq(&mask, &pixmap);
+    assert!(mask.data()[50 * 100 + 20] > mask.data()[50 * 100 + 80]);
+}
+
+#[test]
+fn fill_rect_with_paint_and_clip() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(0, 0, 0, 127);
+
+    let clip = circle_mask(50.0, 50.0, 100);
+    let rect = Rect::from_xywh(10.0, 20.0, 60.0, 50.0).unwrap();
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_rect_with_paint(rect, &paint, Transform::identity(), Clip::Mask(&clip));
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::Mask(&clip));
+
+    assert_alpha_eq(&mask, &pixmap);
+    assert_eq!(mask.data()[0], 0);
+}
+
+#[test]
+fn draw_mask() {
+    let circle = circle_mask(50.0, 50.0, 100);
+
+    let mut mask = Mask::new(200, 200).unwrap();
+    mask.draw_mask(30, 40, &circle, &PixmapPaint::default(), Transform::identity(), Clip::None);
+
+    let mut expected = Mask::new(200, 200).unwrap();
+    expected.combine(&circle, MaskOp::Union, 30, 40);
+
+    assert!(mask.data().iter().zip(expected.data()).all(|(a, b)| a.abs_diff(*b) <= 1));
+    assert!(mask.bounds().unwrap().contains(&IntRect::from_xywh(30, 40, 100, 100).unwrap()));
+}
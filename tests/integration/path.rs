@@ -294,3 +294,535 @@ fn tight_bounds_2() {
     let tight_bounds = path.compute_tight_bounds().unwrap();
     assert_eq!(tight_bounds, Rect::from_xywh(-21.707121, 52.609154, 86.894302, 37.729645).unwrap());
 }
+
+#[test]
+fn offset_rect() {
+    let path = PathBuilder::from_rect(Rect::from_xywh(50.0, 50.0, 100.0, 100.0).unwrap());
+
+    let outset = path.offset(10.0, LineJoin::Miter, 4.0, 0.25).unwrap();
+    assert_eq!(outset.bounds(), Rect::from_ltrb(40.0, 40.0, 160.0, 160.0).unwrap());
+    // Redundant points are removed.
+    assert_eq!(outset.points().len(), 4);
+
+    let inset = path.offset(-10.0, LineJoin::Miter, 4.0, 0.25).unwrap();
+    assert_eq!(inset.bounds(), Rect::from_ltrb(60.0, 60.0, 140.0, 140.0).unwrap());
+    assert_eq!(inset.points().len(), 4);
+
+    let bevel = path.offset(10.0, LineJoin::Bevel, 4.0, 0.25).unwrap();
+    assert_eq!(bevel.bounds(), Rect::from_ltrb(40.0, 40.0, 160.0, 160.0).unwrap());
+    assert_eq!(bevel.points().len(), 8);
+
+    assert!(path.offset(-60.0, LineJoin::Miter, 4.0, 0.25).is_none());
+    assert!(path.offset(f32::NAN, LineJoin::Miter, 4.0, 0.25).is_none());
+    assert!(path.offset(10.0, LineJoin::Miter, 4.0, 0.0).is_none());
+}
+
+#[test]
+fn offset_circle() {
+    let path = PathBuilder::from_circle(100.0, 100.0, 50.0).unwrap();
+
+    let outset = path.offset(10.0, LineJoin::Round, 4.0, 0.1).unwrap();
+    let bounds = outset.bounds();
+    assert!((bounds.left() - 40.0).abs() < 0.2);
+    assert!((bounds.bottom() - 160.0).abs() < 0.2);
+
+    let inset = path.offset(-10.0, LineJoin::Round, 4.0, 0.1).unwrap();
+    let bounds = inset.bounds();
+    assert!((bounds.left() - 60.0).abs() < 0.2);
+    assert!((bounds.bottom() - 140.0).abs() < 0.2);
+
+    // A single contour.
+    assert_eq!(inset.segments().filter(|s| matches!(s, PathSegment::MoveTo(_))).count(), 1);
+}
+
+#[test]
+fn offset_removes_overlaps() {
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_ltrb(0.0, 0.0, 20.0, 20.0).unwrap());
+    pb.push_rect(Rect::from_ltrb(10.0, 10.0, 30.0, 30.0).unwrap());
+    let path = pb.finish().unwrap();
+
+    let union = path.offset(0.0, LineJoin::Miter, 4.0, 0.25).unwrap();
+    assert_eq!(union.segments().filter(|s| matches!(s, PathSegment::MoveTo(_))).count(), 1);
+    assert_eq!(union.points().len(), 8);
+    assert_eq!(union.bounds(), Rect::from_ltrb(0.0, 0.0, 30.0, 30.0).unwrap());
+}
+
+#[test]
+fn offset_with_hole() {
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_ltrb(0.0, 0.0, 100.0, 100.0).unwrap());
+    // A counter-clockwise hole.
+    pb.move_to(30.0, 30.0);
+    pb.line_to(30.0, 70.0);
+    pb.line_to(70.0, 70.0);
+    pb.line_to(70.0, 30.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    let outset = path.offset(5.0, LineJoin::Miter, 4.0, 0.25).unwrap();
+    assert_eq!(outset.bounds(), Rect::from_ltrb(-5.0, -5.0, 105.0, 105.0).unwrap());
+
+    let mut pixmap = Pixmap::new(120, 120).unwrap();
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(0, 0, 0, 255);
+    let ts = Transform::from_translate(10.0, 10.0);
+    pixmap.fill_path(&outset, &paint, FillRule::EvenOdd, ts, Clip::None);
+    // The hole shrinks.
+    assert_eq!(pixmap.pixel(10 + 32, 10 + 50).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(10 + 50, 10 + 50).unwrap().alpha(), 0);
+
+    // Closing the hole.
+    let outset = path.offset(25.0, LineJoin::Miter, 4.0, 0.25).unwrap();
+    assert_eq!(outset.segments().filter(|s| matches!(s, PathSegment::MoveTo(_))).count(), 1);
+}
+
+#[test]
+fn offset_complex() {
+    let mut pb = PathBuilder::new();
+    // A self-intersecting star.
+    pb.move_to(60.0, 10.0);
+    pb.line_to(90.0, 100.0);
+    pb.line_to(15.0, 45.0);
+    pb.line_to(105.0, 45.0);
+    pb.line_to(30.0, 100.0);
+    pb.close();
+    pb.move_to(120.0, 120.0);
+    pb.cubic_to(220.0, 80.0, 180.0, 220.0, 150.0, 180.0);
+    pb.quad_to(100.0, 190.0, 120.0, 120.0);
+    let path = pb.finish().unwrap();
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    let mut paint = Paint::default();
+    paint.anti_alias = true;
+
+    paint.set_color_rgba8(50, 127, 150, 255);
+    let outset = path.offset(8.0, LineJoin::Round, 4.0, 0.25).unwrap();
+    pixmap.fill_path(&outset, &paint, FillRule::EvenOdd, Transform::default(), Clip::None);
+
+    paint.set_color_rgba8(220, 140, 75, 255);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), Clip::None);
+
+    paint.set_color_rgba8(255, 255, 255, 255);
+    let inset = path.offset(-5.0, LineJoin::Miter, 4.0, 0.25).unwrap();
+    pixmap.fill_path(&inset, &paint, FillRule::EvenOdd, Transform::default(), Clip::None);
+
+    let expected = Pixmap::load_png("tests/images/path/offset.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn flatten() {
+    let mut pb = PathBuilder::new();
+    pb.push_circle(50.0, 50.0, 40.0);
+    pb.move_to(10.0, 10.0);
+    pb.line_to(20.0, 10.0);
+    pb.quad_to(30.0, 20.0, 40.0, 10.0);
+    let path = pb.finish().unwrap();
+
+    let polylines = path.flatten(0.1);
+    assert_eq!(polylines.len(), 2);
+
+    let circle = &polylines[0];
+    assert!(circle.len() > 16);
+    assert_eq!(circle.first(), circle.last());
+    for p in circle {
+        let r = p.distance(Point::from_xy(50.0, 50.0));
+        assert!((r - 40.0).abs() <= 0.1);
+    }
+
+    let open = &polylines[1];
+    assert!(open.len() > 3);
+    assert_eq!(open[0], Point::from_xy(10.0, 10.0));
+    assert_eq!(open[1], Point::from_xy(20.0, 10.0));
+    assert_eq!(open[open.len() - 1], Point::from_xy(40.0, 10.0));
+}
+
+#[test]
+fn simplify_polyline() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    for i in 1..=100 {
+        let y = if i % 2 == 0 { 0.2 } else { -0.2 };
+        pb.line_to(i as f32, y);
+    }
+    pb.line_to(100.0, 50.0);
+    pb.push_rect(Rect::from_xywh(10.0, 10.0, 20.0, 20.0).unwrap());
+    let path = pb.finish().unwrap();
+
+    let path = path.simplify_polyline(1.0).unwrap();
+    let points = path.points();
+    assert_eq!(points.len(), 3 + 4);
+    assert_eq!(points[0], Point::from_xy(0.0, 0.0));
+    assert_eq!(points[2], Point::from_xy(100.0, 50.0));
+    assert_eq!(
+        &points[3..],
+        &[
+            Point::from_xy(10.0, 10.0),
+            Point::from_xy(30.0, 10.0),
+            Point::from_xy(30.0, 30.0),
+            Point::from_xy(10.0, 30.0),
+        ]
+    );
+    assert_eq!(path.segments().last(), Some(PathSegment::Close));
+}
+
+#[test]
+fn simplify_polyline_curves() {
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+
+    let coarse = path.simplify_polyline(2.0).unwrap();
+    let fine = path.simplify_polyline(0.1).unwrap();
+    assert!(coarse.len() < fine.len());
+    for p in coarse.points() {
+        let r = p.distance(Point::from_xy(50.0, 50.0));
+        assert!((r - 40.0).abs() <= 2.0);
+    }
+
+    assert_eq!(path.simplify_polyline(0.0), None);
+}
+
+#[test]
+fn fit_curves() {
+    let points: Vec<_> = (0..=100)
+        .map(|i| {
+            let x = i as f32;
+            Point::from_xy(x, 50.0 + (x * 0.1).sin() * 20.0)
+        })
+        .collect();
+
+    let mut pb = PathBuilder::new();
+    pb.fit_curves(&points, 0.5);
+    let path = pb.finish().unwrap();
+
+    assert!(path.len() < 20);
+    assert_eq!(path.points()[0], points[0]);
+    assert_eq!(path.points()[path.points().len() - 1], points[100]);
+
+    // Check the distance to the flattened curves, which are close enough.
+    let polyline = &path.flatten(0.01)[0];
+    for p in &points {
+        let dist = polyline
+            .windows(2)
+            .map(|line| distance_to_line(*p, line[0], line[1]))
+            .fold(f32::MAX, f32::min);
+        assert!(dist < 0.51);
+    }
+}
+
+fn distance_to_line(p: Point, a: Point, b: Point) -> f32 {
+    let ab = b - a;
+    let t = ((p - a).dot(ab) / ab.dot(ab)).clamp(0.0, 1.0);
+    p.distance(Point::from_xy(a.x + ab.x * t, a.y + ab.y * t))
+}
+
+#[test]
+fn fit_curves_closed() {
+    let mut points: Vec<_> = (0..64)
+        .map(|i| {
+            let a = i as f32 / 64.0 * std::f32::consts::PI * 2.0;
+            Point::from_xy(50.0 + a.cos() * 40.0, 50.0 + a.sin() * 40.0)
+        })
+        .collect();
+    points.push(points[0]);
+
+    let mut pb = PathBuilder::new();
+    pb.fit_curves(&points, 0.1);
+    let path = pb.finish().unwrap();
+
+    assert_eq!(path.segments().last(), Some(PathSegment::Close));
+    for polyline in path.flatten(0.01) {
+        for p in polyline {
+            let r = p.distance(Point::from_xy(50.0, 50.0));
+            assert!((r - 40.0).abs() < 0.2);
+        }
+    }
+}
+
+#[test]
+fn fit_curves_invalid() {
+    let mut pb = PathBuilder::new();
+    pb.fit_curves(&[Point::from_xy(1.0, 1.0), Point::from_xy(1.0, 1.0)], 0.5);
+    pb.fit_curves(&[Point::from_xy(1.0, 1.0), Point::from_xy(2.0, 1.0)], 0.0);
+    pb.fit_curves(&[Point::from_xy(1.0, 1.0), Point::from_xy(f32::NAN, 1.0)], 0.5);
+    assert!(pb.is_empty());
+}
+
+#[test]
+fn reverse() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 20.0);
+    pb.line_to(30.0, 40.0);
+    pb.quad_to(50.0, 60.0, 70.0, 80.0);
+    pb.move_to(1.0, 2.0);
+    pb.cubic_to(3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    let reversed = path.reverse();
+    let segments: Vec<_> = reversed.segments().collect();
+    assert_eq!(
+        segments,
+        &[
+            PathSegment::MoveTo(Point::from_xy(70.0, 80.0)),
+            PathSegment::QuadTo(Point::from_xy(50.0, 60.0), Point::from_xy(30.0, 40.0)),
+            PathSegment::LineTo(Point::from_xy(10.0, 20.0)),
+            PathSegment::MoveTo(Point::from_xy(7.0, 8.0)),
+            PathSegment::CubicTo(
+                Point::from_xy(5.0, 6.0),
+                Point::from_xy(3.0, 4.0),
+                Point::from_xy(1.0, 2.0)
+            ),
+            PathSegment::Close,
+        ]
+    );
+    assert_eq!(reversed.bounds(), path.bounds());
+    assert_eq!(reversed.reverse(), path);
+}
+
+#[test]
+fn contours() {
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_xywh(10.0, 10.0, 20.0, 20.0).unwrap());
+    pb.move_to(50.0, 50.0);
+    pb.line_to(60.0, 70.0);
+    pb.push_circle(100.0, 100.0, 10.0);
+    let path = pb.finish().unwrap();
+
+    let contours: Vec<_> = path.contours().collect();
+    assert_eq!(contours.len(), 3);
+    assert_eq!(
+        contours[0],
+        PathBuilder::from_rect(Rect::from_xywh(10.0, 10.0, 20.0, 20.0).unwrap())
+    );
+    assert_eq!(contours[1].bounds(), Rect::from_ltrb(50.0, 50.0, 60.0, 70.0).unwrap());
+    assert_eq!(contours[2], PathBuilder::from_circle(100.0, 100.0, 10.0).unwrap());
+}
+
+#[test]
+fn winding_direction() {
+    let rect = PathBuilder::from_rect(Rect::from_xywh(10.0, 10.0, 20.0, 20.0).unwrap());
+    assert_eq!(rect.winding_direction(), Some(PathDirection::CW));
+    assert_eq!(rect.reverse().winding_direction(), Some(PathDirection::CCW));
+
+    let circle = PathBuilder::from_circle(100.0, 100.0, 10.0).unwrap();
+    assert_eq!(circle.winding_direction(), Some(PathDirection::CW));
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 10.0);
+    pb.line_to(20.0, 20.0);
+    let line = pb.finish().unwrap();
+    assert_eq!(line.winding_direction(), None);
+}
+
+#[test]
+fn is_rect() {
+    let rect = Rect::from_xywh(10.0, 10.0, 20.0, 30.0).unwrap();
+    assert_eq!(PathBuilder::from_rect(rect).is_rect(), Some(rect));
+    assert_eq!(PathBuilder::from_rect(rect).reverse().is_rect(), Some(rect));
+
+    // Starts in the middle of an edge, has a duplicated point and isn't closed.
+    let mut pb = PathBuilder::new();
+    pb.move_to(20.0, 10.0);
+    pb.line_to(30.0, 10.0);
+    pb.line_to(30.0, 40.0);
+    pb.line_to(30.0, 40.0);
+    pb.line_to(10.0, 40.0);
+    pb.line_to(10.0, 10.0);
+    assert_eq!(pb.finish().unwrap().is_rect(), Some(rect));
+
+    // Not axis-aligned.
+    let mut pb = PathBuilder::new();
+    pb.move_to(20.0, 10.0);
+    pb.line_to(30.0, 20.0);
+    pb.line_to(20.0, 30.0);
+    pb.line_to(10.0, 20.0);
+    pb.close();
+    assert_eq!(pb.finish().unwrap().is_rect(), None);
+
+    // Goes back along an edge.
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 10.0);
+    pb.line_to(30.0, 10.0);
+    pb.line_to(20.0, 10.0);
+    pb.line_to(20.0, 40.0);
+    pb.line_to(10.0, 40.0);
+    pb.close();
+    assert_eq!(pb.finish().unwrap().is_rect(), None);
+
+    // Two contours.
+    let mut pb = PathBuilder::new();
+    pb.push_rect(rect);
+    pb.push_rect(rect);
+    assert_eq!(pb.finish().unwrap().is_rect(), None);
+}
+
+#[test]
+fn is_oval() {
+    for rect in [
+        Rect::from_xywh(10.0, 10.0, 20.0, 30.0).unwrap(),
+        Rect::from_xywh(0.5, 1.0, 2.0, 1.0).unwrap(),
+        Rect::from_xywh(-500.0, 100.0, 1000.0, 50.0).unwrap(),
+    ] {
+        let oval = PathBuilder::from_oval(rect).unwrap();
+        let bounds = oval.is_oval().unwrap();
+        assert!((bounds.left() - rect.left()).abs() < 0.001);
+        assert!((bounds.top() - rect.top()).abs() < 0.001);
+        assert!((bounds.right() - rect.right()).abs() < 0.001);
+        assert!((bounds.bottom() - rect.bottom()).abs() < 0.001);
+        assert!(oval.reverse().is_oval().is_some());
+    }
+
+    // A circle made of cubics.
+    let k = 0.5523 * 50.0;
+    let mut pb = PathBuilder::new();
+    pb.move_to(100.0, 50.0);
+    pb.cubic_to(100.0, 50.0 + k, 50.0 + k, 100.0, 50.0, 100.0);
+    pb.cubic_to(50.0 - k, 100.0, 0.0, 50.0 + k, 0.0, 50.0);
+    pb.cubic_to(0.0, 50.0 - k, 50.0 - k, 0.0, 50.0, 0.0);
+    pb.cubic_to(50.0 + k, 0.0, 100.0, 50.0 - k, 100.0, 50.0);
+    pb.close();
+    assert_eq!(
+        pb.finish().unwrap().is_oval(),
+        Some(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap())
+    );
+
+    // A rounded rectangle.
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 0.0);
+    pb.quad_to(100.0, 0.0, 100.0, 90.0);
+    pb.quad_to(100.0, 100.0, 10.0, 100.0);
+    pb.quad_to(0.0, 100.0, 0.0, 10.0);
+    pb.quad_to(0.0, 0.0, 10.0, 0.0);
+    pb.close();
+    assert_eq!(pb.finish().unwrap().is_oval(), None);
+
+    // An open oval.
+    let mut pb = PathBuilder::new();
+    pb.move_to(100.0, 50.0);
+    pb.cubic_to(100.0, 50.0 + k, 50.0 + k, 100.0, 50.0, 100.0);
+    pb.cubic_to(50.0 - k, 100.0, 0.0, 50.0 + k, 0.0, 50.0);
+    assert_eq!(pb.finish().unwrap().is_oval(), None);
+
+    let rect = PathBuilder::from_rect(Rect::from_xywh(10.0, 10.0, 20.0, 30.0).unwrap());
+    assert_eq!(rect.is_oval(), None);
+}
+
+#[test]
+fn push_path_with_transform() {
+    let rect = PathBuilder::from_rect(Rect::from_xywh(10.0, 10.0, 20.0, 30.0).unwrap());
+
+    let mut pb = PathBuilder::new();
+    pb.push_path_with_transform(&rect, Transform::from_row(2.0, 0.0, 0.0, 2.0, 5.0, 0.0));
+    pb.push_path_with_transform(&rect, Transform::from_scale(f32::INFINITY, 1.0));
+    let path = pb.finish().unwrap();
+
+    assert_eq!(
+        path,
+        PathBuilder::from_rect(Rect::from_xywh(25.0, 20.0, 40.0, 60.0).unwrap())
+    );
+}
+
+#[test]
+fn push_path_with_transform_then_line_to() {
+    let rect = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 20.0, 20.0).unwrap());
+
+    // A closed path requires a new contour.
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(10.0, 0.0);
+    pb.push_path_with_transform(&rect, Transform::identity());
+    pb.line_to(50.0, 50.0);
+    let path = pb.finish().unwrap();
+    assert_eq!(
+        format!("{:?}", path),
+        "Path { segments: \"M 0 0 L 10 0 M 10 10 L 20 10 L 20 20 L 10 20 Z M 10 10 L 50 50\", \
+         bounds: Rect { left: 0.0, top: 0.0, right: 50.0, bottom: 50.0 } }"
+    );
+
+    // An open path is continued from its last contour.
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(10.0, 0.0);
+    pb.move_to(20.0, 20.0);
+    pb.line_to(30.0, 20.0);
+    let open = pb.finish().unwrap();
+
+    let mut pb = PathBuilder::new();
+    pb.push_path_with_transform(&open, Transform::identity());
+    pb.close();
+    pb.line_to(50.0, 50.0);
+    let path = pb.finish().unwrap();
+    assert_eq!(
+        format!("{:?}", path),
+        "Path { segments: \"M 0 0 L 10 0 M 20 20 L 30 20 Z M 20 20 L 50 50\", \
+         bounds: Rect { left: 0.0, top: 0.0, right: 50.0, bottom: 50.0 } }"
+    );
+}
+
+#[test]
+fn offset_tangent_circles() {
+    let mut pb = PathBuilder::new();
+    pb.push_circle(50.0, 50.0, 20.0);
+    pb.push_circle(90.0, 50.0, 20.0);
+    let path = pb.finish().unwrap();
+
+    let contours = |path: &Path| path.segments().filter(|s| matches!(s, PathSegment::MoveTo(_))).count();
+
+    // Circles touch at a single point and stay separate.
+    let union = path.offset(0.0, LineJoin::Round, 4.0, 0.1).unwrap();
+    assert_eq!(union.bounds(), Rect::from_ltrb(30.0, 30.0, 110.0, 70.0).unwrap());
+    assert_eq!(contours(&union), 2);
+
+    let outset = path.offset(5.0, LineJoin::Round, 4.0, 0.1).unwrap();
+    assert!((outset.bounds().left() - 25.0).abs() < 0.1);
+    assert!((outset.bounds().right() - 115.0).abs() < 0.1);
+    assert_eq!(contours(&outset), 1);
+
+    let inset = path.offset(-5.0, LineJoin::Round, 4.0, 0.1).unwrap();
+    assert!((inset.bounds().left() - 35.0).abs() < 0.1);
+    assert!((inset.bounds().right() - 105.0).abs() < 0.1);
+    assert_eq!(contours(&inset), 2);
+}
+
+#[test]
+fn offset_collinear_overlapping_edges() {
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_ltrb(10.0, 10.0, 50.0, 50.0).unwrap());
+    // Edges partially overlapping the first rect ones.
+    pb.push_rect(Rect::from_ltrb(50.0, 20.0, 90.0, 40.0).unwrap());
+    pb.push_rect(Rect::from_ltrb(20.0, 50.0, 40.0, 70.0).unwrap());
+    // Edges fully overlapping the first rect ones.
+    pb.push_rect(Rect::from_ltrb(10.0, 0.0, 50.0, 10.0).unwrap());
+    let path = pb.finish().unwrap();
+
+    for (distance, bounds) in [
+        (0.0, Rect::from_ltrb(10.0, 0.0, 90.0, 70.0).unwrap()),
+        (5.0, Rect::from_ltrb(5.0, -5.0, 95.0, 75.0).unwrap()),
+        (-5.0, Rect::from_ltrb(15.0, 5.0, 85.0, 65.0).unwrap()),
+    ] {
+        let offset = path.offset(distance, LineJoin::Miter, 4.0, 0.25).unwrap();
+        assert_eq!(offset.bounds(), bounds);
+        assert_eq!(offset.segments().filter(|s| matches!(s, PathSegment::MoveTo(_))).count(), 1);
+        assert_eq!(offset.points().len(), 12);
+    }
+}
+
+#[test]
+fn offset_signed_zeros() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, -10.0);
+    pb.line_to(-15.0, -15.0);
+    pb.line_to(-0.0, -10.0);
+    pb.line_to(-10.0, 10.0);
+    pb.line_to(-5.0, 15.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    // The zero-area spike is removed, leaving only the triangle.
+    let offset = path.offset(1.0, LineJoin::Miter, 4.0, 0.25).unwrap();
+    let bounds = offset.bounds();
+    assert!(bounds.left() < -10.0 && bounds.right() > 0.0);
+    assert!(bounds.top() < -10.0 && bounds.bottom() > 15.0);
+    assert_eq!(offset.segments().filter(|s| matches!(s, PathSegment::MoveTo(_))).count(), 1);
+}
@@ -13,7 +13,7 @@ fn crate_triangle() -> Pixmap {
     let path = pb.finish().unwrap();
 
     let mut pixmap = Pixmap::new(20, 20).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
     pixmap
 }
 
@@ -34,7 +34,7 @@ fn pad_nearest() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/pad-nearest.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -57,7 +57,7 @@ fn repeat_nearest() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/repeat-nearest.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -80,7 +80,7 @@ fn reflect_nearest() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/reflect-nearest.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -106,7 +106,7 @@ fn pad_bicubic() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/pad-bicubic.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -130,7 +130,7 @@ fn repeat_bicubic() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/repeat-bicubic.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -154,7 +154,7 @@ fn reflect_bicubic() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/reflect-bicubic.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -177,7 +177,7 @@ fn filter_nearest_no_ts() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/filter-nearest-no-ts.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -200,7 +200,7 @@ fn filter_nearest() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/filter-nearest.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -223,7 +223,7 @@ fn filter_bilinear() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/filter-bilinear.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -246,8 +246,146 @@ fn filter_bicubic() {
     let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/pattern/filter-bicubic.png").unwrap();
     assert_eq!(pixmap, expected);
 }
+
+fn draw_cubic(quality: FilterQuality) -> Pixmap {
+    let triangle = crate_triangle();
+
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = Pattern::new(
+        triangle.as_ref(),
+        SpreadMode::Repeat,
+        quality,
+        1.0,
+        Transform::from_row(1.5, 0.0, -0.4, -0.8, 5.0, 1.0),
+    );
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+    pixmap
+}
+
+#[test]
+fn filter_cubic_mitchell() {
+    let pixmap = draw_cubic(FilterQuality::Cubic(CubicResampler::MITCHELL));
+    let expected = Pixmap::load_png("tests/images/pattern/filter-bicubic.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn filter_cubic_catmull_rom() {
+    let pixmap = draw_cubic(FilterQuality::Cubic(CubicResampler::CATMULL_ROM));
+    let expected = Pixmap::load_png("tests/images/pattern/filter-catmull-rom.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+fn checkerboard() -> Pixmap {
+    let mut pixmap = Pixmap::new(256, 256).unwrap();
+    for (i, p) in pixmap.pixels_mut().iter_mut().enumerate() {
+        let x = i % 256;
+        let y = i / 256;
+        if (x + y) % 2 == 0 {
+            *p = PremultipliedColorU8::from_rgba(255, 255, 255, 255).unwrap();
+        } else {
+            *p = PremultipliedColorU8::from_rgba(0, 0, 0, 255).unwrap();
+        }
+    }
+
+    pixmap
+}
+
+fn draw_downscaled(shader: Shader) -> Pixmap {
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = shader;
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(0.0, 0.0, 64.0, 64.0).unwrap());
+
+    let mut pixmap = Pixmap::new(64, 64).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+    pixmap
+}
+
+#[test]
+fn filter_medium_downscale() {
+    let checkerboard = checkerboard();
+
+    let pixmap = draw_downscaled(Pattern::new(
+        checkerboard.as_ref(),
+        SpreadMode::Pad,
+        FilterQuality::Medium,
+        1.0,
+        Transform::from_scale(0.25, 0.25),
+    ));
+
+    // A 1px checkerboard downscaled by 4x must be a uniform gray, without any aliasing.
+    for p in pixmap.pixels() {
+        assert!(p.red() >= 126 && p.red() <= 129, "{:?}", p);
+        assert_eq!(p.alpha(), 255);
+    }
+}
+
+#[test]
+fn filter_medium_upscale() {
+    let triangle = crate_triangle();
+
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = Pattern::new(
+        triangle.as_ref(),
+        SpreadMode::Repeat,
+        FilterQuality::Medium,
+        1.0,
+        Transform::from_row(1.5, 0.0, -0.4, -0.8, 5.0, 1.0),
+    );
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+
+    // No mipmaps are used when upscaling, so it's the same as bilinear.
+    let expected = Pixmap::load_png("tests/images/pattern/filter-bilinear.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn filter_medium_mipmapped() {
+    let checkerboard = checkerboard();
+    let ts = Transform::from_row(0.2, 0.05, -0.05, 0.3, 1.0, 2.0);
+
+    let expected = draw_downscaled(Pattern::new(
+        checkerboard.as_ref(),
+        SpreadMode::Repeat,
+        FilterQuality::Medium,
+        1.0,
+        ts,
+    ));
+
+    let mipmaps = Mipmaps::new(checkerboard.as_ref());
+    let pixmap = draw_downscaled(Pattern::new_mipmapped(
+        &mipmaps,
+        SpreadMode::Repeat,
+        FilterQuality::Medium,
+        1.0,
+        ts,
+    ));
+
+    assert_eq!(pixmap, expected);
+    assert!(mipmaps.is_level_ready(2));
+    assert!(!mipmaps.is_level_ready(3));
+}
+
+#[test]
+fn send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Mipmaps>();
+    assert_send_sync::<Paint>();
+}
@@ -0,0 +1,101 @@
+use tiny_skia::*;
+
+fn fill(shader: Shader) -> Pixmap {
+    let mut paint = Paint::default();
+    paint.shader = shader;
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_rect(
+        Rect::from_xywh(0.0, 0.0, 200.0, 200.0).unwrap(),
+        &paint,
+        Transform::identity(),
+        Clip::None,
+    );
+    pixmap
+}
+
+#[test]
+fn fractal_noise() {
+    let shader =
+        PerlinNoise::new_fractal_noise(0.05, 0.05, 4, 2.0, None, Transform::identity()).unwrap();
+    let pixmap = fill(shader);
+
+    let expected = Pixmap::load_png("tests/images/perlin-noise/fractal-noise.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn turbulence() {
+    let shader =
+        PerlinNoise::new_turbulence(0.02, 0.04, 3, 7.0, None, Transform::identity()).unwrap();
+    let pixmap = fill(shader);
+
+    let expected = Pixmap::load_png("tests/images/perlin-noise/turbulence.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn turbulence_with_transform() {
+    let mut shader =
+        PerlinNoise::new_turbulence(0.05, 0.05, 2, 0.0, None, Transform::identity()).unwrap();
+    shader.transform(Transform::from_row(2.0, 0.0, 0.0, 2.0, 30.0, 10.0));
+    shader.apply_opacity(0.5);
+    let pixmap = fill(shader);
+
+    let expected =
+        Pixmap::load_png("tests/images/perlin-noise/turbulence-with-transform.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn stitch_tiles() {
+    let tile = IntSize::from_wh(50, 40).unwrap();
+    // Compensate Skia's 1px noise offset, so the tile starts at the pixmap origin.
+    let ts = Transform::from_translate(1.0, 1.0);
+    let shader = PerlinNoise::new_fractal_noise(0.045, 0.03, 3, 1.0, Some(tile), ts).unwrap();
+    let pixmap = fill(shader);
+
+    let is_near = |a: PremultipliedColorU8, b: PremultipliedColorU8| {
+        a.red().abs_diff(b.red()) <= 1
+            && a.green().abs_diff(b.green()) <= 1
+            && a.blue().abs_diff(b.blue()) <= 1
+            && a.alpha().abs_diff(b.alpha()) <= 1
+    };
+
+    // The noise past the tile's edge continues from its opposite edge.
+    for y in 0..40 {
+        assert!(is_near(pixmap.pixel(0, y).unwrap(), pixmap.pixel(50, y).unwrap()));
+    }
+
+    for x in 0..50 {
+        assert!(is_near(pixmap.pixel(x, 0).unwrap(), pixmap.pixel(x, 40).unwrap()));
+    }
+
+    // Without stitching, the noise is different.
+    let shader = PerlinNoise::new_fractal_noise(0.045, 0.03, 3, 1.0, None, ts).unwrap();
+    let pixmap = fill(shader);
+    assert!((0..40).any(|y| !is_near(pixmap.pixel(0, y).unwrap(), pixmap.pixel(50, y).unwrap())));
+}
+
+#[test]
+fn zero_octaves() {
+    let shader = PerlinNoise::new_fractal_noise(0.05, 0.05, 0, 0.0, None, Transform::identity());
+    assert_eq!(
+        shader,
+        Some(Shader::SolidColor(Color::from_rgba(0.5, 0.5, 0.5, 0.5).unwrap()))
+    );
+
+    let shader = PerlinNoise::new_turbulence(0.05, 0.05, 0, 0.0, None, Transform::identity());
+    assert_eq!(shader, Some(Shader::SolidColor(Color::TRANSPARENT)));
+}
+
+#[test]
+fn invalid_input() {
+    let ts = Transform::identity();
+    assert!(PerlinNoise::new_fractal_noise(-0.05, 0.05, 1, 0.0, None, ts).is_none());
+    assert!(PerlinNoise::new_fractal_noise(0.05, f32::NAN, 1, 0.0, None, ts).is_none());
+    assert!(PerlinNoise::new_fractal_noise(0.05, 0.05, 256, 0.0, None, ts).is_none());
+    assert!(PerlinNoise::new_turbulence(0.05, 0.05, 1, f32::INFINITY, None, ts).is_none());
+    let ts = Transform::from_row(1.0, 2.0, 2.0, 4.0, 0.0, 0.0);
+    assert!(PerlinNoise::new_turbulence(0.05, 0.05, 1, 0.0, None, ts).is_none());
+}
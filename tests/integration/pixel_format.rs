@@ -0,0 +1,227 @@
+use tiny_skia::*;
+
+const FORMATS: &[PixelFormat] = &[
+    PixelFormat::Rgba8888,
+    PixelFormat::Bgra8888,
+    PixelFormat::Rgb565,
+    PixelFormat::A8,
+    PixelFormat::Gray8,
+    PixelFormat::Rgba16161616,
+    PixelFormat::RgbaF16,
+];
+
+fn gradient_paint(hq: bool) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.anti_alias = true;
+    paint.force_hq_pipeline = hq;
+    paint.shader = LinearGradient::new(
+        Point::from_xy(10.0, 10.0),
+        Point::from_xy(90.0, 90.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+    paint
+}
+
+fn draw(pixmap: &mut PixmapMut, paint: &Paint) {
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+
+    let mut background = Paint::default();
+    background.set_color_rgba8(0, 0, 255, 100);
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 60.0, 60.0).unwrap(), &background, Transform::identity(), Clip::None);
+    pixmap.fill_path(&path, paint, FillRule::Winding, Transform::identity(), Clip::None);
+}
+
+fn render(format: PixelFormat, paint: &Paint) -> Pixmap {
+    let mut pixmap = Pixmap::new_with_format(100, 100, format).unwrap();
+    draw(&mut pixmap.as_mut(), paint);
+    pixmap
+}
+
+fn max_diff(a: &Pixmap, b: &Pixmap, compare: impl Fn(PremultipliedColorU8) -> [u8; 4]) -> u8 {
+    let mut max = 0;
+    for y in 0..a.height() {
+        for x in 0..a.width() {
+            let c1 = compare(a.pixel(x, y).unwrap());
+            let c2 = compare(b.pixel(x, y).unwrap());
+            for i in 0..4 {
+                max = max.max(c1[i].abs_diff(c2[i]));
+            }
+        }
+    }
+
+    max
+}
+
+fn rgba(c: PremultipliedColorU8) -> [u8; 4] {
+    [c.red(), c.green(), c.blue(), c.alpha()]
+}
+
+#[test]
+fn bgra() {
+    for hq in [false, true] {
+        let paint = gradient_paint(hq);
+        let expected = render(PixelFormat::Rgba8888, &paint);
+        let pixmap = render(PixelFormat::Bgra8888, &paint);
+
+        assert_eq!(pixmap.data().len(), expected.data().len());
+        for (p1, p2) in pixmap.data().chunks(4).zip(expected.data().chunks(4)) {
+            assert_eq!(p1, &[p2[2], p2[1], p2[0], p2[3]]);
+        }
+    }
+}
+
+#[test]
+fn bgra_framebuffer() {
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    draw(&mut expected.as_mut(), &gradient_paint(false));
+
+    let mut data = vec![0; 100 * 100 * 4];
+    let mut pixmap = PixmapMut::from_bytes_with_format(&mut data, 100, 100, PixelFormat::Bgra8888).unwrap();
+    draw(&mut pixmap, &gradient_paint(false));
+
+    assert_eq!(pixmap.to_owned().take_demultiplied(), expected.take_demultiplied());
+}
+
+#[test]
+fn a8() {
+    for hq in [false, true] {
+        let paint = gradient_paint(hq);
+        let expected = render(PixelFormat::Rgba8888, &paint);
+        let pixmap = render(PixelFormat::A8, &paint);
+        assert_eq!(max_diff(&pixmap, &expected, |c| [0, 0, 0, c.alpha()]), 0);
+    }
+}
+
+#[test]
+fn rgb565() {
+    for hq in [false, true] {
+        let paint = gradient_paint(hq);
+        let mut expected = Pixmap::new(100, 100).unwrap();
+        expected.fill(Color::BLACK);
+        draw(&mut expected.as_mut(), &paint);
+
+        let pixmap = render(PixelFormat::Rgb565, &paint);
+        // 5 bits per channel + rounding.
+        assert!(max_diff(&pixmap, &expected, rgba) <= 8);
+    }
+}
+
+#[test]
+fn gray8() {
+    for hq in [false, true] {
+        let paint = gradient_paint(hq);
+        let mut expected = Pixmap::new(100, 100).unwrap();
+        expected.fill(Color::BLACK);
+        draw(&mut expected.as_mut(), &paint);
+
+        let pixmap = render(PixelFormat::Gray8, &paint);
+        let luma = |c: PremultipliedColorU8| {
+            let v = (c.red() as f32 * 0.2126 + c.green() as f32 * 0.7152 + c.blue() as f32 * 0.0722 + 0.5) as u8;
+            [v, v, v, c.alpha()]
+        };
+        assert!(max_diff(&pixmap, &expected, luma) <= 2);
+    }
+}
+
+#[test]
+fn rgba16() {
+    let paint = gradient_paint(true);
+    let expected = render(PixelFormat::Rgba8888, &paint);
+    let pixmap = render(PixelFormat::Rgba16161616, &paint);
+    assert!(max_diff(&pixmap, &expected, rgba) <= 1);
+
+    // Must be more precise than RGBA8888.
+    let data = pixmap.data();
+    assert!(data.chunks(2).any(|v| u16::from_ne_bytes([v[0], v[1]]) % 257 != 0));
+}
+
+#[test]
+fn rgba_f16() {
+    let paint = gradient_paint(true);
+    let expected = render(PixelFormat::Rgba8888, &paint);
+    let pixmap = render(PixelFormat::RgbaF16, &paint);
+    assert!(max_diff(&pixmap, &expected, rgba) <= 1);
+}
+
+#[test]
+fn fill_and_pixel() {
+    let color = Color::from_rgba8(255, 0, 255, 255);
+    for format in FORMATS {
+        let mut pixmap = Pixmap::new_with_format(7, 3, *format).unwrap();
+        assert_eq!(pixmap.data().len(), 7 * 3 * format.bytes_per_pixel());
+
+        pixmap.fill(color);
+        let expected = match format {
+            PixelFormat::A8 => PremultipliedColorU8::from_rgba(0, 0, 0, 255).unwrap(),
+            PixelFormat::Gray8 => PremultipliedColorU8::from_rgba(73, 73, 73, 255).unwrap(),
+            _ => PremultipliedColorU8::from_rgba(255, 0, 255, 255).unwrap(),
+        };
+
+        assert_eq!(pixmap.pixel(6, 2), Some(expected));
+        assert_eq!(pixmap.pixel(7, 2), None);
+    }
+}
+
+#[test]
+#[should_panic(expected = "only Rgba8888 pixmaps can be accessed as pixels")]
+fn pixels_of_other_formats() {
+    let pixmap = Pixmap::new_with_format(10, 10, PixelFormat::Bgra8888).unwrap();
+    let _ = pixmap.pixels();
+}
+
+#[test]
+#[should_panic(expected = "only Rgba8888 pixmaps can be accessed as pixels")]
+fn pixels_mut_of_other_formats() {
+    let mut pixmap = Pixmap::new_with_format(10, 10, PixelFormat::Bgra8888).unwrap();
+    let _ = pixmap.pixels_mut();
+}
+
+#[test]
+fn clone_rect() {
+    let pixmap = render(PixelFormat::Rgb565, &gradient_paint(false));
+    let part = pixmap.clone_rect(IntRect::from_xywh(10, 15, 30, 40).unwrap()).unwrap();
+    assert_eq!(part.format(), PixelFormat::Rgb565);
+    assert_eq!(part.pixel(5, 5), pixmap.pixel(15, 20));
+}
+
+#[test]
+fn draw_pixmap_from_bgra() {
+    let rgba_image = render(PixelFormat::Rgba8888, &gradient_paint(false));
+    let bgra_image = render(PixelFormat::Bgra8888, &gradient_paint(false));
+
+    let mut paint = PixmapPaint::default();
+    paint.quality = FilterQuality::Bicubic;
+    let ts = Transform::from_row(1.2, 0.5, 0.5, 1.2, 0.0, 0.0);
+
+    let mut expected = Pixmap::new(200, 200).unwrap();
+    expected.draw_pixmap(5, 10, rgba_image.as_ref(), &paint, ts, Clip::None);
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.draw_pixmap(5, 10, bgra_image.as_ref(), &paint, ts, Clip::None);
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn apply_mask() {
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(
+        &PathBuilder::from_rect(Rect::from_xywh(20.0, 20.0, 60.0, 60.0).unwrap()),
+        FillRule::Winding,
+        true,
+        Transform::identity(),
+    );
+
+    let mut expected = render(PixelFormat::Rgba8888, &gradient_paint(false));
+    expected.apply_mask(&mask);
+
+    let mut pixmap = render(PixelFormat::Bgra8888, &gradient_paint(false));
+    pixmap.apply_mask(&mask);
+
+    assert_eq!(pixmap.take_demultiplied(), expected.take_demultiplied());
+}
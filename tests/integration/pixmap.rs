@@ -13,7 +13,7 @@ fn clone_rect_1() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     let part = pixmap.as_ref().clone_rect(IntRect::from_xywh(10, 15, 80, 90).unwrap()).unwrap();
@@ -35,7 +35,7 @@ fn clone_rect_2() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     let part = pixmap.as_ref().clone_rect(IntRect::from_xywh(130, 120, 80, 90).unwrap()).unwrap();
@@ -57,7 +57,7 @@ fn clone_rect_out_of_bound() {
         &paint,
         FillRule::Winding,
         Transform::identity(),
-        None,
+        Clip::None,
     );
 
     assert!(pixmap.as_ref().clone_rect(IntRect::from_xywh(250, 15, 80, 90).unwrap()).is_none());
@@ -87,7 +87,7 @@ fn draw_pixmap() {
         let rect = Rect::from_xywh(0.0, 50.0, 100.0, 50.0).unwrap();
 
         let mut pixmap = Pixmap::new(100, 100).unwrap();
-        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        pixmap.fill_rect(rect, &paint, Transform::identity(), Clip::None);
         pixmap
     };
 
@@ -95,7 +95,7 @@ fn draw_pixmap() {
     paint.quality = FilterQuality::Bicubic;
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.draw_pixmap(20, 20, sub_pixmap.as_ref(), &paint, Transform::identity(), None);
+    pixmap.draw_pixmap(20, 20, sub_pixmap.as_ref(), &paint, Transform::identity(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/canvas/draw-pixmap.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -116,7 +116,7 @@ fn draw_pixmap_ts() {
         let path = pb.finish().unwrap();
 
         let mut pixmap = Pixmap::new(100, 100).unwrap();
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
         pixmap
     };
 
@@ -129,7 +129,7 @@ fn draw_pixmap_ts() {
         triangle.as_ref(),
         &paint,
         Transform::from_row(1.2, 0.5, 0.5, 1.2, 0.0, 0.0),
-        None,
+        Clip::None,
     );
 
     let expected = Pixmap::load_png("tests/images/canvas/draw-pixmap-ts.png").unwrap();
@@ -151,7 +151,7 @@ fn draw_pixmap_opacity() {
         let path = pb.finish().unwrap();
 
         let mut pixmap = Pixmap::new(100, 100).unwrap();
-        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
         pixmap
     };
 
@@ -165,9 +165,112 @@ fn draw_pixmap_opacity() {
         triangle.as_ref(),
         &paint,
         Transform::from_row(1.2, 0.5, 0.5, 1.2, 0.0, 0.0),
-        None,
+        Clip::None,
     );
 
     let expected = Pixmap::load_png("tests/images/canvas/draw-pixmap-opacity.png").unwrap();
     assert_eq!(pixmap, expected);
 }
+
+fn resize_source() -> Pixmap {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    pixmap.fill_path(
+        &PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap(),
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        Clip::None,
+    );
+
+    pixmap
+}
+
+#[test]
+fn resize_lanczos3_down() {
+    let pixmap = resize_source();
+    let resized = pixmap.resize(IntSize::from_wh(37, 45).unwrap(), ResizeFilter::Lanczos3).unwrap();
+    let expected = Pixmap::load_png("tests/images/pixmap/resize-lanczos3-down.png").unwrap();
+    assert_eq!(resized, expected);
+}
+
+#[test]
+fn resize_mitchell_up() {
+    let pixmap = resize_source().clone_rect(IntRect::from_xywh(0, 0, 50, 50).unwrap()).unwrap();
+    let resized = pixmap.resize(IntSize::from_wh(150, 120).unwrap(), ResizeFilter::Mitchell).unwrap();
+    let expected = Pixmap::load_png("tests/images/pixmap/resize-mitchell-up.png").unwrap();
+    assert_eq!(resized, expected);
+}
+
+#[test]
+fn resize_same_size() {
+    let pixmap = resize_source();
+    let resized = pixmap.resize(IntSize::from_wh(100, 100).unwrap(), ResizeFilter::CatmullRom).unwrap();
+    assert_eq!(resized, pixmap);
+}
+
+#[test]
+fn resize_box_average() {
+    // 2x2 checkerboard of opaque white and transparent pixels.
+    let mut pixmap = Pixmap::new(2, 2).unwrap();
+    pixmap.pixels_mut()[0] = PremultipliedColorU8::from_rgba(255, 255, 255, 255).unwrap();
+    pixmap.pixels_mut()[3] = PremultipliedColorU8::from_rgba(255, 255, 255, 255).unwrap();
+
+    let resized = pixmap.resize(IntSize::from_wh(1, 1).unwrap(), ResizeFilter::Box).unwrap();
+    assert_eq!(resized.pixel(0, 0).unwrap(), PremultipliedColorU8::from_rgba(128, 128, 128, 128).unwrap());
+}
+
+#[test]
+fn resize_keeps_premultiplied() {
+    // A sharp edge between an opaque and a transparent region produces ringing,
+    // which must not result in an invalid premultiplied color.
+    let mut pixmap = Pixmap::new(8, 1).unwrap();
+    for p in &mut pixmap.pixels_mut()[..4] {
+        *p = PremultipliedColorU8::from_rgba(255, 0, 0, 255).unwrap();
+    }
+
+    for filter in [ResizeFilter::Lanczos3, ResizeFilter::CatmullRom] {
+        let resized = pixmap.resize(IntSize::from_wh(21, 1).unwrap(), filter).unwrap();
+        for p in resized.pixels() {
+            assert!(p.red() <= p.alpha());
+            assert_eq!(p.green(), 0);
+        }
+    }
+}
+
+#[test]
+fn from_rgba8_unpremultiplied() {
+    let data = vec![
+        255, 0, 0, 255,
+        255, 255, 0, 128,
+        10, 20, 30, 0,
+    ];
+    let pixmap = Pixmap::from_rgba8_unpremultiplied(data, IntSize::from_wh(3, 1).unwrap()).unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), PremultipliedColorU8::from_rgba(255, 0, 0, 255).unwrap());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), PremultipliedColorU8::from_rgba(128, 128, 0, 128).unwrap());
+    assert_eq!(pixmap.pixel(2, 0).unwrap(), PremultipliedColorU8::from_rgba(0, 0, 0, 0).unwrap());
+
+    assert_eq!(pixmap.take_demultiplied(), vec![255, 0, 0, 255, 255, 255, 0, 128, 0, 0, 0, 0]);
+}
+
+#[test]
+fn from_rgba8_unpremultiplied_invalid_size() {
+    let size = IntSize::from_wh(3, 1).unwrap();
+    assert!(Pixmap::from_rgba8_unpremultiplied(vec![0; 8], size).is_none());
+}
+
+#[test]
+fn swizzle_bgra_round_trip() {
+    let mut pixmap = Pixmap::new(10, 10).unwrap();
+    pixmap.fill(Color::from_rgba8(10, 20, 30, 255));
+    let mut data = pixmap.take();
+    swizzle_rgba_bgra(&mut data);
+    assert_eq!(&data[..4], &[30, 20, 10, 255]);
+
+    let bgra = Pixmap::from_vec_with_format(data, IntSize::from_wh(10, 10).unwrap(), PixelFormat::Bgra8888).unwrap();
+    assert_eq!(bgra.pixel(9, 9).unwrap(), PremultipliedColorU8::from_rgba(10, 20, 30, 255).unwrap());
+}
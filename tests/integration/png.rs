@@ -29,4 +29,116 @@ fn decode_rgba() {
     assert_eq!(pixmap.pixel(50, 50).unwrap(), ColorU8::from_rgba(33, 190, 47, 252).premultiply());
 }
 
-// TODO: test encoding, somehow
+#[test]
+fn decode_indexed() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/indexed.png").unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(255, 0, 0, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(0, 255, 0, 255).premultiply());
+    assert_eq!(pixmap.pixel(2, 0).unwrap(), ColorU8::from_rgba(0, 0, 255, 128).premultiply());
+    assert_eq!(pixmap.pixel(3, 0).unwrap(), ColorU8::from_rgba(0, 0, 0, 0).premultiply());
+    assert_eq!(pixmap.pixel(0, 1).unwrap(), ColorU8::from_rgba(0, 0, 0, 0).premultiply());
+    assert_eq!(pixmap.pixel(3, 1).unwrap(), ColorU8::from_rgba(255, 0, 0, 255).premultiply());
+}
+
+#[test]
+fn decode_rgba16() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgba16.png").unwrap();
+    assert_eq!(pixmap.format(), PixelFormat::Rgba8888);
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(255, 128, 0, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(255, 255, 255, 128).premultiply());
+}
+
+#[test]
+fn decode_rgba16_keep_16_bit() {
+    let data = std::fs::read("tests/images/pngs/rgba16.png").unwrap();
+    let mut options = PngDecodeOptions::default();
+    options.keep_16_bit = true;
+    let pixmap = Pixmap::decode_png_with(&data, &options).unwrap();
+    assert_eq!(pixmap.format(), PixelFormat::Rgba16161616);
+
+    let values: Vec<u16> = pixmap.data().chunks(2).map(|v| u16::from_ne_bytes([v[0], v[1]])).collect();
+    assert_eq!(values, &[65535, 32768, 0, 65535, 32768, 32768, 32768, 32768]);
+}
+
+#[test]
+fn decode_gray16() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/gray16.png").unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(18, 18, 18, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(254, 254, 254, 255).premultiply());
+}
+
+fn load_color_managed(path: &str) -> Pixmap {
+    let data = std::fs::read(path).unwrap();
+    let mut options = PngDecodeOptions::default();
+    options.color_management = true;
+    Pixmap::decode_png_with(&data, &options).unwrap()
+}
+
+#[test]
+fn decode_gamma() {
+    let pixmap = load_color_managed("tests/images/pngs/gamma-linear.png");
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(188, 188, 188, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(255, 0, 137, 255).premultiply());
+
+    // Colors are left as is by default.
+    let pixmap = Pixmap::load_png("tests/images/pngs/gamma-linear.png").unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(128, 128, 128, 255).premultiply());
+}
+
+#[test]
+fn decode_srgb_overrides_gamma() {
+    let pixmap = load_color_managed("tests/images/pngs/gamma-srgb.png");
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(128, 128, 128, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(255, 0, 64, 255).premultiply());
+}
+
+#[test]
+fn decode_cicp_overrides_gamma() {
+    let pixmap = load_color_managed("tests/images/pngs/cicp-linear.png");
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(188, 188, 188, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(255, 0, 137, 255).premultiply());
+}
+
+#[test]
+fn encode_round_trip() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgba.png").unwrap();
+    let data = pixmap.encode_png().unwrap();
+    assert_eq!(Pixmap::decode_png(&data).unwrap(), pixmap);
+}
+
+#[test]
+fn encode_with_options() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgba.png").unwrap();
+
+    let mut options = PngEncodeOptions::default();
+    options.compression = PngCompression::None;
+    options.filter = Some(PngFilter::Paeth);
+    options.dpi = Some(96.0);
+    options.srgb = true;
+    options.text = vec![
+        ("Title".to_string(), "Test".to_string()),
+        ("Author".to_string(), "Юникод".to_string()),
+    ];
+    let data = pixmap.encode_png_with(&options).unwrap();
+    assert!(data.len() > pixmap.encode_png().unwrap().len());
+    assert_eq!(Pixmap::decode_png(&data).unwrap(), pixmap);
+
+    let decoder = png::Decoder::new(std::io::Cursor::new(&data));
+    let reader = decoder.read_info().unwrap();
+    let info = reader.info();
+    let dims = info.pixel_dims.unwrap();
+    assert_eq!((dims.xppu, dims.yppu, dims.unit), (3780, 3780, png::Unit::Meter));
+    assert!(info.srgb.is_some());
+    assert_eq!(info.uncompressed_latin1_text[0].keyword, "Title");
+    assert_eq!(info.uncompressed_latin1_text[0].text, "Test");
+    assert_eq!(info.utf8_text[0].keyword, "Author");
+    assert_eq!(info.utf8_text[0].get_text().unwrap(), "Юникод");
+}
+
+#[test]
+fn encode_invalid_dpi() {
+    let pixmap = Pixmap::new(10, 10).unwrap();
+    let mut options = PngEncodeOptions::default();
+    options.dpi = Some(-1.0);
+    assert!(pixmap.encode_png_with(&options).is_err());
+}
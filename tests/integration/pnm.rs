@@ -0,0 +1,77 @@
+use tiny_skia::*;
+
+fn circle_mask() -> Mask {
+    let mut mask = Mask::new(20, 20).unwrap();
+    mask.fill_path(&PathBuilder::from_circle(10.0, 10.0, 8.0).unwrap(), FillRule::Winding, true, Transform::identity());
+    mask
+}
+
+#[test]
+fn decode_ppm() {
+    let data = b"P6\n# comment\n2 1\n255\n\xff\x00\x00\x00\x80\xff";
+    let pixmap = Pixmap::decode_pnm(data).unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(255, 0, 0, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(0, 128, 255, 255).premultiply());
+}
+
+#[test]
+fn decode_pgm_16_bit() {
+    let data = b"P5 2 1 1000 \x03\xe8\x01\xf4";
+    let pixmap = Pixmap::decode_pnm(data).unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(255, 255, 255, 255).premultiply());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), ColorU8::from_rgba(128, 128, 128, 255).premultiply());
+}
+
+#[test]
+fn decode_pam_gray_alpha() {
+    let data = b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 15\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n\x0f\x05";
+    let pixmap = Pixmap::decode_pnm(data).unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), ColorU8::from_rgba(255, 255, 255, 85).premultiply());
+}
+
+#[test]
+fn decode_invalid() {
+    assert!(Pixmap::decode_pnm(b"P3\n1 1\n255\n1 2 3").is_none());
+    assert!(Pixmap::decode_pnm(b"P6\n2 1\n255\n\xff\x00\x00").is_none());
+    assert!(Pixmap::decode_pnm(b"P6\n1 1\n0\n\xff\x00\x00").is_none());
+    assert!(Pixmap::decode_pnm(b"P6\n100000 100000\n255\n\xff\x00\x00").is_none());
+    assert!(Pixmap::decode_pnm(b"P7\nWIDTH 1\nHEIGHT 1\nMAXVAL 255\nENDHDR\n\xff").is_none());
+}
+
+#[test]
+fn ppm_round_trip() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgb.png").unwrap();
+    let data = pixmap.encode_ppm();
+    assert!(data.starts_with(b"P6\n"));
+    assert_eq!(Pixmap::decode_pnm(&data).unwrap(), pixmap);
+}
+
+#[test]
+fn ppm_ignores_alpha() {
+    let mut pixmap = Pixmap::new(1, 1).unwrap();
+    pixmap.fill(Color::from_rgba8(255, 0, 0, 128));
+    let data = pixmap.encode_ppm();
+    assert_eq!(&data[data.len() - 3..], &[255, 0, 0]);
+}
+
+#[test]
+fn pam_round_trip() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgba.png").unwrap();
+    let data = pixmap.encode_pam();
+    assert!(data.starts_with(b"P7\n"));
+    assert_eq!(Pixmap::decode_pnm(&data).unwrap(), pixmap);
+}
+
+#[test]
+fn pgm_mask_round_trip() {
+    let mask = circle_mask();
+    let data = mask.encode_pgm();
+    assert!(data.starts_with(b"P5\n20 20\n255\n"));
+    assert_eq!(Mask::decode_pnm(&data).unwrap(), mask);
+}
+
+#[test]
+fn mask_from_color_image() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgb.png").unwrap();
+    assert!(Mask::decode_pnm(&pixmap.encode_ppm()).is_none());
+}
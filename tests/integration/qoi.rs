@@ -0,0 +1,78 @@
+use tiny_skia::*;
+
+#[test]
+fn encode() {
+    let mut pixmap = Pixmap::new(2, 1).unwrap();
+    pixmap.fill(Color::from_rgba8(255, 0, 0, 255));
+
+    let data = pixmap.encode_qoi();
+    assert_eq!(&data[..14], b"qoif\0\0\0\x02\0\0\0\x01\x04\0");
+    // A diff from the initial pixel and a single pixel run.
+    assert_eq!(&data[14..16], &[0x5a, 0xc0]);
+    assert_eq!(&data[16..], &[0, 0, 0, 0, 0, 0, 0, 1]);
+}
+
+#[test]
+fn round_trip() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgba.png").unwrap();
+    let data = pixmap.encode_qoi();
+    assert!(data.len() < pixmap.data().len());
+    assert_eq!(Pixmap::decode_qoi(&data).unwrap(), pixmap);
+}
+
+#[test]
+fn round_trip_noise() {
+    // Covers all the operations.
+    let mut pixmap = Pixmap::new(64, 64).unwrap();
+    let mut seed = 1u32;
+    for p in pixmap.pixels_mut() {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        let [r, g, b, a] = seed.to_le_bytes();
+        let a = if a < 128 { 255 } else { a };
+        let v = |c: u8| if seed < u32::MAX / 3 { c % 4 } else { c };
+        *p = ColorU8::from_rgba(v(r), v(g), v(b), a).premultiply();
+    }
+
+    let data = pixmap.encode_qoi();
+    assert_eq!(Pixmap::decode_qoi(&data).unwrap(), pixmap);
+}
+
+#[test]
+fn mask_round_trip() {
+    let mut mask = Mask::new(20, 20).unwrap();
+    mask.fill_path(&PathBuilder::from_circle(10.0, 10.0, 8.0).unwrap(), FillRule::Winding, true, Transform::identity());
+
+    let data = mask.encode_qoi();
+    assert_eq!(data[12], 3);
+    assert_eq!(Mask::decode_qoi(&data).unwrap(), mask);
+}
+
+#[test]
+fn mask_from_color_image() {
+    let pixmap = Pixmap::load_png("tests/images/pngs/rgba.png").unwrap();
+    assert!(Mask::decode_qoi(&pixmap.encode_qoi()).is_none());
+}
+
+#[test]
+fn decode_invalid() {
+    let mut pixmap = Pixmap::new(10, 10).unwrap();
+    pixmap.fill(Color::from_rgba8(10, 20, 30, 40));
+    let data = pixmap.encode_qoi();
+
+    assert!(Pixmap::decode_qoi(&[]).is_none());
+    assert!(Pixmap::decode_qoi(&data[1..]).is_none());
+
+    // Too big for the data.
+    let mut big = data.clone();
+    big[4..8].copy_from_slice(&100000u32.to_be_bytes());
+    assert!(Pixmap::decode_qoi(&big).is_none());
+
+    // The pixels count overflows on 32-bit targets.
+    let mut huge = data.clone();
+    huge[4..12].copy_from_slice(&[0xFF; 8]);
+    assert!(Pixmap::decode_qoi(&huge).is_none());
+
+    let mut zero = data.clone();
+    zero[4..8].copy_from_slice(&0u32.to_be_bytes());
+    assert!(Pixmap::decode_qoi(&zero).is_none());
+}
@@ -39,7 +39,7 @@ fn infinite_dash() {
     stroke.dash = StrokeDash::new(vec![0.2, 0.2], 0.0);
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None); // Doesn't draw anything.
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None); // Doesn't draw anything.
 
     assert!(true);
 }
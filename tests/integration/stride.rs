@@ -0,0 +1,159 @@
+use tiny_skia::*;
+
+const PADDING: u8 = 0xAB;
+
+fn draw(pixmap: &mut PixmapMut) {
+    let mut paint = Paint::default();
+    paint.anti_alias = true;
+    paint.shader = LinearGradient::new(
+        Point::from_xy(10.0, 10.0),
+        Point::from_xy(90.0, 90.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let mut background = Paint::default();
+    background.set_color_rgba8(0, 0, 255, 100);
+
+    pixmap.fill(Color::from_rgba8(255, 255, 255, 255));
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 60.0, 60.0).unwrap(), &background, Transform::identity(), Clip::None);
+
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), Clip::None);
+    paint.force_hq_pipeline = true;
+    let stroke = Stroke { width: 3.0, ..Stroke::default() };
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Clip::None);
+}
+
+fn check_padding(data: &[u8], width: usize, height: usize, row_bytes: usize, bpp: usize) {
+    for y in 0..height - 1 {
+        assert!(data[y * row_bytes + width * bpp..(y + 1) * row_bytes].iter().all(|v| *v == PADDING));
+    }
+}
+
+#[test]
+fn draw_into_padded() {
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    draw(&mut expected.as_mut());
+
+    let row_bytes = 100 * 4 + 24;
+    let mut data = vec![PADDING; row_bytes * 100];
+    let mut pixmap = PixmapMut::from_bytes_with_stride(&mut data, 100, 100, row_bytes).unwrap();
+    assert_eq!(pixmap.row_bytes(), row_bytes);
+    draw(&mut pixmap);
+
+    assert_eq!(pixmap.to_owned(), expected);
+    check_padding(&data, 100, 100, row_bytes, 4);
+}
+
+#[test]
+fn draw_into_padded_bgra() {
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    draw(&mut expected.as_mut());
+
+    let row_bytes = 128 * 4;
+    let mut data = vec![PADDING; row_bytes * 100];
+    let mut pixmap = PixmapMut::from_bytes_with_stride_and_format(
+        &mut data, 100, 100, row_bytes, PixelFormat::Bgra8888,
+    ).unwrap();
+    draw(&mut pixmap);
+
+    assert_eq!(pixmap.to_owned().take_demultiplied(), expected.take_demultiplied());
+    check_padding(&data, 100, 100, row_bytes, 4);
+}
+
+#[test]
+fn draw_padded_pattern() {
+    let mut image = Pixmap::new(100, 100).unwrap();
+    draw(&mut image.as_mut());
+
+    // Copy the image into a padded buffer.
+    let row_bytes = 100 * 4 + 12;
+    let mut data = vec![PADDING; row_bytes * 100];
+    for (y, row) in image.data().chunks(400).enumerate() {
+        data[y * row_bytes..y * row_bytes + 400].copy_from_slice(row);
+    }
+    let padded = PixmapRef::from_bytes_with_stride(&data, 100, 100, row_bytes).unwrap();
+    assert_eq!(padded.pixel(99, 50), image.pixel(99, 50));
+    assert_eq!(padded.to_owned(), image);
+
+    for quality in [FilterQuality::Nearest, FilterQuality::Bicubic, FilterQuality::Medium] {
+        let mut paint = PixmapPaint::default();
+        paint.quality = quality;
+        let ts = Transform::from_row(0.7, 0.2, -0.2, 0.7, 20.0, 10.0);
+
+        let mut expected = Pixmap::new(120, 120).unwrap();
+        expected.draw_pixmap(0, 0, image.as_ref(), &paint, ts, Clip::None);
+
+        let mut pixmap = Pixmap::new(120, 120).unwrap();
+        pixmap.draw_pixmap(0, 0, padded, &paint, ts, Clip::None);
+
+        assert_eq!(pixmap, expected);
+    }
+
+    assert_eq!(padded.resize(IntSize::from_wh(40, 40).unwrap(), ResizeFilter::Mitchell),
+               image.resize(IntSize::from_wh(40, 40).unwrap(), ResizeFilter::Mitchell));
+
+    let rect = IntRect::from_xywh(30, 40, 50, 20).unwrap();
+    assert_eq!(padded.clone_rect(rect), image.clone_rect(rect));
+
+    assert_eq!(Mask::from_pixmap(padded, MaskType::Luminance), Mask::from_pixmap(image.as_ref(), MaskType::Luminance));
+}
+
+#[test]
+fn apply_mask_to_padded() {
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(
+        &PathBuilder::from_circle(50.0, 50.0, 30.0).unwrap(),
+        FillRule::Winding,
+        true,
+        Transform::identity(),
+    );
+
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    draw(&mut expected.as_mut());
+    expected.apply_mask(&mask);
+
+    let row_bytes = 100 * 4 + 8;
+    let mut data = vec![PADDING; row_bytes * 100];
+    let mut pixmap = PixmapMut::from_bytes_with_stride(&mut data, 100, 100, row_bytes).unwrap();
+    draw(&mut pixmap);
+    pixmap.apply_mask(&mask);
+
+    assert_eq!(pixmap.to_owned(), expected);
+    check_padding(&data, 100, 100, row_bytes, 4);
+}
+
+#[test]
+fn invalid_stride() {
+    let mut data = vec![0; 1000];
+    // Too small.
+    assert!(PixmapRef::from_bytes_with_stride(&data, 10, 10, 36).is_none());
+    // Not a multiple of 4.
+    assert!(PixmapRef::from_bytes_with_stride(&data, 10, 10, 42).is_none());
+    // Not enough data.
+    assert!(PixmapMut::from_bytes_with_stride(&mut data, 10, 10, 120).is_none());
+    // The last row doesn't have to be padded.
+    assert!(PixmapMut::from_bytes_with_stride(&mut data, 10, 9, 120).is_some());
+    assert!(PixmapMut::from_bytes_with_stride(&mut data[..1000 - 4], 10, 9, 120).is_none());
+    assert!(PixmapRef::from_bytes_with_stride_and_format(&data, 10, 10, 22, PixelFormat::Rgb565).is_some());
+    assert!(PixmapRef::from_bytes_with_stride_and_format(&data, 10, 10, 21, PixelFormat::Rgb565).is_none());
+}
+
+#[test]
+fn mask_from_bytes_with_stride() {
+    let data = [
+        1, 2, 3, 0, 0,
+        4, 5, 6, 0, 0,
+        7, 8, 9,
+    ];
+    let mask = Mask::from_bytes_with_stride(&data, 3, 3, 5).unwrap();
+    assert_eq!(mask.data(), &[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+    assert!(Mask::from_bytes_with_stride(&data, 3, 3, 2).is_none());
+    assert!(Mask::from_bytes_with_stride(&data, 3, 4, 5).is_none());
+}
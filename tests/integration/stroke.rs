@@ -20,7 +20,7 @@ fn round_caps_and_large_scale() {
     let transform = Transform::from_scale(16.0, 16.0);
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+    pixmap.stroke_path(&path, &paint, &stroke, transform, Clip::None);
 
     let expected = Pixmap::load_png("tests/images/stroke/round-caps-and-large-scale.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -37,7 +37,7 @@ fn circle() {
     stroke.width = 2.0;
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/stroke/circle.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -59,7 +59,7 @@ fn zero_len_subpath_butt_cap() {
     stroke.line_cap = LineCap::Butt;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/stroke/zero-len-subpath-butt-cap.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -81,7 +81,7 @@ fn zero_len_subpath_round_cap() {
     stroke.line_cap = LineCap::Round;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/stroke/zero-len-subpath-round-cap.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -103,7 +103,7 @@ fn zero_len_subpath_square_cap() {
     stroke.line_cap = LineCap::Square;
 
     let mut pixmap = Pixmap::new(100, 100).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/stroke/zero-len-subpath-square-cap.png").unwrap();
     assert_eq!(pixmap, expected);
@@ -127,8 +127,297 @@ fn round_cap_join() {
     stroke.line_join = LineJoin::Round;
 
     let mut pixmap = Pixmap::new(200, 200).unwrap();
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), None);
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
 
     let expected = Pixmap::load_png("tests/images/stroke/round-cap-join.png").unwrap();
     assert_eq!(pixmap, expected);
 }
+
+#[test]
+fn variable_width() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(20.0, 150.0);
+    pb.cubic_to(40.0, 20.0, 120.0, 20.0, 120.0, 100.0);
+    pb.line_to(180.0, 40.0);
+    let path = pb.finish().unwrap();
+
+    let mut stroke = Stroke::default();
+    stroke.line_cap = LineCap::Round;
+    stroke.line_join = LineJoin::Round;
+
+    let widths = [2.0, 30.0, 10.0];
+    let path = path
+        .stroke_variable(&stroke, WidthProfile::Vertices(&widths), 1.0)
+        .unwrap();
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), Clip::None);
+
+    let expected = Pixmap::load_png("tests/images/stroke/variable-width.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn variable_width_along_length() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let path = PathBuilder::from_circle(100.0, 100.0, 60.0).unwrap();
+
+    let mut stroke = Stroke::default();
+    stroke.line_join = LineJoin::Miter;
+
+    // Tapers towards the contour start and end.
+    let profile = |t: f32| 2.0 + 28.0 * (t * core::f32::consts::PI).sin();
+    let path = path
+        .stroke_variable(&stroke, WidthProfile::Length(&profile), 1.0)
+        .unwrap();
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), Clip::None);
+
+    let expected = Pixmap::load_png("tests/images/stroke/variable-width-along-length.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn variable_width_same_as_stroke() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 10.0);
+    pb.line_to(20.0, 50.0);
+    pb.line_to(30.0, 10.0);
+    pb.close();
+    pb.move_to(50.0, 50.0);
+    pb.line_to(90.0, 20.0);
+    let path = pb.finish().unwrap();
+
+    let mut stroke = Stroke::default();
+    stroke.width = 4.0;
+    stroke.line_cap = LineCap::Square;
+
+    let widths = [4.0; 5];
+    let variable = path
+        .stroke_variable(&stroke, WidthProfile::Vertices(&widths), 1.0)
+        .unwrap();
+    assert_eq!(variable, path.stroke(&stroke, 1.0).unwrap());
+}
+
+#[test]
+fn variable_width_curves_same_as_stroke() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(20.0, 150.0);
+    pb.cubic_to(40.0, 20.0, 120.0, 20.0, 120.0, 100.0);
+    pb.quad_to(150.0, 150.0, 180.0, 40.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    let mut stroke = Stroke::default();
+    stroke.width = 12.0;
+    stroke.line_join = LineJoin::Round;
+
+    let widths = [12.0; 3];
+    let variable = path
+        .stroke_variable(&stroke, WidthProfile::Vertices(&widths), 1.0)
+        .unwrap();
+    assert_eq!(variable, path.stroke(&stroke, 1.0).unwrap());
+
+    let variable = path
+        .stroke_variable(&stroke, WidthProfile::Length(&|_| 12.0), 1.0)
+        .unwrap();
+    assert_eq!(variable, path.stroke(&stroke, 1.0).unwrap());
+}
+
+#[test]
+fn variable_width_invalid_profile() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 10.0);
+    pb.line_to(50.0, 50.0);
+    let path = pb.finish().unwrap();
+
+    let stroke = Stroke::default();
+    assert!(path.stroke_variable(&stroke, WidthProfile::Vertices(&[1.0]), 1.0).is_none());
+    assert!(path.stroke_variable(&stroke, WidthProfile::Vertices(&[1.0, -1.0]), 1.0).is_none());
+    assert!(path.stroke_variable(&stroke, WidthProfile::Length(&|_| f32::NAN), 1.0).is_none());
+}
+
+fn stroke_rect_with_alignment(alignment: StrokeAlignment, clockwise: bool) -> Pixmap {
+    let mut pb = PathBuilder::new();
+    if clockwise {
+        pb.push_rect(Rect::from_xywh(50.0, 50.0, 100.0, 100.0).unwrap());
+    } else {
+        pb.move_to(50.0, 50.0);
+        pb.line_to(50.0, 150.0);
+        pb.line_to(150.0, 150.0);
+        pb.line_to(150.0, 50.0);
+        pb.close();
+    }
+    let path = pb.finish().unwrap();
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let stroke = Stroke { width: 10.0, alignment, ..Stroke::default() };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
+    pixmap
+}
+
+#[test]
+fn inside_alignment() {
+    for clockwise in [true, false] {
+        let pixmap = stroke_rect_with_alignment(StrokeAlignment::Inside, clockwise);
+        assert_eq!(pixmap.pixel(45, 45).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(50, 50).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(59, 100).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(60, 100).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(100, 100).unwrap().alpha(), 0);
+    }
+}
+
+#[test]
+fn outside_alignment() {
+    for clockwise in [true, false] {
+        let pixmap = stroke_rect_with_alignment(StrokeAlignment::Outside, clockwise);
+        assert_eq!(pixmap.pixel(39, 39).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(40, 40).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(49, 100).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(50, 100).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(155, 155).unwrap().alpha(), 255);
+    }
+}
+
+#[test]
+fn thin_inside_alignment() {
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 30.0, 30.0).unwrap());
+
+    let paint = Paint { anti_alias: true, ..Paint::default() };
+
+    // Thin strokes are not drawn as centered hairlines.
+    let mut pixmap = Pixmap::new(40, 40).unwrap();
+    let stroke = Stroke { width: 0.5, alignment: StrokeAlignment::Inside, ..Stroke::default() };
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
+    assert_eq!(pixmap.pixel(9, 20).unwrap().alpha(), 0);
+    assert_ne!(pixmap.pixel(10, 20).unwrap().alpha(), 0);
+
+    let stroke = Stroke { width: 0.5, ..Stroke::default() };
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
+    assert_ne!(pixmap.pixel(9, 20).unwrap().alpha(), 0);
+}
+
+#[test]
+fn alignment_with_curves() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let mut pb = PathBuilder::new();
+    pb.push_circle(60.0, 60.0, 40.0);
+    pb.move_to(110.0, 180.0);
+    pb.cubic_to(110.0, 80.0, 190.0, 80.0, 190.0, 180.0);
+    pb.close();
+    // An open contour is always centered.
+    pb.move_to(20.0, 150.0);
+    pb.line_to(80.0, 190.0);
+    let path = pb.finish().unwrap();
+
+    let mut stroke = Stroke::default();
+    stroke.width = 12.0;
+    stroke.line_join = LineJoin::Round;
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    stroke.alignment = StrokeAlignment::Outside;
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
+    paint.set_color_rgba8(220, 140, 75, 180);
+    stroke.alignment = StrokeAlignment::Inside;
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
+
+    let expected = Pixmap::load_png("tests/images/stroke/alignment.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn cap_shapes() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 50.0);
+    pb.line_to(90.0, 50.0);
+    let path = pb.finish().unwrap();
+
+    let shapes = path
+        .cap_shapes(Some(&MarkerShape::Triangle), Some(&MarkerShape::Arrow), 2.0)
+        .unwrap();
+    // The start triangle is flipped and the arrow tip is ahead of the end.
+    assert_eq!(shapes.bounds(), Rect::from_ltrb(5.0, 47.0, 94.0, 53.0).unwrap());
+
+    // Closed contours have no caps.
+    let rect = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 20.0, 20.0).unwrap());
+    assert!(rect.cap_shapes(Some(&MarkerShape::Arrow), None, 2.0).is_none());
+    assert!(path.cap_shapes(Some(&MarkerShape::Arrow), None, 0.0).is_none());
+}
+
+#[test]
+fn markers() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 10.0);
+    pb.line_to(50.0, 10.0);
+    pb.line_to(50.0, 50.0);
+    let path = pb.finish().unwrap();
+
+    let markers = Markers {
+        mid: Some(MarkerShape::Diamond),
+        ..Markers::default()
+    };
+    let shapes = path.markers(&markers, 2.0).unwrap();
+    // A single mid marker, rotated by 45 degrees along the bisector.
+    let bounds = shapes.bounds();
+    assert!((bounds.left() - (50.0 - 3.0 * core::f32::consts::FRAC_1_SQRT_2)).abs() < 0.01);
+    assert!((bounds.width() - bounds.height()).abs() < 0.01);
+
+    let markers = Markers {
+        start: Some(MarkerShape::Path(PathBuilder::from_rect(Rect::from_ltrb(0.0, 0.0, 1.0, 1.0).unwrap()))),
+        end: Some(MarkerShape::Triangle),
+        ..Markers::default()
+    };
+    let shapes = path.markers(&markers, 10.0).unwrap();
+    assert_eq!(shapes.bounds(), Rect::from_ltrb(10.0, 10.0, 65.0, 75.0).unwrap());
+}
+
+#[test]
+fn markers_and_caps() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(20.0, 150.0);
+    pb.line_to(60.0, 60.0);
+    pb.quad_to(100.0, 20.0, 140.0, 60.0);
+    pb.cubic_to(150.0, 100.0, 180.0, 130.0, 180.0, 170.0);
+    pb.move_to(30.0, 180.0);
+    pb.line_to(110.0, 180.0);
+    let path = pb.finish().unwrap();
+
+    let stroke = Stroke { width: 4.0, ..Stroke::default() };
+    let caps = path
+        .cap_shapes(Some(&MarkerShape::Circle), Some(&MarkerShape::Arrow), stroke.width)
+        .unwrap();
+    let markers = Markers {
+        mid: Some(MarkerShape::Diamond),
+        ..Markers::default()
+    };
+    let markers = path.markers(&markers, stroke.width).unwrap();
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::default(), Clip::None);
+    pixmap.fill_path(&caps, &paint, FillRule::Winding, Transform::default(), Clip::None);
+    paint.set_color_rgba8(220, 140, 75, 220);
+    pixmap.fill_path(&markers, &paint, FillRule::Winding, Transform::default(), Clip::None);
+
+    let expected = Pixmap::load_png("tests/images/stroke/markers-and-caps.png").unwrap();
+    assert_eq!(pixmap, expected);
+}